@@ -6,7 +6,11 @@ use serde::Serialize;
 #[cfg(feature = "display")]
 use std::fmt::Display;
 
-use crate::{util::ws, KconfigInput};
+use crate::{
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 
 use super::{parse_expression, parse_if_attribute, Expression};
 
@@ -17,8 +21,7 @@ use super::{parse_expression, parse_if_attribute, Expression};
 /// The default value is only assigned to the config symbol if no other value was set by the user.
 ///
 /// see ["default value"](https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#menu-attributes) for more information.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct DefaultAttribute {
@@ -28,8 +31,11 @@ pub struct DefaultAttribute {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(DefaultAttribute { expression, r#if });
+
 #[cfg(feature = "display")]
 impl Display for DefaultAttribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -62,17 +68,19 @@ impl Display for DefaultAttribute {
 ///             expression: Expression::Term(AndExpression::Term(Term::Atom(
 ///                 Atom::Symbol(Symbol::Constant(ConstantSymbol::Hex("0x1".to_string())))
 ///             ))),
-///             r#if: None
+///             r#if: None,
+///             span: Default::default(),
 ///         }
 ///     ))
 /// )
 /// ```
 pub fn parse_default(input: KconfigInput) -> IResult<KconfigInput, DefaultAttribute> {
     map(
-        (ws(tag("default")), ws(parse_expression), parse_if_attribute),
-        |(_, e, i)| DefaultAttribute {
+        spanned((ws(tag("default")), ws(parse_expression), parse_if_attribute)),
+        |((_, e, i), span)| DefaultAttribute {
             expression: e,
             r#if: i,
+            span,
         },
     )
     .parse(input)
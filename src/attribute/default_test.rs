@@ -18,7 +18,8 @@ fn test_parse_default() {
                 expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                     Symbol::Constant(ConstantSymbol::Hex("0x1".to_string()))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -36,7 +37,8 @@ fn test_parse_default_constant_symbol_with_numbers() {
                 expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                     Symbol::NonConstant("7.10.d".to_string())
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -71,12 +73,14 @@ fn test_parse_default_ambigus() {
                                     "CC".to_string()
                                 )])
                             )
-                        })
+                        }),
+                        span: Default::default()
                     })
                 )))),
                 r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                     Atom::Symbol(Symbol::NonConstant("CC_IS_GCC".to_string()))
-                ))))
+                )))),
+                span: Default::default()
             }
         ))
     )
@@ -89,7 +93,8 @@ fn test_default_attribute_to_string() {
             expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                 Symbol::Constant(ConstantSymbol::Integer(64))
             )))),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         }
         .to_string(),
         "64"
@@ -102,7 +107,8 @@ fn test_default_attribute_to_string() {
             )))),
             r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                 Atom::Symbol(Symbol::NonConstant("NET".to_string()))
-            ))))
+            )))),
+            span: Default::default()
         }
         .to_string(),
         "64 if NET"
@@ -121,7 +127,8 @@ fn test_default_attribute_number() {
                 expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                     Symbol::Constant(ConstantSymbol::Integer(0))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -139,7 +146,8 @@ fn test_default_attribute_number_2() {
                 expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                     Symbol::Constant(ConstantSymbol::String("845".to_string()))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -178,7 +186,8 @@ fn test_default_attribute_dt_node() {
                             Parameter {
                                 tokens: vec!(ExpressionToken::Literal("d-cache-line-size".to_string()))
                             }
-                        ]
+                        ],
+                        span: Default::default(),
                     }
                     ))))),
                 r#if: Some(
@@ -192,10 +201,12 @@ fn test_default_attribute_dt_node() {
                                 Parameter {
                                     tokens: vec!(ExpressionToken::Literal("d-cache-line-size".to_string()))
                                 }
-                            ]
+                            ],
+                            span: Default::default(),
                         }
                     )))))
-                )
+                ),
+                span: Default::default(),
             }
         ))
     )
@@ -10,6 +10,7 @@ use nom::{
 use super::expression::parse_expression;
 use crate::{
     attribute::{expression::parse_if_expression, Expression},
+    span::{ignore_span, spanned, Span},
     util::wsi,
     KconfigInput,
 };
@@ -19,8 +20,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 /// While normal dependencies reduce the upper limit of a symbol, reverse dependencies can be used to force a lower limit of another symbol. The value of the current menu symbol is used as the minimal value [symbol](crate::Symbol) can be set to. If [symbol](crate::Symbol) is selected multiple times, the limit is set to the largest selection. Reverse dependencies can only be used with boolean or tristate symbols.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct DependsOn {
@@ -30,8 +30,11 @@ pub struct DependsOn {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(DependsOn { expression, r#if });
+
 /// Parses a `depends on` attribute.
 /// If multiple dependencies are defined, they are connected with '&&'.
 /// Dependencies are applied to all other options within this menu entry (which also accept an "if" expression).
@@ -42,7 +45,7 @@ pub struct DependsOn {
 /// use nom_kconfig::{
 ///     assert_parsing_eq,
 ///     attribute::{
-///         parse_depends_on,
+///         parse_depends_on, depends_on::DependsOn,
 ///         AndExpression, Atom, Expression, OrExpression, Term,
 ///     },
 ///     symbol::Symbol,
@@ -56,21 +59,22 @@ pub struct DependsOn {
 ///         "",
 ///         DependsOn { expression: Expression::Term(AndExpression::Term(
 ///             Term::Atom(Atom::Symbol(Symbol::NonConstant("PCI".to_string())))
-///         )), r#if: None }
+///         )), r#if: None, span: Default::default() }
 ///     ))
 /// )
 /// ```
 pub fn parse_depends_on(input: KconfigInput) -> IResult<KconfigInput, DependsOn> {
     map(
-        (
+        spanned((
             tag("depends"),
             wsi(opt(tag("on"))),
             wsi(parse_expression),
             opt(parse_if_expression),
-        ),
-        |(_, _, e, r#if)| DependsOn {
+        )),
+        |((_, _, e, r#if), span)| DependsOn {
             expression: e,
             r#if,
+            span,
         },
     )
     .parse(input)
@@ -21,6 +21,7 @@ fn test_parse_depends_on() {
                     Symbol::NonConstant("PCI".to_string())
                 )))),
                 r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -39,6 +40,7 @@ fn test_parse_depends_on_weird_tab() {
                     Symbol::NonConstant("LIVEPATCH".to_string())
                 )))),
                 r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -74,6 +76,7 @@ fn test_parse_depends_on_backslash() {
                     )))
                 )))),
                 r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -101,6 +104,7 @@ fn test_parse_depends_on_with_minus_one() {
                     }
                 )))),
                 r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -128,6 +132,7 @@ fn test_parse_depends_on_with_if_condition() {
                 r#if: Some(Expression::Term(AndExpression::Term(Term::Not(
                     Atom::Symbol(Symbol::NonConstant("USB_GADGET".to_string()))
                 )))),
+                span: Default::default()
             }
         ))
     )
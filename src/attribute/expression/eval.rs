@@ -0,0 +1,232 @@
+//! Evaluation of [expressions](crate::attribute::Expression) following the Kconfig semantics.
+//! See ["Menu dependencies"](https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#menu-dependencies) for more information.
+//!
+//! - `&&` returns the minimum of both operands, `||` the maximum and `!` returns `2 - x` with `n = 0`, `m = 1` and `y = 2`.
+//! - Comparisons are numeric when both operands are int or hex values, otherwise strings are compared.
+//! - Unknown symbols, string/int/hex symbols used as a boolean and unexpanded macros evaluate to `n`.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use nom_kconfig::{
+//!     attribute::{evaluate, parse_expression, SymbolValue},
+//!     tristate::Tristate,
+//!     KconfigInput,
+//! };
+//!
+//! let (_, expression) = parse_expression(KconfigInput::new_extra("NET && (HZ > 100 || !PCI)", Default::default())).unwrap();
+//! let mut values = HashMap::new();
+//! values.insert("NET".to_string(), SymbolValue::Tristate(Tristate::Module));
+//! values.insert("HZ".to_string(), SymbolValue::Int(250));
+//! assert_eq!(evaluate(&expression, &values), Tristate::Module);
+//! ```
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{
+        expression::{
+            AndExpression, Atom, CompareExpression, CompareOperand, CompareOperator, Term,
+        },
+        Expression,
+    },
+    symbol::ConstantSymbol,
+    tristate::Tristate,
+    Symbol,
+};
+
+/// The value of a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum SymbolValue {
+    Tristate(Tristate),
+    Int(i64),
+    /// Hex values are kept as written, prefix included.
+    Hex(String),
+    String(String),
+}
+
+impl SymbolValue {
+    /// The value as it appears in a `.config` file, without quotes.
+    pub fn raw(&self) -> String {
+        match self {
+            SymbolValue::Tristate(Tristate::Yes) => "y".to_string(),
+            SymbolValue::Tristate(Tristate::Module) => "m".to_string(),
+            SymbolValue::Tristate(Tristate::No) => "n".to_string(),
+            SymbolValue::Int(i) => i.to_string(),
+            SymbolValue::Hex(h) => h.clone(),
+            SymbolValue::String(s) => s.clone(),
+        }
+    }
+
+    /// The tristate value of the symbol when used in a boolean context. Non-tristate values are `n`.
+    pub fn tristate(&self) -> Tristate {
+        match self {
+            SymbolValue::Tristate(t) => *t,
+            _ => Tristate::No,
+        }
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for SymbolValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw())
+    }
+}
+
+/// Gives access to the current value of symbols.
+pub trait SymbolProvider {
+    /// Returns the value of the symbol, `None` when the symbol is unknown.
+    fn value(&self, symbol: &str) -> Option<SymbolValue>;
+}
+
+impl<S: BuildHasher> SymbolProvider for HashMap<String, SymbolValue, S> {
+    fn value(&self, symbol: &str) -> Option<SymbolValue> {
+        self.get(symbol).cloned()
+    }
+}
+
+impl SymbolProvider for BTreeMap<String, SymbolValue> {
+    fn value(&self, symbol: &str) -> Option<SymbolValue> {
+        self.get(symbol).cloned()
+    }
+}
+
+impl<P: SymbolProvider + ?Sized> SymbolProvider for &P {
+    fn value(&self, symbol: &str) -> Option<SymbolValue> {
+        (**self).value(symbol)
+    }
+}
+
+/// Evaluates an expression to a tristate value.
+pub fn evaluate<P: SymbolProvider + ?Sized>(expression: &Expression, provider: &P) -> Tristate {
+    match expression {
+        Expression::Term(and) => evaluate_and(and, provider),
+        Expression::Expression(ands) => ands
+            .iter()
+            .fold(Tristate::No, |acc, and| acc.or(evaluate_and(and, provider))),
+    }
+}
+
+/// Evaluates an expression to a value. An expression made of a single symbol or constant returns the value of that symbol,
+/// this is what `default` and `range` expect for int, hex and string symbols. Any other expression returns a tristate.
+pub fn evaluate_value<P: SymbolProvider + ?Sized>(
+    expression: &Expression,
+    provider: &P,
+) -> SymbolValue {
+    match expression {
+        Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(symbol)))) => {
+            symbol_value(symbol, provider)
+        }
+        Expression::Term(AndExpression::Term(Term::Atom(Atom::Parenthesis(e)))) => {
+            evaluate_value(e, provider)
+        }
+        e => SymbolValue::Tristate(evaluate(e, provider)),
+    }
+}
+
+fn evaluate_and<P: SymbolProvider + ?Sized>(and: &AndExpression, provider: &P) -> Tristate {
+    match and {
+        AndExpression::Term(term) => evaluate_term(term, provider),
+        AndExpression::Expression(terms) => terms.iter().fold(Tristate::Yes, |acc, term| {
+            acc.and(evaluate_term(term, provider))
+        }),
+    }
+}
+
+fn evaluate_term<P: SymbolProvider + ?Sized>(term: &Term, provider: &P) -> Tristate {
+    match term {
+        Term::Not(atom) => !evaluate_atom(atom, provider),
+        Term::Atom(atom) => evaluate_atom(atom, provider),
+    }
+}
+
+fn evaluate_atom<P: SymbolProvider + ?Sized>(atom: &Atom, provider: &P) -> Tristate {
+    match atom {
+        Atom::Symbol(symbol) => symbol_value(symbol, provider).tristate(),
+        Atom::Compare(compare) => evaluate_compare(compare, provider),
+        Atom::Parenthesis(expression) => evaluate(expression, provider),
+        Atom::Macro(_) => Tristate::No,
+    }
+}
+
+fn symbol_value<P: SymbolProvider + ?Sized>(symbol: &Symbol, provider: &P) -> SymbolValue {
+    match symbol {
+        Symbol::NonConstant(name) => provider
+            .value(name)
+            .unwrap_or(SymbolValue::Tristate(Tristate::No)),
+        Symbol::Constant(constant) => constant_value(constant),
+    }
+}
+
+fn constant_value(constant: &ConstantSymbol) -> SymbolValue {
+    match constant {
+        ConstantSymbol::Integer(i) => SymbolValue::Int(*i),
+        ConstantSymbol::Hex(h) => SymbolValue::Hex(h.clone()),
+        ConstantSymbol::Boolean(b) => SymbolValue::Tristate(Tristate::from(*b)),
+        ConstantSymbol::Tristate(t) => SymbolValue::Tristate(*t),
+        // "y", "m" and "n" are the same symbols as y, m and n
+        ConstantSymbol::String(s) => match s.as_str() {
+            "y" => SymbolValue::Tristate(Tristate::Yes),
+            "m" => SymbolValue::Tristate(Tristate::Module),
+            "n" => SymbolValue::Tristate(Tristate::No),
+            _ => SymbolValue::String(s.clone()),
+        },
+    }
+}
+
+fn evaluate_compare<P: SymbolProvider + ?Sized>(
+    compare: &CompareExpression,
+    provider: &P,
+) -> Tristate {
+    let (left, right) = match (&compare.left, &compare.right) {
+        (CompareOperand::Symbol(l), CompareOperand::Symbol(r)) => {
+            (symbol_value(l, provider), symbol_value(r, provider))
+        }
+        // Macros must be expanded before evaluating the expression.
+        _ => return Tristate::No,
+    };
+    let ordering = match (parse_number(&left), parse_number(&right)) {
+        (Some(l), Some(r)) => l.cmp(&r),
+        _ => left.raw().cmp(&right.raw()),
+    };
+    Tristate::from(match compare.operator {
+        CompareOperator::Equal => ordering == Ordering::Equal,
+        CompareOperator::NotEqual => ordering != Ordering::Equal,
+        CompareOperator::LowerThan => ordering == Ordering::Less,
+        CompareOperator::LowerOrEqual => ordering != Ordering::Greater,
+        CompareOperator::GreaterThan => ordering == Ordering::Greater,
+        CompareOperator::GreaterOrEqual => ordering != Ordering::Less,
+    })
+}
+
+/// Parses int and hex values, strings containing a number are accepted as well.
+pub(crate) fn parse_number(value: &SymbolValue) -> Option<i128> {
+    match value {
+        SymbolValue::Tristate(_) => None,
+        SymbolValue::Int(i) => Some(*i as i128),
+        SymbolValue::Hex(h) | SymbolValue::String(h) => parse_number_str(h),
+    }
+}
+
+pub(crate) fn parse_number_str(value: &str) -> Option<i128> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => i128::from_str_radix(hex, 16).ok(),
+        None => value.parse::<i128>().ok(),
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::{
+    attribute::expression::{
+        eval::{evaluate, evaluate_value, SymbolValue},
+        parse_expression,
+    },
+    tristate::Tristate,
+    KconfigInput,
+};
+
+fn values() -> HashMap<String, SymbolValue> {
+    HashMap::from([
+        ("YES".to_string(), SymbolValue::Tristate(Tristate::Yes)),
+        ("MOD".to_string(), SymbolValue::Tristate(Tristate::Module)),
+        ("NO".to_string(), SymbolValue::Tristate(Tristate::No)),
+        ("HZ".to_string(), SymbolValue::Int(250)),
+        ("BASE".to_string(), SymbolValue::Hex("0x1000".to_string())),
+        ("NAME".to_string(), SymbolValue::String("linux".to_string())),
+    ])
+}
+
+fn eval(input: &str) -> Tristate {
+    let (_, expression) =
+        parse_expression(KconfigInput::new_extra(input, Default::default())).unwrap();
+    evaluate(&expression, &values())
+}
+
+#[test]
+fn test_evaluate_symbols() {
+    assert_eq!(eval("YES"), Tristate::Yes);
+    assert_eq!(eval("MOD"), Tristate::Module);
+    assert_eq!(eval("UNKNOWN"), Tristate::No);
+    assert_eq!(eval("HZ"), Tristate::No);
+    assert_eq!(eval("m"), Tristate::Module);
+    assert_eq!(eval("\"y\""), Tristate::Yes);
+}
+
+#[test]
+fn test_evaluate_logical_operators() {
+    assert_eq!(eval("YES && MOD"), Tristate::Module);
+    assert_eq!(eval("NO || MOD"), Tristate::Module);
+    assert_eq!(eval("!MOD"), Tristate::Module);
+    assert_eq!(eval("!YES || NO"), Tristate::No);
+    assert_eq!(eval("YES && (NO || !NO)"), Tristate::Yes);
+    assert_eq!(eval("!(YES && MOD)"), Tristate::Module);
+}
+
+#[test]
+fn test_evaluate_comparisons() {
+    assert_eq!(eval("HZ > 100"), Tristate::Yes);
+    assert_eq!(eval("HZ <= 100"), Tristate::No);
+    assert_eq!(eval("BASE = 4096"), Tristate::Yes);
+    assert_eq!(eval("BASE >= 0x2000"), Tristate::No);
+    assert_eq!(eval("NAME = \"linux\""), Tristate::Yes);
+    assert_eq!(eval("NAME != \"linux\""), Tristate::No);
+    assert_eq!(eval("NAME != linux"), Tristate::Yes);
+    assert_eq!(eval("MOD = m"), Tristate::Yes);
+    assert_eq!(eval("UNKNOWN = n"), Tristate::Yes);
+    assert_eq!(eval("UNKNOWN != y"), Tristate::Yes);
+}
+
+#[test]
+fn test_evaluate_value() {
+    let (_, expression) =
+        parse_expression(KconfigInput::new_extra("(HZ)", Default::default())).unwrap();
+    assert_eq!(
+        evaluate_value(&expression, &values()),
+        SymbolValue::Int(250)
+    );
+    let (_, expression) =
+        parse_expression(KconfigInput::new_extra("YES && MOD", Default::default())).unwrap();
+    assert_eq!(
+        evaluate_value(&expression, &values()),
+        SymbolValue::Tristate(Tristate::Module)
+    );
+}
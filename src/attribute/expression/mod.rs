@@ -1,11 +1,15 @@
 pub mod atom;
 pub mod compare;
+pub mod eval;
+#[cfg(test)]
+mod eval_test;
 #[cfg(test)]
 mod mod_test;
 pub mod term;
 
 pub use atom::*;
 pub use compare::*;
+pub use eval::*;
 pub use term::*;
 
 #[cfg(feature = "display")]
@@ -23,7 +27,7 @@ use serde::Deserialize;
 #[cfg(feature = "serialize")]
 use serde::Serialize;
 
-use crate::{util::wsi, KconfigInput};
+use crate::{util::wsi, KconfigInput, Symbol};
 
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
@@ -91,6 +95,120 @@ impl Display for OrExpression {
     }
 }
 
+impl AndExpression {
+    fn into_terms(self) -> Vec<Term> {
+        match self {
+            Self::Term(t) => vec![t],
+            Self::Expression(terms) => terms,
+        }
+    }
+}
+
+impl OrExpression {
+    /// Builds an expression made of a single symbol.
+    pub fn symbol(name: &str) -> Self {
+        Self::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
+            Symbol::NonConstant(name.to_string()),
+        ))))
+    }
+
+    /// Joins two expressions with `&&`. Or-expressions are wrapped in parentheses to preserve the precedence.
+    pub fn and(self, other: Expression) -> Expression {
+        let mut terms = self.into_and_terms();
+        terms.extend(other.into_and_terms());
+        Self::Term(AndExpression::Expression(terms))
+    }
+
+    /// Joins two expressions with `||`.
+    pub fn or(self, other: Expression) -> Expression {
+        let mut expressions = self.into_or_terms();
+        expressions.extend(other.into_or_terms());
+        Self::Expression(expressions)
+    }
+
+    /// Negates the expression, `!A` becomes `A` and any other expression `E` becomes `!(E)`.
+    pub fn negate(self) -> Expression {
+        match self {
+            Self::Term(AndExpression::Term(Term::Not(atom))) => {
+                Self::Term(AndExpression::Term(Term::Atom(atom)))
+            }
+            Self::Term(AndExpression::Term(Term::Atom(atom))) => {
+                Self::Term(AndExpression::Term(Term::Not(atom)))
+            }
+            e => Self::Term(AndExpression::Term(Term::Not(Atom::Parenthesis(Box::new(
+                e,
+            ))))),
+        }
+    }
+
+    /// Joins two optional expressions with `&&`, `None` being the `y` constant.
+    pub fn and_opt(left: Option<Expression>, right: Option<Expression>) -> Option<Expression> {
+        match (left, right) {
+            (Some(l), Some(r)) => Some(l.and(r)),
+            (l, r) => l.or(r),
+        }
+    }
+
+    /// Names of the non-constant symbols referenced by the expression, in order of appearance and without duplicates.
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols = vec![];
+        self.collect_symbols(&mut symbols);
+        symbols
+    }
+
+    fn collect_symbols<'a>(&'a self, symbols: &mut Vec<&'a str>) {
+        for and in self.and_expressions() {
+            let terms = match and {
+                AndExpression::Term(term) => std::slice::from_ref(term),
+                AndExpression::Expression(terms) => terms.as_slice(),
+            };
+            for term in terms {
+                let (Term::Not(atom) | Term::Atom(atom)) = term;
+                let mut push = |symbol: &'a Symbol| {
+                    if let Symbol::NonConstant(name) = symbol {
+                        if !symbols.contains(&name.as_str()) {
+                            symbols.push(name);
+                        }
+                    }
+                };
+                match atom {
+                    Atom::Symbol(symbol) => push(symbol),
+                    Atom::Compare(compare) => {
+                        for operand in [&compare.left, &compare.right] {
+                            if let CompareOperand::Symbol(symbol) = operand {
+                                push(symbol)
+                            }
+                        }
+                    }
+                    Atom::Macro(_) => (),
+                    Atom::Parenthesis(expression) => expression.collect_symbols(symbols),
+                }
+            }
+        }
+    }
+
+    fn and_expressions(&self) -> &[AndExpression] {
+        match self {
+            Self::Term(and) => std::slice::from_ref(and),
+            Self::Expression(ands) => ands,
+        }
+    }
+
+    fn into_and_terms(self) -> Vec<Term> {
+        match self {
+            Self::Term(and) => and.into_terms(),
+            e => vec![Term::Atom(Atom::Parenthesis(Box::new(e)))],
+        }
+    }
+
+    fn into_or_terms(self) -> Vec<AndExpression> {
+        match self {
+            Self::Term(and) => vec![and],
+            Self::Expression(ands) => ands,
+        }
+    }
+}
+
 pub fn parse_or_expression(input: KconfigInput) -> IResult<KconfigInput, OrExpression> {
     map(
         (
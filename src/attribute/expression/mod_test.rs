@@ -190,7 +190,8 @@ fn test_parse_expression_function() {
                             ExpressionToken::Space,
                             ExpressionToken::Literal("llvm".to_string())
                         )
-                    })
+                    }),
+                    span: Default::default()
                 })
             ))))
         ))
@@ -355,7 +356,8 @@ fn test_expression_to_string() {
         Expression::Term(AndExpression::Term(Term::Atom(Atom::Macro(
             Macro::FunctionCall(FunctionCall {
                 name: "warning".to_string(),
-                parameters: vec!()
+                parameters: vec!(),
+                span: Default::default()
             })
         ))))
         .to_string()
@@ -409,7 +411,8 @@ fn test_expression_compare_with_function_call() {
                                 ExpressionToken::Space,
                                 ExpressionToken::Literal("10000".to_string())
                             )
-                        })
+                        }),
+                        span: Default::default()
                     }))
                 }))
             )))
@@ -468,3 +471,13 @@ fn test_expression_compare_with_macro() {
         ))
     )
 }
+
+#[test]
+fn test_expression_symbols() {
+    let (_, expression) = parse_expression(crate::KconfigInput::new_extra(
+        "A && !(B || A = C) || $(VAR) && D != 3 && y",
+        Default::default(),
+    ))
+    .unwrap();
+    assert_eq!(expression.symbols(), vec!["A", "B", "C", "D"]);
+}
@@ -14,23 +14,31 @@ use serde::Deserialize;
 #[cfg(feature = "serialize")]
 use serde::Serialize;
 
-use crate::{util::ws, KconfigInput};
+use crate::{
+    preprocess::Preprocessor,
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 
-#[derive(Debug, PartialEq, Clone, Default)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct FunctionCall {
     pub name: String,
     pub parameters: Vec<Parameter>,
+    pub span: Span,
 }
 
-//#[cfg(feature = "kconfiglib")]
-//impl FunctionCall {
-//    fn call(&self) -> String {
-//
-//    }
-//}
+ignore_span!(FunctionCall { name, parameters });
+
+impl FunctionCall {
+    /// Evaluates the call with the variables and functions known to the preprocessor.
+    pub fn call(&self, preprocessor: &mut Preprocessor) -> String {
+        let arguments: Vec<String> = self.parameters.iter().map(|p| p.to_string()).collect();
+        preprocessor.call(&self.name, &arguments)
+    }
+}
 
 impl Display for Parameter {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -50,7 +58,7 @@ impl Display for ExpressionToken {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             ExpressionToken::Literal(s) => write!(f, "{}", s),
-            ExpressionToken::Variable(v) => write!(f, "${}", v),
+            ExpressionToken::Variable(v) => write!(f, "$({})", v),
             ExpressionToken::DoubleQuotes(s) => write!(
                 f,
                 r#""{}""#,
@@ -219,17 +227,18 @@ pub fn parse_function_call(input: KconfigInput) -> IResult<KconfigInput, Functio
 
 fn parse_function_call_inner(input: KconfigInput) -> IResult<KconfigInput, FunctionCall> {
     map(
-        delimited(
+        spanned(delimited(
             tag("$("),
             (
                 terminated(parse_function_name, ws(tag(","))),
                 separated_list0(ws(tag(",")), ws(parse_parameter)),
             ),
             ws(tag(")")),
-        ),
-        |(name, parameters)| FunctionCall {
+        )),
+        |((name, parameters), span)| FunctionCall {
             name: name.to_string(),
             parameters,
+            span,
         },
     )
     .parse(input)
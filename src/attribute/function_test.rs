@@ -14,7 +14,8 @@ fn test_parse_function_call() {
                 name: "hello".to_string(),
                 parameters: vec!(Parameter {
                     tokens: vec!(ExpressionToken::Literal("world".to_string()))
-                },)
+                },),
+                span: Default::default()
             }
         ))
     )
@@ -36,7 +37,8 @@ fn test_parse_function_call_with_parameters() {
                     Parameter {
                         tokens: vec!(ExpressionToken::Literal("John".to_string()))
                     }
-                )
+                ),
+                span: Default::default()
             }
         ))
     )
@@ -59,7 +61,8 @@ fn test_parse_function_call_expression() {
                         ExpressionToken::Space,
                         ExpressionToken::Literal("basic".to_string())
                     )
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -83,7 +86,8 @@ fn test_parse_function_call_expanded_variable() {
                         ExpressionToken::Space,
                         ExpressionToken::Variable("SIMPLE".to_string())
                     )
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -109,9 +113,11 @@ fn test_parse_function_call_recursive_function() {
                             Parameter {
                                 tokens: vec!(ExpressionToken::Literal("Jean-Louis".to_string()),)
                             }
-                        )
+                        ),
+                        span: Default::default()
                     })))
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -133,7 +139,8 @@ fn test_parse_function_call_complex_expression() {
                         ExpressionToken::Literal("=".to_string()),
                         ExpressionToken::Variable("filename".to_string())
                     )
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -155,7 +162,8 @@ fn test_parse_function_call_special_chars() {
                         ExpressionToken::Space,
                         ExpressionToken::Literal("armv8.5-a+memtag".to_string()),
                     )
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -186,7 +194,8 @@ fn test_parse_function_call_special_backtick() {
                             "119".to_string()
                         )))
                     )
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -228,9 +237,10 @@ fn test_function_to_string() {
                     )
                 }
             ),
+            span: Default::default()
         }
         .to_string(),
-        "$(warning, ls -la, echo $HOME)"
+        "$(warning, ls -la, echo $(HOME))"
     )
 }
 
@@ -240,7 +250,7 @@ fn test_expression_token_to_string() {
     assert_eq!(ExpressionToken::Space.to_string(), " ");
     assert_eq!(
         ExpressionToken::Variable("PWD".to_string()).to_string(),
-        "$PWD"
+        "$(PWD)"
     );
     assert_eq!(
         ExpressionToken::DoubleQuotes(vec!(
@@ -262,7 +272,8 @@ fn test_expression_token_to_string() {
     assert_eq!(
         ExpressionToken::Function(Box::new(FunctionCall {
             name: "warning".to_string(),
-            parameters: vec!()
+            parameters: vec!(),
+            span: Default::default()
         }))
         .to_string(),
         "$(warning)"
@@ -297,7 +308,8 @@ fn test_parse_config_string_with_double_quotes() {
                             "CC".to_string()
                         )])
                     )
-                })
+                }),
+                span: Default::default()
             }
         ))
     )
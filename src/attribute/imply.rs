@@ -5,6 +5,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::{
+    span::{ignore_span, spanned, Span},
     symbol::{parse_symbol, Symbol},
     util::ws,
     KconfigInput,
@@ -13,8 +14,7 @@ use crate::{
 use super::{expression::Expression, parse_if_attribute};
 
 /// Imply` is similar to "select" as it enforces a lower limit on another symbol except that the "implied" symbol's value may still be set to n from a direct dependency or with a visible prompt.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Imply {
@@ -24,8 +24,11 @@ pub struct Imply {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(Imply { symbol, r#if });
+
 #[cfg(feature = "display")]
 use std::fmt::Display;
 #[cfg(feature = "display")]
@@ -54,15 +57,20 @@ impl Display for Imply {
 ///         "",
 ///         Imply {
 ///             symbol: Symbol::NonConstant("PCI".to_string()),
-///             r#if: None
+///             r#if: None,
+///             span: Default::default(),
 ///         }
 ///     ))
 /// )
 /// ```
 pub fn parse_imply(input: KconfigInput) -> IResult<KconfigInput, Imply> {
     map(
-        (ws(tag("imply")), ws(parse_symbol), parse_if_attribute),
-        |(_, s, i)| Imply { symbol: s, r#if: i },
+        spanned((ws(tag("imply")), ws(parse_symbol), parse_if_attribute)),
+        |((_, s, i), span)| Imply {
+            symbol: s,
+            r#if: i,
+            span,
+        },
     )
     .parse(input)
 }
@@ -13,7 +13,8 @@ fn test_parse_imply() {
             "",
             Imply {
                 symbol: Symbol::NonConstant("PCI".to_string()),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -24,7 +25,8 @@ fn test_imply_to_string() {
     assert_eq!(
         Imply {
             symbol: Symbol::NonConstant("PCI".to_string()),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         }
         .to_string(),
         "PCI".to_string()
@@ -34,7 +36,8 @@ fn test_imply_to_string() {
             symbol: Symbol::NonConstant("PCI".to_string()),
             r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                 Atom::Symbol(Symbol::NonConstant("64BITS".to_string()))
-            ))))
+            )))),
+            span: Default::default()
         }
         .to_string(),
         "PCI if 64BITS".to_string()
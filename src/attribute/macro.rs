@@ -32,7 +32,7 @@ impl Display for Macro {
         match self {
             Macro::FunctionCall(func) => write!(f, "{}", func),
             Macro::Variable(v) => write!(f, "$({})", v),
-            Macro::DoubleQuoted(d) => write!(f, "\"{})\"", d),
+            Macro::DoubleQuoted(d) => write!(f, "\"{}\"", d),
         }
     }
 }
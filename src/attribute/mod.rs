@@ -28,7 +28,8 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::{
-    attribute::{depends_on::DependsOn, transitional::parse_transitional},
+    attribute::{depends_on::DependsOn, transitional::parse_transitional, visible::Visible},
+    span::{spanned, Span},
     util::ws,
     KconfigInput,
 };
@@ -50,33 +51,98 @@ pub use self::{
 };
 
 pub use self::expression::{
-    parse_expression, parse_if_attribute, AndExpression, Atom, CompareExpression, CompareOperator,
-    OrExpression, Term,
+    evaluate, evaluate_value, parse_expression, parse_if_attribute, AndExpression, Atom,
+    CompareExpression, CompareOperator, OrExpression, SymbolProvider, SymbolValue, Term,
 };
 pub use self::function::{parse_function_call, ExpressionToken, FunctionCall, Parameter};
 pub use self::optional::parse_optional;
 pub use self::prompt::parse_prompt_value;
 
 /// Official documentation regarding the different attributes: [https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#menu-attributes](https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#menu-attributes)
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub enum Attribute {
-    Help(String),
+    Help(String, Span),
     Prompt(Prompt),
-    Modules,
+    Modules(Span),
     Select(Select),
     DependsOn(DependsOn),
-    Optional,
+    Optional(Span),
     Range(Range),
-    Visible(Option<Expression>),
+    Visible(Visible),
     Default(DefaultAttribute),
     Imply(Imply),
-    Requires(Expression),
+    Requires(Expression, Span),
     Type(ConfigType),
-    Option(OptionValues),
-    Transitional,
+    Option(OptionValues, Span),
+    Transitional(Span),
+}
+
+impl Attribute {
+    /// The location of the attribute.
+    pub fn span(&self) -> &Span {
+        match self {
+            Attribute::Help(_, span)
+            | Attribute::Modules(span)
+            | Attribute::Optional(span)
+            | Attribute::Requires(_, span)
+            | Attribute::Option(_, span)
+            | Attribute::Transitional(span) => span,
+            Attribute::Prompt(p) => &p.span,
+            Attribute::Select(s) => &s.span,
+            Attribute::DependsOn(d) => &d.span,
+            Attribute::Range(r) => &r.span,
+            Attribute::Visible(v) => &v.span,
+            Attribute::Default(d) => &d.span,
+            Attribute::Imply(i) => &i.span,
+            Attribute::Type(t) => &t.span,
+        }
+    }
+}
+
+/// Like the other nodes of the AST, attributes are compared without their [Span].
+impl PartialEq for Attribute {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Attribute::Help(a, _), Attribute::Help(b, _)) => a == b,
+            (Attribute::Prompt(a), Attribute::Prompt(b)) => a == b,
+            (Attribute::Modules(_), Attribute::Modules(_)) => true,
+            (Attribute::Select(a), Attribute::Select(b)) => a == b,
+            (Attribute::DependsOn(a), Attribute::DependsOn(b)) => a == b,
+            (Attribute::Optional(_), Attribute::Optional(_)) => true,
+            (Attribute::Range(a), Attribute::Range(b)) => a == b,
+            (Attribute::Visible(a), Attribute::Visible(b)) => a == b,
+            (Attribute::Default(a), Attribute::Default(b)) => a == b,
+            (Attribute::Imply(a), Attribute::Imply(b)) => a == b,
+            (Attribute::Requires(a, _), Attribute::Requires(b, _)) => a == b,
+            (Attribute::Type(a), Attribute::Type(b)) => a == b,
+            (Attribute::Option(a, _), Attribute::Option(b, _)) => a == b,
+            (Attribute::Transitional(_), Attribute::Transitional(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "hash")]
+impl std::hash::Hash for Attribute {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Attribute::Help(help, _) => help.hash(state),
+            Attribute::Prompt(prompt) => prompt.hash(state),
+            Attribute::Select(select) => select.hash(state),
+            Attribute::DependsOn(depends_on) => depends_on.hash(state),
+            Attribute::Range(range) => range.hash(state),
+            Attribute::Visible(visible) => visible.hash(state),
+            Attribute::Default(default) => default.hash(state),
+            Attribute::Imply(imply) => imply.hash(state),
+            Attribute::Requires(requires, _) => requires.hash(state),
+            Attribute::Type(r#type) => r#type.hash(state),
+            Attribute::Option(option, _) => option.hash(state),
+            Attribute::Modules(_) | Attribute::Optional(_) | Attribute::Transitional(_) => (),
+        }
+    }
 }
 
 pub fn parse_attributes(input: KconfigInput) -> IResult<KconfigInput, Vec<Attribute>> {
@@ -86,18 +152,30 @@ pub fn parse_attributes(input: KconfigInput) -> IResult<KconfigInput, Vec<Attrib
 pub fn parse_attribute(input: KconfigInput) -> IResult<KconfigInput, Attribute> {
     alt((
         map(ws(parse_prompt), Attribute::Prompt),
-        map(ws(parse_help), Attribute::Help),
+        map(ws(spanned(parse_help)), |(h, span)| {
+            Attribute::Help(h, span)
+        }),
         map(ws(parse_depends_on), Attribute::DependsOn),
         map(ws(parse_select), Attribute::Select),
         map(ws(parse_default), Attribute::Default),
-        map(ws(parse_requires), Attribute::Requires),
-        map(ws(parse_modules), |_| Attribute::Modules),
+        map(ws(spanned(parse_requires)), |(r, span)| {
+            Attribute::Requires(r, span)
+        }),
+        map(ws(spanned(parse_modules)), |(_, span)| {
+            Attribute::Modules(span)
+        }),
         map(ws(parse_range), Attribute::Range),
         map(ws(parse_imply), Attribute::Imply),
         map(ws(parse_visible), Attribute::Visible),
-        map(ws(parse_option), Attribute::Option),
-        map(ws(parse_optional), |_| Attribute::Optional),
-        map(ws(parse_transitional), |_| Attribute::Transitional),
+        map(ws(spanned(parse_option)), |(o, span)| {
+            Attribute::Option(o, span)
+        }),
+        map(ws(spanned(parse_optional)), |(_, span)| {
+            Attribute::Optional(span)
+        }),
+        map(ws(spanned(parse_transitional)), |(_, span)| {
+            Attribute::Transitional(span)
+        }),
     ))
     .parse(input)
 }
@@ -108,23 +186,24 @@ use std::fmt::Display;
 impl Display for Attribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Attribute::Help(s) => write!(f, "help\n  {}", s),
+            Attribute::Help(s, _) => write!(
+                f,
+                "{}",
+                crate::format::format_help(s, "  ", None).trim_end_matches('\n')
+            ),
             Attribute::Prompt(p) => write!(f, "prompt {p}"),
-            Attribute::Modules => write!(f, "modules"),
+            Attribute::Modules(_) => write!(f, "modules"),
             Attribute::Select(s) => write!(f, "select {s}"),
             Attribute::DependsOn(d) => write!(f, "depends on {d}"),
-            Attribute::Optional => write!(f, "optional"),
+            Attribute::Optional(_) => write!(f, "optional"),
             Attribute::Range(r) => write!(f, "range {r}"),
-            Attribute::Visible(v) => match v {
-                Some(e) => write!(f, "visible if {e}"),
-                None => write!(f, "visible"),
-            },
+            Attribute::Visible(v) => write!(f, "{v}"),
             Attribute::Default(d) => write!(f, "default {d}"),
             Attribute::Imply(i) => write!(f, "imply {i}"),
-            Attribute::Requires(r) => write!(f, "requires {r}"),
+            Attribute::Requires(r, _) => write!(f, "requires {r}"),
             Attribute::Type(t) => write!(f, "{t}"),
-            Attribute::Option(o) => write!(f, "option {o}"),
-            Attribute::Transitional => write!(f, "transitional"),
+            Attribute::Option(o, _) => write!(f, "option {o}"),
+            Attribute::Transitional(_) => write!(f, "transitional"),
         }
     }
 }
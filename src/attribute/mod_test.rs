@@ -5,8 +5,8 @@ use crate::attribute::r#type::{ConfigType, Type};
 use crate::attribute::range::RangeBound;
 use crate::attribute::{
     default::DefaultAttribute, imply::Imply, parse_attribute, prompt::Prompt, range::Range,
-    select::Select, AndExpression, Atom, Attribute, CompareExpression, CompareOperator, Expression,
-    OptionValues, Term,
+    select::Select, visible::Visible, AndExpression, Atom, Attribute, CompareExpression,
+    CompareOperator, Expression, OptionValues, Term,
 };
 
 use crate::assert_parsing_eq;
@@ -18,7 +18,7 @@ fn test_parse_attribute() {
     assert_parsing_eq!(
         parse_attribute,
         "    transitional",
-        Ok(("", Attribute::Transitional))
+        Ok(("", Attribute::Transitional(Default::default())))
     );
 
     assert_parsing_eq!(
@@ -30,7 +30,8 @@ fn test_parse_attribute() {
                 expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                     Symbol::Constant(ConstantSymbol::Tristate(Tristate::Module))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     );
@@ -44,6 +45,7 @@ fn test_parse_attribute() {
                     Symbol::NonConstant("KVM".to_string())
                 )))),
                 r#if: None,
+                span: Default::default()
             })
         ))
     );
@@ -54,14 +56,18 @@ fn test_parse_attribute() {
             "",
             Attribute::Select(Select {
                 symbol: "MTK_INFRACFG".to_string(),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     );
     assert_parsing_eq!(
         parse_attribute,
         "help\n please",
-        Ok(("", Attribute::Help("please".to_string())))
+        Ok((
+            "",
+            Attribute::Help("please".to_string(), Default::default())
+        ))
     );
     assert_parsing_eq!(
         parse_attribute,
@@ -70,15 +76,23 @@ fn test_parse_attribute() {
             "",
             Attribute::Imply(Imply {
                 symbol: Symbol::NonConstant("KVM".to_string()),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     );
-    assert_parsing_eq!(parse_attribute, "    modules", Ok(("", Attribute::Modules)));
+    assert_parsing_eq!(
+        parse_attribute,
+        "    modules",
+        Ok(("", Attribute::Modules(Default::default())))
+    );
     assert_parsing_eq!(
         parse_attribute,
         "    option      defconfig_list",
-        Ok(("", Attribute::Option(OptionValues::DefconfigList)))
+        Ok((
+            "",
+            Attribute::Option(OptionValues::DefconfigList, Default::default())
+        ))
     );
     assert_parsing_eq!(
         parse_attribute,
@@ -87,7 +101,8 @@ fn test_parse_attribute() {
             "",
             Attribute::Prompt(Prompt {
                 prompt: "hello world".to_string(),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     );
@@ -99,7 +114,8 @@ fn test_parse_attribute() {
             Attribute::Range(Range {
                 lower_bound: RangeBound::Number(0),
                 upper_bound: RangeBound::Number(512),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     );
@@ -108,13 +124,20 @@ fn test_parse_attribute() {
         "    requires   MTK_INFRACFG=y",
         Ok((
             "",
-            Attribute::Requires(Expression::Term(AndExpression::Term(Term::Atom(
-                Atom::Compare(CompareExpression {
-                    left: CompareOperand::Symbol(Symbol::NonConstant("MTK_INFRACFG".to_string())),
-                    operator: CompareOperator::Equal,
-                    right: CompareOperand::Symbol(Symbol::Constant(ConstantSymbol::Boolean(true)))
-                })
-            ))))
+            Attribute::Requires(
+                Expression::Term(AndExpression::Term(Term::Atom(Atom::Compare(
+                    CompareExpression {
+                        left: CompareOperand::Symbol(Symbol::NonConstant(
+                            "MTK_INFRACFG".to_string()
+                        )),
+                        operator: CompareOperator::Equal,
+                        right: CompareOperand::Symbol(Symbol::Constant(ConstantSymbol::Boolean(
+                            true
+                        )))
+                    }
+                )))),
+                Default::default()
+            )
         ))
     );
     assert_parsing_eq!(
@@ -124,14 +147,21 @@ fn test_parse_attribute() {
             "",
             Attribute::Select(Select {
                 symbol: "KVM".to_string(),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     );
     assert_parsing_eq!(
         parse_attribute,
         "    visible",
-        Ok(("", Attribute::Visible(None)))
+        Ok((
+            "",
+            Attribute::Visible(Visible {
+                r#if: None,
+                span: Default::default()
+            })
+        ))
     );
 }
 
@@ -149,14 +179,16 @@ fn test_parse_attributes() {
             vec!(
                 Attribute::Select(Select {
                     symbol: "KVM".to_string(),
-                    r#if: None
+                    r#if: None,
+                    span: Default::default()
                 }),
-                Attribute::Modules,
+                Attribute::Modules(Default::default()),
                 Attribute::Default(DefaultAttribute {
                     expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                         Symbol::Constant(ConstantSymbol::Integer(5))
                     )))),
-                    r#if: None
+                    r#if: None,
+                    span: Default::default()
                 }),
             )
         ))
@@ -169,28 +201,47 @@ fn test_attributes_to_string() {
         Symbol::NonConstant("KVM".to_string()),
     ))));
     assert_eq!(
-        Attribute::Help("help please".to_string()).to_string(),
+        Attribute::Help("help please".to_string(), Default::default()).to_string(),
         "help\n  help please".to_string()
     );
     assert_eq!(
         Attribute::Prompt(Prompt {
             prompt: "a prompt".to_string(),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         r#"prompt "a prompt""#.to_string()
     );
-    assert_eq!(Attribute::Modules.to_string(), "modules".to_string());
-    assert_eq!(Attribute::Optional.to_string(), "optional".to_string());
-    assert_eq!(Attribute::Visible(None).to_string(), "visible".to_string());
     assert_eq!(
-        Attribute::Visible(Some(expression.clone())).to_string(),
+        Attribute::Modules(Default::default()).to_string(),
+        "modules".to_string()
+    );
+    assert_eq!(
+        Attribute::Optional(Default::default()).to_string(),
+        "optional".to_string()
+    );
+    assert_eq!(
+        Attribute::Visible(Visible {
+            r#if: None,
+            span: Default::default()
+        })
+        .to_string(),
+        "visible".to_string()
+    );
+    assert_eq!(
+        Attribute::Visible(Visible {
+            r#if: Some(expression.clone()),
+            span: Default::default()
+        })
+        .to_string(),
         "visible if KVM".to_string()
     );
     assert_eq!(
         Attribute::Select(Select {
             symbol: "NET".to_string(),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         "select NET".to_string()
@@ -198,20 +249,22 @@ fn test_attributes_to_string() {
     assert_eq!(
         Attribute::DependsOn(DependsOn {
             expression: expression.clone(),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         "depends on KVM".to_string()
     );
     assert_eq!(
-        Attribute::Transitional.to_string(),
+        Attribute::Transitional(Default::default()).to_string(),
         "transitional".to_string()
     );
     assert_eq!(
         Attribute::Range(Range {
             lower_bound: RangeBound::Number(0),
             upper_bound: RangeBound::Number(15),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         "range 0 15".to_string()
@@ -219,7 +272,8 @@ fn test_attributes_to_string() {
     assert_eq!(
         Attribute::Default(DefaultAttribute {
             expression: expression.clone(),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         "default KVM".to_string()
@@ -227,25 +281,27 @@ fn test_attributes_to_string() {
     assert_eq!(
         Attribute::Imply(Imply {
             symbol: Symbol::NonConstant("DEBUGGER".to_string()),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         "imply DEBUGGER".to_string()
     );
     assert_eq!(
-        Attribute::Requires(expression).to_string(),
+        Attribute::Requires(expression, Default::default()).to_string(),
         "requires KVM".to_string()
     );
     assert_eq!(
         Attribute::Type(ConfigType {
             r#type: Type::Bool(None),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         })
         .to_string(),
         "bool".to_string()
     );
     assert_eq!(
-        Attribute::Option(OptionValues::DefconfigList).to_string(),
+        Attribute::Option(OptionValues::DefconfigList, Default::default()).to_string(),
         "option defconfig_list".to_string()
     );
 }
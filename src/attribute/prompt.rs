@@ -1,5 +1,9 @@
 use super::expression::{parse_if_attribute, Expression};
-use crate::{util::ws, KconfigInput};
+use crate::{
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_until},
@@ -16,8 +20,7 @@ use serde::Serialize;
 
 /// Every menu entry can have at most one prompt, which is used to display to the user.
 /// Optionally dependencies only for this prompt can be added with "if".
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Prompt {
@@ -27,8 +30,11 @@ pub struct Prompt {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(Prompt { prompt, r#if });
+
 #[cfg(feature = "display")]
 use std::fmt::Display;
 #[cfg(feature = "display")]
@@ -43,10 +49,11 @@ impl Display for Prompt {
 
 pub fn parse_prompt(input: KconfigInput) -> IResult<KconfigInput, Prompt> {
     map(
-        (ws(tag("prompt")), parse_prompt_value, parse_if_attribute),
-        |(_, p, i)| Prompt {
+        spanned((ws(tag("prompt")), parse_prompt_value, parse_if_attribute)),
+        |((_, p, i), span)| Prompt {
             prompt: p.to_string(),
             r#if: i,
+            span,
         },
     )
     .parse(input)
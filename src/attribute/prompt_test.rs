@@ -36,7 +36,8 @@ fn test_prompt_to_string() {
     assert_eq!(
         Prompt {
             prompt: "Support of KVM".to_string(),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         }
         .to_string(),
         r#""Support of KVM""#
@@ -47,7 +48,8 @@ fn test_prompt_to_string() {
             prompt: "Support of KVM".to_string(),
             r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                 Atom::Symbol(Symbol::NonConstant("KVM".to_string()))
-            ))))
+            )))),
+            span: Default::default()
         }
         .to_string(),
         r#""Support of KVM" if KVM"#
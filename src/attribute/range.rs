@@ -17,13 +17,13 @@ use std::fmt::Display;
 use super::expression::{parse_if_attribute, Expression};
 use crate::{
     number::parse_number,
+    span::{ignore_span, spanned, Span},
     symbol::{parse_constant_hex_as_string, parse_non_constant_symbol},
 };
 use crate::{util::ws, KconfigInput};
 
 /// This attribute allows to limit the range of possible input values for int and hex symbols. The user can only input a value which is larger than or equal to the first symbol and smaller than or equal to the second symbol.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Range {
@@ -34,8 +34,15 @@ pub struct Range {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(Range {
+    lower_bound,
+    upper_bound,
+    r#if,
+});
+
 #[cfg(feature = "display")]
 impl Display for Range {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -67,18 +74,23 @@ fn parse_bounds(input: KconfigInput) -> IResult<KconfigInput, (RangeBound, Range
 ///         Range {
 ///             lower_bound: RangeBound::Number(1),
 ///             upper_bound: RangeBound::Number(5),
-///             r#if: None
+///             r#if: None,
+///             span: Default::default(),
 ///         }
 ///     ))
 /// )
 /// ```
 pub fn parse_range(input: KconfigInput) -> IResult<KconfigInput, Range> {
     map(
-        preceded(ws(tag("range")), pair(ws(parse_bounds), parse_if_attribute)),
-        |((l, r), i)| Range {
+        spanned(preceded(
+            ws(tag("range")),
+            pair(ws(parse_bounds), parse_if_attribute),
+        )),
+        |(((l, r), i), span)| Range {
             lower_bound: l,
             upper_bound: r,
             r#if: i,
+            span,
         },
     )
     .parse(input)
@@ -14,7 +14,8 @@ fn test_parse_range() {
             Range {
                 lower_bound: RangeBound::Number(1),
                 upper_bound: RangeBound::Number(5),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -26,7 +27,8 @@ fn test_parse_range_to_string() {
         Range {
             lower_bound: RangeBound::Number(1),
             upper_bound: RangeBound::Number(5),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         }
         .to_string(),
         "1 5"
@@ -38,7 +40,8 @@ fn test_parse_range_to_string() {
             upper_bound: RangeBound::Number(5),
             r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                 Atom::Symbol(Symbol::NonConstant("NET".to_string()))
-            ))))
+            )))),
+            span: Default::default()
         }
         .to_string(),
         "1 5 if NET"
@@ -56,7 +59,8 @@ fn test_parse_range_with_hex() {
             Range {
                 lower_bound: RangeBound::Number(0),
                 upper_bound: RangeBound::Hex("0xff".to_string()),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -74,7 +78,8 @@ fn test_parse_range_symbol() {
             Range {
                 lower_bound: RangeBound::Number(0),
                 upper_bound: RangeBound::Symbol("LOG_MAX_LEVEL".to_string()),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
@@ -91,7 +96,8 @@ fn test_parse_range_variable() {
             Range {
                 lower_bound: RangeBound::Number(0),
                 upper_bound: RangeBound::Variable("UINT8_MAX".to_string()),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             }
         ))
     )
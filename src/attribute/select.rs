@@ -8,11 +8,14 @@ use std::fmt::Display;
 
 use super::expression::{parse_if_attribute, Expression};
 use crate::symbol::parse_non_constant_symbol;
-use crate::{util::ws, KconfigInput};
+use crate::{
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 
 /// While normal dependencies reduce the upper limit of a symbol, reverse dependencies can be used to force a lower limit of another symbol. The value of the current menu symbol is used as the minimal value [symbol](crate::Symbol) can be set to. If [symbol](crate::Symbol) is selected multiple times, the limit is set to the largest selection. Reverse dependencies can only be used with boolean or tristate symbols.
-#[derive(Debug, Default, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Select {
@@ -22,8 +25,11 @@ pub struct Select {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(Select { symbol, r#if });
+
 #[cfg(feature = "display")]
 impl Display for Select {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -47,21 +53,23 @@ impl Display for Select {
 ///     "select MTK_INFRACFG",
 ///     Ok(("", Select {
 ///             r#if: None,
-///             symbol: "MTK_INFRACFG".to_string()
+///             symbol: "MTK_INFRACFG".to_string(),
+///             span: Default::default(),
 ///         }
 ///     ))
 /// )
 /// ```
 pub fn parse_select(input: KconfigInput) -> IResult<KconfigInput, Select> {
     map(
-        (
+        spanned((
             ws(alt((tag("select"), tag("enable")))),
             ws(parse_non_constant_symbol),
             parse_if_attribute,
-        ),
-        |(_, s, i)| Select {
+        )),
+        |((_, s, i), span)| Select {
             symbol: s.to_string(),
             r#if: i,
+            span,
         },
     )
     .parse(input)
@@ -13,7 +13,8 @@ fn test_parse_select() {
             "",
             Select {
                 r#if: None,
-                symbol: "MTK_INFRACFG".to_string()
+                symbol: "MTK_INFRACFG".to_string(),
+                span: Default::default()
             }
         ))
     )
@@ -24,7 +25,8 @@ fn test_select_to_string() {
     assert_eq!(
         Select {
             r#if: None,
-            symbol: "MTK_INFRACFG".to_string()
+            symbol: "MTK_INFRACFG".to_string(),
+            span: Default::default()
         }
         .to_string(),
         "MTK_INFRACFG"
@@ -35,7 +37,8 @@ fn test_select_to_string() {
             r#if: Some(Expression::Term(AndExpression::Term(Term::Not(
                 Atom::Symbol(Symbol::NonConstant("KVM".to_string()))
             )))),
-            symbol: "MTK_INFRACFG".to_string()
+            symbol: "MTK_INFRACFG".to_string(),
+            span: Default::default()
         }
         .to_string(),
         "MTK_INFRACFG if !KVM"
@@ -52,7 +55,8 @@ fn test_parse_enable() {
             "",
             Select {
                 r#if: None,
-                symbol: "MTK_INFRACFG".to_string()
+                symbol: "MTK_INFRACFG".to_string(),
+                span: Default::default()
             }
         ))
     )
@@ -1,5 +1,9 @@
 use crate::Attribute;
-use crate::{util::ws, KconfigInput};
+use crate::{
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 use nom::sequence::pair;
 use nom::Parser;
 use nom::{
@@ -20,7 +24,7 @@ use super::{parse_expression, parse_if_attribute, parse_prompt_value, Expression
 
 pub fn parse_type(input: KconfigInput) -> IResult<KconfigInput, Attribute> {
     map(
-        pair(
+        spanned(pair(
             ws(alt((
                 map(
                     preceded(tag("boolean"), opt(parse_prompt_value)),
@@ -57,8 +61,14 @@ pub fn parse_type(input: KconfigInput) -> IResult<KconfigInput, Attribute> {
                 }),
             ))),
             parse_if_attribute,
-        ),
-        |(t, i)| Attribute::Type(ConfigType { r#type: t, r#if: i }),
+        )),
+        |((t, i), span)| {
+            Attribute::Type(ConfigType {
+                r#type: t,
+                r#if: i,
+                span,
+            })
+        },
     )
     .parse(input)
 }
@@ -88,8 +98,7 @@ pub enum Type {
 }
 
 /// Every config option must have a type. There are only two basic types: tristate and string; the other types are based on these two. The type definition optionally accepts an input prompt.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct ConfigType {
@@ -99,8 +108,11 @@ pub struct ConfigType {
         serde(skip_serializing_if = "Option::is_none")
     )]
     pub r#if: Option<Expression>,
+    pub span: Span,
 }
 
+ignore_span!(ConfigType { r#type, r#if });
+
 #[cfg(feature = "display")]
 impl Display for ConfigType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
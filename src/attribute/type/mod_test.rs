@@ -20,7 +20,8 @@ fn test_parse_type() {
             "",
             Attribute::Type(ConfigType {
                 r#type: Type::String(None),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             },)
         ))
     )
@@ -37,7 +38,8 @@ fn test_parse_type_with_weird_prompt() {
             "",
             Attribute::Type(ConfigType {
                 r#type: Type::Bool(Some("TCC8000".to_string())),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             },)
         ))
     )
@@ -54,7 +56,8 @@ fn test_parse_type_bool() {
             "",
             Attribute::Type(ConfigType {
                 r#type: Type::Bool(None),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             },)
         ))
     )
@@ -81,7 +84,8 @@ fn test_parse_type_backslash() {
                     AndExpression::Term(Term::Atom(Atom::Symbol(Symbol::NonConstant(
                         "BROKEN".to_string()
                     ))),)
-                )))
+                ))),
+                span: Default::default()
             },)
         ))
     )
@@ -98,7 +102,8 @@ fn test_parse_def_bool() {
                 r#type: Type::DefBool(Expression::Term(AndExpression::Term(Term::Not(
                     Atom::Symbol(Symbol::NonConstant("PCI".to_string()))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -117,7 +122,8 @@ fn test_parse_type_if() {
                 )))),
                 r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                     Atom::Symbol(Symbol::NonConstant("NET".to_string()))
-                ))))
+                )))),
+                span: Default::default()
             })
         ))
     )
@@ -143,10 +149,12 @@ fn test_parse_def_bool_function() {
                                 ExpressionToken::Variable("comma".to_string()),
                                 ExpressionToken::Literal("%zmm5".to_string())
                             )
-                        })
+                        }),
+                        span: Default::default()
                     }))
                 )))),
                 r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -163,7 +171,8 @@ fn test_parse_def_tristate() {
                 r#type: Type::DefTristate(Expression::Term(AndExpression::Term(Term::Atom(
                     Atom::Symbol(Symbol::Constant(ConstantSymbol::Tristate(Tristate::Module)))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -204,7 +213,8 @@ fn test_type_with_prompt() {
             "",
             Attribute::Type(ConfigType {
                 r#type: Type::Bool(Some("enable it for KVM".to_string())),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -215,7 +225,8 @@ fn test_config_type_to_string() {
     assert_eq!(
         ConfigType {
             r#type: Type::Bool(None),
-            r#if: None
+            r#if: None,
+            span: Default::default()
         }
         .to_string(),
         "bool"
@@ -226,7 +237,8 @@ fn test_config_type_to_string() {
             r#type: Type::Bool(None),
             r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                 Atom::Symbol(Symbol::NonConstant("NET".to_string()))
-            ))))
+            )))),
+            span: Default::default()
         }
         .to_string(),
         "bool if NET".to_string()
@@ -246,7 +258,8 @@ fn test_parse_def_int() {
                 r#type: Type::DefInt(Expression::Term(AndExpression::Term(Term::Atom(
                     Atom::Symbol(Symbol::Constant(ConstantSymbol::Integer(34)))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -265,7 +278,8 @@ fn test_parse_def_hex() {
                 r#type: Type::DefHex(Expression::Term(AndExpression::Term(Term::Atom(
                     Atom::Symbol(Symbol::Constant(ConstantSymbol::Hex("0x23".to_string())))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -286,7 +300,8 @@ fn test_parse_def_string() {
                         "hello".to_string()
                     )))
                 )))),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -307,7 +322,8 @@ fn c() {
 	     Microphone Control Service Microphone Device"
                         .to_string()
                 )),
-                r#if: None
+                r#if: None,
+                span: Default::default()
             })
         ))
     )
@@ -1,19 +1,54 @@
 //! The `visible` attribute is only applicable to menu blocks, if the condition is false, the menu block is not displayed to the user (the symbols contained there can still be selected by other symbols, though). It is similar to a conditional "prompt" attribute for individual menu entries. Default value of "visible" is true.
 
 use super::{parse_if_attribute, Expression};
-use crate::{util::ws, KconfigInput};
-use nom::{bytes::complete::tag, sequence::preceded, IResult, Parser};
+use crate::{
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
+use nom::{bytes::complete::tag, combinator::map, sequence::preceded, IResult, Parser};
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Visible {
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub r#if: Option<Expression>,
+    pub span: Span,
+}
+
+ignore_span!(Visible { r#if });
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for Visible {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.r#if {
+            Some(e) => write!(f, "visible if {e}"),
+            None => write!(f, "visible"),
+        }
+    }
+}
 
-pub type Visible = Option<Expression>;
 /// Parses a `visible` attribute.
 /// # Example
 /// ```
 /// use nom_kconfig::{
 /// assert_parsing_eq,
-/// attribute::{parse_visible},
+/// attribute::{parse_visible, visible::Visible},
 /// };
-/// assert_parsing_eq!(parse_visible, "visible", Ok(("", None)))
+/// assert_parsing_eq!(parse_visible, "visible", Ok(("", Visible::default())))
 /// ```
 pub fn parse_visible(input: KconfigInput) -> IResult<KconfigInput, Visible> {
-    preceded(ws(tag("visible")), parse_if_attribute).parse(input)
+    map(
+        spanned(preceded(ws(tag("visible")), parse_if_attribute)),
+        |(r#if, span)| Visible { r#if, span },
+    )
+    .parse(input)
 }
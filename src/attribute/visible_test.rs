@@ -1,6 +1,9 @@
-use crate::{assert_parsing_eq, attribute::parse_visible};
+use crate::{
+    assert_parsing_eq,
+    attribute::{parse_visible, visible::Visible},
+};
 
 #[test]
 fn test_parse_type() {
-    assert_parsing_eq!(parse_visible, " visible", Ok(("", None)))
+    assert_parsing_eq!(parse_visible, " visible", Ok(("", Visible::default())))
 }
@@ -0,0 +1,228 @@
+//! The build artifacts written by `conf --syncconfig` once the values of the symbols are known:
+//! - `include/generated/autoconf.h`, the configuration as C macros,
+//! - `include/config/auto.conf`, the configuration included by the Makefiles,
+//! - `include/config/auto.conf.cmd`, the Kconfig files and environment variables `auto.conf` depends on,
+//! - `include/config/<SYMBOL>`, an empty file touched when the value of the symbol changes, used by `fixdep` for incremental rebuilds.
+//!
+//! Symbols set to `n` and symbols that are not written to `.config` are left out, like in the C implementation.
+//!
+//! ```
+//! use nom_kconfig::{autoconf::AutoConf, parse_kconfig, resolve::resolve, KconfigInput};
+//!
+//! let input = r#"
+//! config MODULES
+//!     def_bool y
+//!     modules
+//! config USB
+//!     def_tristate m
+//! config HZ
+//!     hex
+//!     default 100
+//! "#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let configuration = resolve(&kconfig, None);
+//! let autoconf = AutoConf::new(&configuration);
+//! assert!(autoconf.autoconf_h().contains("#define CONFIG_USB_MODULE 1\n#define CONFIG_HZ 0x100\n"));
+//! assert!(autoconf.auto_conf().ends_with("CONFIG_USB=m\nCONFIG_HZ=0x100\n"));
+//! ```
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::{
+    attribute::SymbolValue,
+    dotconfig::{format_value, DotConfig, DEFAULT_PREFIX},
+    resolve::{Configuration, ResolvedSymbol},
+    symbol_table::SymbolType,
+    tristate::Tristate,
+    Kconfig,
+};
+
+/// Path of `autoconf.h`, relative to the output directory.
+pub const AUTOCONF_H: &str = "include/generated/autoconf.h";
+/// Path of `auto.conf`, relative to the output directory.
+pub const AUTO_CONF: &str = "include/config/auto.conf";
+/// Path of `auto.conf.cmd`, relative to the output directory.
+pub const AUTO_CONF_CMD: &str = "include/config/auto.conf.cmd";
+/// Directory of the per-symbol files, relative to the output directory.
+pub const CONFIG_DIRECTORY: &str = "include/config";
+
+/// Writers of the `syncconfig` outputs for a resolved [Configuration].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoConf<'a> {
+    configuration: &'a Configuration,
+    prefix: String,
+    title: Option<String>,
+    files: Vec<String>,
+    environment: BTreeMap<String, String>,
+}
+
+impl<'a> AutoConf<'a> {
+    pub fn new(configuration: &'a Configuration) -> Self {
+        Self {
+            configuration,
+            prefix: DEFAULT_PREFIX.to_string(),
+            title: None,
+            files: vec![],
+            environment: BTreeMap::new(),
+        }
+    }
+
+    /// Changes the prefix of the symbols, `CONFIG_` by default.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Takes the title of the headers from the `mainmenu` and the dependencies of `auto.conf.cmd` from the files of the tree.
+    pub fn with_kconfig(mut self, kconfig: &Kconfig) -> Self {
+        self.title = kconfig.main_menu().map(str::to_string);
+        self.files = kconfig.files().into_iter().map(str::to_string).collect();
+        self
+    }
+
+    /// The title written at the top of `autoconf.h` and `auto.conf`.
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Environment variables used while parsing, `auto.conf` is regenerated when one of them changes.
+    pub fn with_environment<K: Into<String>, V: Into<String>>(
+        mut self,
+        environment: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.environment = environment
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self
+    }
+
+    /// Symbols written to the outputs, in definition order.
+    fn symbols(&self) -> impl Iterator<Item = &ResolvedSymbol> {
+        self.configuration
+            .symbols
+            .iter()
+            .filter(|s| s.written && is_set(s))
+    }
+
+    fn title_lines(&self) -> Vec<String> {
+        let mut lines = vec!["Automatically generated file; DO NOT EDIT.".to_string()];
+        lines.extend(self.title.clone());
+        lines
+    }
+
+    /// The content of `include/generated/autoconf.h`.
+    pub fn autoconf_h(&self) -> String {
+        let mut output = "/*\n".to_string();
+        for line in self.title_lines() {
+            output.push_str(&format!(" * {}\n", line));
+        }
+        output.push_str(" */\n");
+        for symbol in self.symbols() {
+            let name = format!("{}{}", self.prefix, symbol.name);
+            let define = match (symbol.r#type, &symbol.value) {
+                (SymbolType::Bool | SymbolType::Tristate, SymbolValue::Tristate(Tristate::Yes)) => {
+                    format!("{} 1", name)
+                }
+                (SymbolType::Tristate, SymbolValue::Tristate(Tristate::Module)) => {
+                    format!("{}_MODULE 1", name)
+                }
+                (SymbolType::Hex, value) => {
+                    let value = value.raw();
+                    match value.starts_with("0x") || value.starts_with("0X") {
+                        true => format!("{} {}", name, value),
+                        false => format!("{} 0x{}", name, value),
+                    }
+                }
+                (SymbolType::String, value) => format!("{} {}", name, format_value(value)),
+                (_, value) => format!("{} {}", name, value.raw()),
+            };
+            output.push_str(&format!("#define {}\n", define));
+        }
+        output
+    }
+
+    /// The content of `include/config/auto.conf`.
+    pub fn auto_conf(&self) -> String {
+        let mut output = "#\n".to_string();
+        for line in self.title_lines() {
+            output.push_str(&format!("# {}\n", line));
+        }
+        output.push_str("#\n");
+        for symbol in self.symbols() {
+            output.push_str(&format!(
+                "{}{}={}\n",
+                self.prefix,
+                symbol.name,
+                format_value(&symbol.value)
+            ));
+        }
+        output
+    }
+
+    /// The content of `include/config/auto.conf.cmd`.
+    pub fn auto_conf_cmd(&self) -> String {
+        let mut output = "# The files below are parsed by Kconfig\ndeps_config := \\\n".to_string();
+        for file in &self.files {
+            output.push_str(&format!("\t{} \\\n", file));
+        }
+        output.push_str(&format!("\n{}: $(deps_config)\n\n", AUTO_CONF));
+        for (name, value) in &self.environment {
+            output.push_str(&format!(
+                "ifneq \"$({})\" \"{}\"\n{}: FORCE\nendif\n",
+                name, value, AUTO_CONF
+            ));
+        }
+        output.push_str("\n$(deps_config): ;\n");
+        output
+    }
+
+    /// Symbols whose value in `auto.conf` differs from `previous`, the former `auto.conf`.
+    /// Every symbol that is set is considered changed when there is no previous `auto.conf`.
+    pub fn changed_symbols(&self, previous: Option<&DotConfig>) -> Vec<&str> {
+        self.configuration
+            .symbols
+            .iter()
+            .filter(|symbol| {
+                let old = previous.and_then(|p| p.get(&symbol.name));
+                let new = (symbol.written && is_set(symbol)).then_some(&symbol.value);
+                match (old, new) {
+                    (None, None) => false,
+                    (Some(old), Some(new)) => old.raw() != new.raw(),
+                    _ => true,
+                }
+            })
+            .map(|symbol| symbol.name.as_str())
+            .collect()
+    }
+
+    /// Writes every output under `directory`, the root of the build tree.
+    /// The former `auto.conf` is read to only touch the files of the symbols that changed.
+    pub fn write(&self, directory: &Path) -> io::Result<()> {
+        let config_directory = directory.join(CONFIG_DIRECTORY);
+        fs::create_dir_all(&config_directory)?;
+        fs::create_dir_all(directory.join("include/generated"))?;
+
+        let previous = match DotConfig::from_file(directory.join(AUTO_CONF), &self.prefix) {
+            Ok(previous) => Some(previous),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        for symbol in self.changed_symbols(previous.as_ref()) {
+            fs::write(config_directory.join(symbol), "")?;
+        }
+
+        fs::write(directory.join(AUTO_CONF_CMD), self.auto_conf_cmd())?;
+        fs::write(directory.join(AUTOCONF_H), self.autoconf_h())?;
+        fs::write(directory.join(AUTO_CONF), self.auto_conf())
+    }
+}
+
+/// `n` is never written to the outputs, other values always are.
+fn is_set(symbol: &ResolvedSymbol) -> bool {
+    match symbol.r#type {
+        SymbolType::Bool | SymbolType::Tristate => symbol.value.tristate() != Tristate::No,
+        _ => true,
+    }
+}
@@ -0,0 +1,149 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::{
+    autoconf::{AutoConf, AUTOCONF_H, AUTO_CONF, AUTO_CONF_CMD, CONFIG_DIRECTORY},
+    dotconfig::DotConfig,
+    kconfig::parse_kconfig,
+    lib_test::{resolve_input, CONFIGURATION},
+    resolve::resolve,
+    vfs::MemoryFs,
+    KconfigFile, KconfigInput,
+};
+
+#[test]
+fn test_autoconf_h() {
+    let (kconfig, configuration) = resolve_input(
+        CONFIGURATION,
+        "CONFIG_MODULES=y\nCONFIG_USB=m\nCONFIG_PCI=y\n",
+    );
+    assert_eq!(
+        AutoConf::new(&configuration)
+            .with_kconfig(&kconfig)
+            .autoconf_h(),
+        r#"/*
+ * Automatically generated file; DO NOT EDIT.
+ * Linux Kernel Configuration
+ */
+#define CONFIG_MODULES 1
+#define CONFIG_USB_MODULE 1
+#define CONFIG_PCI 1
+#define CONFIG_HZ 250
+#define CONFIG_PHYS 0x1000000
+#define CONFIG_LOCALVERSION "-custom"
+#define CONFIG_DEPENDENT 1
+"#
+    );
+}
+
+#[test]
+fn test_autoconf_h_hex_without_prefix() {
+    let (_, configuration) = resolve_input("config PHYS\n    hex \"phys\"\n", "CONFIG_PHYS=ff\n");
+    assert!(AutoConf::new(&configuration)
+        .autoconf_h()
+        .ends_with("#define CONFIG_PHYS 0xff\n"));
+}
+
+#[test]
+fn test_auto_conf() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\nCONFIG_USB=m\n");
+    assert_eq!(
+        AutoConf::new(&configuration)
+            .with_prefix("CFG_")
+            .with_title("Test")
+            .auto_conf(),
+        r#"#
+# Automatically generated file; DO NOT EDIT.
+# Test
+#
+CFG_MODULES=y
+CFG_USB=m
+CFG_HZ=250
+CFG_PHYS=0x1000000
+CFG_LOCALVERSION="-custom"
+"#
+    );
+}
+
+#[test]
+fn test_auto_conf_cmd() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Kconfig", "source \"arch/$(SRCARCH)/Kconfig\"\n");
+    fs.insert("/kernel/arch/x86/Kconfig", "config X86\n    def_bool y\n");
+    let kconfig_file = KconfigFile::new_with_vars(
+        PathBuf::from("/kernel"),
+        PathBuf::from("Kconfig"),
+        &HashMap::from([("SRCARCH", "x86")]),
+        &HashMap::default(),
+    )
+    .with_vfs(fs);
+    let content = kconfig_file.read_to_string().unwrap();
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file)).unwrap();
+    let configuration = resolve(&kconfig, None);
+    assert_eq!(
+        AutoConf::new(&configuration)
+            .with_kconfig(&kconfig)
+            .with_environment([("SRCARCH", "x86")])
+            .auto_conf_cmd(),
+        r#"# The files below are parsed by Kconfig
+deps_config := \
+	Kconfig \
+	arch/x86/Kconfig \
+
+include/config/auto.conf: $(deps_config)
+
+ifneq "$(SRCARCH)" "x86"
+include/config/auto.conf: FORCE
+endif
+
+$(deps_config): ;
+"#
+    );
+}
+
+#[test]
+fn test_changed_symbols() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\nCONFIG_USB=m\n");
+    let autoconf = AutoConf::new(&configuration);
+    assert_eq!(
+        autoconf.changed_symbols(None),
+        vec!["MODULES", "USB", "HZ", "PHYS", "LOCALVERSION"]
+    );
+    let previous = DotConfig::parse(
+        "CONFIG_MODULES=y\nCONFIG_USB=y\nCONFIG_DEBUG=y\nCONFIG_HZ=250\nCONFIG_PHYS=0x1000000\nCONFIG_LOCALVERSION=\"\"\n",
+    );
+    assert_eq!(
+        autoconf.changed_symbols(Some(&previous)),
+        vec!["USB", "DEBUG", "LOCALVERSION"]
+    );
+}
+
+#[test]
+fn test_write() {
+    let directory =
+        std::env::temp_dir().join(format!("nom-kconfig-autoconf-{}", std::process::id()));
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\nCONFIG_PCI=y\n");
+    let result = (|| {
+        AutoConf::new(&configuration).write(&directory)?;
+        fs::remove_file(directory.join(CONFIG_DIRECTORY).join("HZ"))?;
+        fs::remove_file(directory.join(CONFIG_DIRECTORY).join("PCI"))?;
+        let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\n");
+        AutoConf::new(&configuration).write(&directory)?;
+        Ok::<_, std::io::Error>((
+            fs::read_to_string(directory.join(AUTOCONF_H))?,
+            fs::read_to_string(directory.join(AUTO_CONF))?,
+            directory.join(AUTO_CONF_CMD).exists(),
+            directory.join(CONFIG_DIRECTORY).join("HZ").exists(),
+            directory.join(CONFIG_DIRECTORY).join("PCI").exists(),
+            directory.join(CONFIG_DIRECTORY).join("USB").exists(),
+        ))
+    })();
+    fs::remove_dir_all(&directory).unwrap();
+    let (autoconf_h, auto_conf, cmd, hz, pci, usb) = result.unwrap();
+    assert!(autoconf_h.contains("#define CONFIG_MODULES 1\n"));
+    assert!(!auto_conf.contains("CONFIG_PCI"));
+    assert!(cmd);
+    // HZ did not change, PCI was unset
+    assert!(!hz);
+    assert!(pci);
+    assert!(!usb);
+}
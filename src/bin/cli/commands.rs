@@ -0,0 +1,242 @@
+//! The subcommands, returning their output instead of printing it.
+
+use std::{error::Error, path::Path};
+
+use nom::Parser;
+use nom_kconfig::{
+    attribute::{evaluate, parse_expression},
+    cst::parse_kconfig_lossless,
+    cycle::find_cycles,
+    diagnostic::{parse_kconfig_with_diagnostics, Severity},
+    dotconfig::{DotConfig, DEFAULT_PREFIX},
+    entry::SourceMode,
+    format::{format_cst, FormatOptions},
+    graph::DependencyGraph,
+    lint::Linter,
+    parse_kconfig,
+    resolve::resolve,
+    util::ws,
+    Kconfig, KconfigFile, KconfigInput, SymbolTable,
+};
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// What a command prints and whether it succeeded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub stdout: String,
+    pub success: bool,
+}
+
+impl Output {
+    fn ok(stdout: String) -> Self {
+        Self {
+            stdout,
+            success: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// One `FROM -> TO (label)` line per edge.
+    Text,
+    Dot,
+    Graphml,
+    Json,
+}
+
+fn read(kconfig_file: &KconfigFile) -> Result<String> {
+    kconfig_file
+        .read_to_string()
+        .map_err(|e| format!("cannot read {}: {}", kconfig_file.full_path().display(), e).into())
+}
+
+fn load(kconfig_file: &KconfigFile) -> Result<Kconfig> {
+    let content = read(kconfig_file)?;
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file.clone()))?;
+    Ok(kconfig)
+}
+
+pub fn parse(kconfig_file: &KconfigFile) -> Result<Output> {
+    let kconfig = load(kconfig_file)?;
+    Ok(Output::ok(serde_json::to_string_pretty(&kconfig)? + "\n"))
+}
+
+/// Syntax errors, recovering from them, then lints and recursive dependencies.
+pub fn check(kconfig_file: &KconfigFile, deny_warnings: bool) -> Result<Output> {
+    let content = read(kconfig_file)?;
+    let result =
+        parse_kconfig_with_diagnostics(KconfigInput::new_extra(&content, kconfig_file.clone()));
+    let mut linter = Linter::default();
+    let mut reports: Vec<(Severity, String)> = result
+        .diagnostics
+        .iter()
+        .chain(&linter.read_directives_from(&result.kconfig, kconfig_file))
+        .map(|d| (d.severity, d.to_string()))
+        .collect();
+    let table = SymbolTable::new(&result.kconfig);
+    reports.extend(
+        linter
+            .lint_table(&table)
+            .iter()
+            .map(|l| (l.severity, l.to_string())),
+    );
+    reports.extend(
+        find_cycles(&table)
+            .iter()
+            .map(|c| c.to_diagnostic())
+            .map(|d| (d.severity, d.to_string())),
+    );
+
+    let count = |severity: Severity| reports.iter().filter(|(s, _)| *s == severity).count();
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+    let mut stdout = String::new();
+    for (_, report) in &reports {
+        stdout.push_str(report);
+        stdout.push_str("\n\n");
+    }
+    stdout.push_str(&format!(
+        "{}, {}\n",
+        plural(errors, "error"),
+        plural(warnings, "warning")
+    ));
+    Ok(Output {
+        stdout,
+        success: errors == 0 && (!deny_warnings || warnings == 0),
+    })
+}
+
+fn plural(count: usize, word: &str) -> String {
+    match count {
+        1 => format!("1 {}", word),
+        _ => format!("{} {}s", count, word),
+    }
+}
+
+/// Formats the file itself, `source` statements are kept as is.
+/// The file is not preprocessed so that macro references like `$(SRCARCH)` are kept as written.
+/// Comments and blank lines are kept.
+pub fn fmt(kconfig_file: &KconfigFile, options: &FormatOptions, check: bool) -> Result<Output> {
+    let kconfig_file = KconfigFile {
+        global_vars: Default::default(),
+        local_vars: Default::default(),
+        source_mode: SourceMode::Lazy,
+        ..kconfig_file.clone()
+    };
+    let path = kconfig_file.full_path();
+    let content = kconfig_file
+        .vfs
+        .read_to_string(&path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let (_, cst) = parse_kconfig_lossless(KconfigInput::new_extra(&content, kconfig_file.clone()))?;
+    let formatted = format_cst(&cst, options);
+    Ok(match check {
+        true if formatted == content => Output::ok(String::new()),
+        true => Output {
+            stdout: format!("{} is not formatted\n", kconfig_file.file.display()),
+            success: false,
+        },
+        false => Output::ok(formatted),
+    })
+}
+
+/// One line per symbol: name, type, location of the first definition and prompt.
+pub fn symbols(kconfig_file: &KconfigFile) -> Result<Output> {
+    let table = SymbolTable::new(&load(kconfig_file)?);
+    let width = table.symbols().map(|s| s.name.len()).max().unwrap_or(0);
+    let mut stdout = String::new();
+    for symbol in table.symbols() {
+        let r#type = symbol
+            .r#type()
+            .map_or("unknown".to_string(), |t| t.to_string());
+        let location = symbol
+            .definitions
+            .first()
+            .map(|d| format!("{}:{}", d.span.file, d.span.line))
+            .unwrap_or_default();
+        let line = match symbol.prompts.first() {
+            Some(prompt) => format!(
+                "{:width$} {:8} {} \"{}\"",
+                symbol.name,
+                r#type,
+                location,
+                prompt.value.prompt,
+                width = width
+            ),
+            None => format!(
+                "{:width$} {:8} {}",
+                symbol.name,
+                r#type,
+                location,
+                width = width
+            ),
+        };
+        stdout.push_str(&line);
+        stdout.push('\n');
+    }
+    Ok(Output::ok(stdout))
+}
+
+/// The symbols `symbol` refers to, or the ones referring to it, through at most `depth` edges.
+pub fn deps(
+    kconfig_file: &KconfigFile,
+    symbol: &str,
+    reverse: bool,
+    depth: usize,
+    format: GraphFormat,
+) -> Result<Output> {
+    let table = SymbolTable::new(&load(kconfig_file)?);
+    let name = table.get(symbol).map_or(symbol, |s| s.name.as_str());
+    let graph = DependencyGraph::new(&table);
+    let subgraph = match reverse {
+        true => graph.ancestors(name, depth),
+        false => graph.descendants(name, depth),
+    }
+    .ok_or_else(|| format!("unknown symbol {}", symbol))?;
+    Ok(Output::ok(export(&subgraph, format)))
+}
+
+/// Evaluates an expression against the configuration read from `config`, or the default values.
+pub fn eval(kconfig_file: &KconfigFile, config: Option<&Path>, expression: &str) -> Result<Output> {
+    let kconfig = load(kconfig_file)?;
+    let config = config
+        .map(|path| DotConfig::from_file(path, DEFAULT_PREFIX))
+        .transpose()?;
+    let (remaining, expression) = ws(parse_expression)
+        .parse(KconfigInput::new_extra(expression, Default::default()))
+        .map_err(|_| format!("invalid expression '{}'", expression))?;
+    if !remaining.fragment().is_empty() {
+        return Err(format!("unexpected '{}' in the expression", remaining.fragment()).into());
+    }
+    let configuration = resolve(&kconfig, config.as_ref());
+    Ok(Output::ok(format!(
+        "{}\n",
+        evaluate(&expression, &configuration)
+    )))
+}
+
+pub fn graph(kconfig_file: &KconfigFile, format: GraphFormat) -> Result<Output> {
+    let graph = DependencyGraph::from(&load(kconfig_file)?);
+    Ok(Output::ok(export(&graph, format)))
+}
+
+fn export(graph: &DependencyGraph, format: GraphFormat) -> String {
+    match format {
+        GraphFormat::Text => graph
+            .edges()
+            .iter()
+            .map(|edge| {
+                format!(
+                    "{} -> {} ({})\n",
+                    graph.nodes()[edge.from].name,
+                    graph.nodes()[edge.to].name,
+                    edge.label()
+                )
+            })
+            .collect(),
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Graphml => graph.to_graphml(),
+        GraphFormat::Json => graph.to_json(),
+    }
+}
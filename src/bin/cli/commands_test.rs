@@ -0,0 +1,183 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use nom_kconfig::{format::FormatOptions, vfs::MemoryFs, KconfigFile};
+
+use crate::commands::{self, GraphFormat};
+
+const KCONFIG: &str = r#"mainmenu "Demo"
+
+config NET
+	bool "Networking"
+	default y
+	select CRC
+	help
+	  Networking support.
+
+config CRC
+	tristate
+
+source "arch/$(SRCARCH)/Kconfig"
+"#;
+
+const ARCH: &str = r#"config X86
+	def_bool y
+	imply INET
+
+config INET
+	bool "TCP/IP"
+	depends on NET
+	help
+	  The Internet protocols.
+"#;
+
+fn kconfig_file(kconfig: &str) -> KconfigFile {
+    let mut fs = MemoryFs::new();
+    fs.insert("/src/Kconfig", kconfig);
+    fs.insert("/src/arch/x86/Kconfig", ARCH);
+    let variables = HashMap::from([("SRCARCH", "x86")]);
+    KconfigFile::new_with_vars(
+        PathBuf::from("/src"),
+        PathBuf::from("Kconfig"),
+        &variables,
+        &HashMap::new(),
+    )
+    .with_vfs(fs)
+}
+
+#[test]
+fn test_parse() {
+    let output = commands::parse(&kconfig_file(KCONFIG)).unwrap();
+    assert!(output.success);
+    assert!(output
+        .stdout
+        .starts_with("{\n  \"file\": \"Kconfig\",\n  \"entries\": [\n"));
+    assert!(output.stdout.contains("\"file\": \"arch/x86/Kconfig\""));
+    assert!(commands::parse(&kconfig_file("config\n")).is_err());
+}
+
+#[test]
+fn test_check() {
+    let output = commands::check(&kconfig_file(KCONFIG), false).unwrap();
+    assert_eq!(output.stdout, "0 errors, 0 warnings\n");
+    assert!(output.success);
+
+    let input = "config A\n\tbool \"a\"\n\tselekt B\n\nconfig B\n\tbool \"b\"\n\tdepends on B\n";
+    let output = commands::check(&kconfig_file(input), false).unwrap();
+    assert!(!output.success);
+    assert!(output
+        .stdout
+        .starts_with("error: unknown keyword `selekt`\n  --> Kconfig:3:2\n\n"));
+    assert!(output
+        .stdout
+        .contains("warning[missing_help]: `A` has a prompt but no help text"));
+    assert!(output.stdout.ends_with("\n\n2 errors, 2 warnings\n"));
+
+    let input = "config A\n\tbool \"a\"\n";
+    assert!(
+        commands::check(&kconfig_file(input), false)
+            .unwrap()
+            .success
+    );
+    assert!(!commands::check(&kconfig_file(input), true).unwrap().success);
+}
+
+#[test]
+fn test_fmt() {
+    let input = "config NET\n    bool \"Networking\"\nsource \"arch/$(SRCARCH)/Kconfig\"\n";
+    let output = commands::fmt(&kconfig_file(input), &FormatOptions::default(), false).unwrap();
+    assert_eq!(
+        output.stdout,
+        "config NET\n\tbool \"Networking\"\n\nsource \"arch/$(SRCARCH)/Kconfig\"\n"
+    );
+
+    let output = commands::fmt(&kconfig_file(input), &FormatOptions::default(), true).unwrap();
+    assert_eq!(output.stdout, "Kconfig is not formatted\n");
+    assert!(!output.success);
+    let output = commands::fmt(&kconfig_file(KCONFIG), &FormatOptions::default(), true).unwrap();
+    assert_eq!(output.stdout, "");
+    assert!(output.success);
+}
+
+#[test]
+fn test_fmt_keeps_macros() {
+    let input = "FOO := bar\n\nconfig A\n\tstring\n\tdefault \"$(FOO)\"\n";
+    let output = commands::fmt(&kconfig_file(input), &FormatOptions::default(), false).unwrap();
+    assert_eq!(output.stdout, input);
+    let output = commands::fmt(&kconfig_file(input), &FormatOptions::default(), true).unwrap();
+    assert!(output.success);
+}
+
+#[test]
+fn test_fmt_keeps_comments() {
+    let input = "# SPDX-License-Identifier: GPL-2.0\nconfig A\n  bool\n";
+    let output = commands::fmt(&kconfig_file(input), &FormatOptions::default(), false).unwrap();
+    assert_eq!(
+        output.stdout,
+        "# SPDX-License-Identifier: GPL-2.0\nconfig A\n\tbool\n"
+    );
+}
+
+#[test]
+fn test_symbols() {
+    let output = commands::symbols(&kconfig_file(KCONFIG)).unwrap();
+    assert_eq!(
+        output.stdout,
+        r#"NET  bool     Kconfig:3 "Networking"
+CRC  tristate Kconfig:10
+X86  bool     arch/x86/Kconfig:1
+INET bool     arch/x86/Kconfig:5 "TCP/IP"
+"#
+    );
+}
+
+#[test]
+fn test_deps() {
+    let file = kconfig_file(KCONFIG);
+    let output = commands::deps(&file, "CONFIG_NET", false, 1, GraphFormat::Text).unwrap();
+    assert_eq!(output.stdout, "NET -> CRC (select)\n");
+    let output = commands::deps(&file, "INET", true, 1, GraphFormat::Text).unwrap();
+    assert_eq!(output.stdout, "X86 -> INET (imply)\n");
+    let output = commands::deps(&file, "INET", false, 2, GraphFormat::Dot).unwrap();
+    assert!(output
+        .stdout
+        .contains("\"INET\" -> \"NET\" [label=\"depends on\"];"));
+    assert!(output
+        .stdout
+        .contains("\"NET\" -> \"CRC\" [label=\"select\"];"));
+    assert_eq!(
+        commands::deps(&file, "NOPE", false, 1, GraphFormat::Text)
+            .unwrap_err()
+            .to_string(),
+        "unknown symbol NOPE"
+    );
+}
+
+#[test]
+fn test_eval() {
+    let file = kconfig_file(KCONFIG);
+    let eval = |expression: &str| commands::eval(&file, None, expression).map(|o| o.stdout);
+    assert_eq!(eval("NET && INET").unwrap(), "y\n");
+    assert_eq!(eval("CRC").unwrap(), "y\n");
+    assert_eq!(eval("!NET || FOO").unwrap(), "n\n");
+    assert!(eval("NET &&").is_err());
+
+    let config = std::env::temp_dir().join(format!("nom-kconfig-eval-{}", std::process::id()));
+    std::fs::write(&config, "CONFIG_NET=y\n# CONFIG_INET is not set\n").unwrap();
+    let output = commands::eval(&file, Some(&config), "NET && !INET");
+    std::fs::remove_file(&config).unwrap();
+    assert_eq!(output.unwrap().stdout, "y\n");
+}
+
+#[test]
+fn test_graph() {
+    let file = kconfig_file(KCONFIG);
+    let output = commands::graph(&file, GraphFormat::Text).unwrap();
+    assert_eq!(
+        output.stdout,
+        "NET -> CRC (select)\nX86 -> INET (imply)\nINET -> NET (depends on)\n"
+    );
+    let output = commands::graph(&file, GraphFormat::Json).unwrap();
+    assert!(output.stdout.starts_with("{\n  \"nodes\": [\n"));
+    let output = commands::graph(&file, GraphFormat::Graphml).unwrap();
+    assert!(output.stdout.contains("<graphml"));
+}
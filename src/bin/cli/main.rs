@@ -0,0 +1,180 @@
+//! Command-line tool to inspect Kconfig trees.
+//!
+//! ```shell
+//! cargo run --features cli --bin nom-kconfig -- check --root /tmp/linux-6.4.9 --srcarch x86
+//! cargo run --features cli --bin nom-kconfig -- eval --root /tmp/linux-6.4.9 --srcarch x86 --config .config 'NET && !EXPERT'
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand};
+use nom_kconfig::{
+    format::{FormatOptions, Indent},
+    kconfig_file::{command_line_variables, parse_variable},
+    KconfigFile,
+};
+
+use crate::commands::GraphFormat;
+
+mod commands;
+#[cfg(test)]
+mod commands_test;
+
+#[derive(Parser)]
+#[command(
+    author,
+    version,
+    about = "Parse, check, format and query Kconfig files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the AST as JSON.
+    Parse {
+        #[command(flatten)]
+        tree: Tree,
+    },
+    /// Report syntax errors, lints and recursive dependencies. Fails if there is an error.
+    Check {
+        #[command(flatten)]
+        tree: Tree,
+        /// Fail on warnings too.
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+    /// Print the file in the canonical format. Sourced files are not formatted, comments are kept.
+    Fmt {
+        #[command(flatten)]
+        tree: Tree,
+        /// Print nothing, fail if the file is not formatted.
+        #[arg(long)]
+        check: bool,
+        /// Indent with spaces instead of a tab.
+        #[arg(long, value_name = "N")]
+        spaces: Option<usize>,
+    },
+    /// List the symbols with their type, location and prompt.
+    Symbols {
+        #[command(flatten)]
+        tree: Tree,
+    },
+    /// Print what a symbol depends on, selects, implies... or, with `--reverse`, what refers to it.
+    Deps {
+        #[command(flatten)]
+        tree: Tree,
+        symbol: String,
+        #[arg(long)]
+        reverse: bool,
+        /// Maximum number of edges to follow.
+        #[arg(long, default_value_t = 1)]
+        depth: usize,
+        #[arg(long, value_enum, default_value_t = GraphFormat::Text)]
+        format: GraphFormat,
+    },
+    /// Evaluate an expression, printing `y`, `m` or `n`.
+    Eval {
+        #[command(flatten)]
+        tree: Tree,
+        /// The `.config` file, default values are used when omitted.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        expression: String,
+    },
+    /// Print the dependency graph.
+    Graph {
+        #[command(flatten)]
+        tree: Tree,
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+    },
+}
+
+/// Arguments locating the Kconfig tree, shared by every command.
+#[derive(Args)]
+struct Tree {
+    /// The Kconfig file, relative to the root directory.
+    #[arg(long, default_value = "Kconfig")]
+    kconfig: PathBuf,
+    /// Root directory of the tree, `source` statements are relative to it.
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    /// A variable of the Kconfig files, can be repeated.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+    /// Shortcut for `--var SRCARCH=<ARCH> --var ARCH=<ARCH>`.
+    #[arg(long)]
+    srcarch: Option<String>,
+}
+
+impl Tree {
+    fn kconfig_file(&self) -> KconfigFile {
+        let variables = command_line_variables(self.srcarch.as_deref(), &self.variables);
+        KconfigFile::new_with_vars(
+            self.root.clone(),
+            self.kconfig.clone(),
+            &variables,
+            &HashMap::new(),
+        )
+    }
+}
+
+fn run(command: Command) -> commands::Result<commands::Output> {
+    match command {
+        Command::Parse { tree } => commands::parse(&tree.kconfig_file()),
+        Command::Check {
+            tree,
+            deny_warnings,
+        } => commands::check(&tree.kconfig_file(), deny_warnings),
+        Command::Fmt {
+            tree,
+            check,
+            spaces,
+        } => {
+            let options = FormatOptions {
+                indent: spaces.map_or(Indent::Tab, Indent::Spaces),
+                ..Default::default()
+            };
+            commands::fmt(&tree.kconfig_file(), &options, check)
+        }
+        Command::Symbols { tree } => commands::symbols(&tree.kconfig_file()),
+        Command::Deps {
+            tree,
+            symbol,
+            reverse,
+            depth,
+            format,
+        } => commands::deps(&tree.kconfig_file(), &symbol, reverse, depth, format),
+        Command::Eval {
+            tree,
+            config,
+            expression,
+        } => commands::eval(&tree.kconfig_file(), config.as_deref(), &expression),
+        Command::Graph { tree, format } => commands::graph(&tree.kconfig_file(), format),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(output) => {
+            let _ = io::stdout().write_all(output.stdout.as_bytes());
+            match output.success {
+                true => ExitCode::SUCCESS,
+                false => ExitCode::FAILURE,
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
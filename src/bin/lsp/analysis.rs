@@ -0,0 +1,719 @@
+//! What the server knows about a Kconfig tree: the parsed files, the symbols and the problems found.
+//!
+//! Files are identified by their path relative to the root directory, like [Span::file].
+//! Positions count characters, which matches the UTF-16 positions of the protocol as long as files are ASCII.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, DocumentSymbol,
+    NumberOrString, Position, Range, SymbolKind,
+};
+use nom_kconfig::{
+    attribute::{r#type::Type, range::RangeBound, Attribute, Expression},
+    cst::parse_kconfig_lossless,
+    cycle::find_cycles,
+    diagnostic::{parse_kconfig_with_diagnostics, Severity},
+    entry::SourceMode,
+    format::{format_cst, FormatOptions},
+    lint::Linter,
+    span::Span,
+    symbol_table::SymbolRecord,
+    vfs::{normalize, RealFs, Vfs},
+    Entry, Kconfig, KconfigFile, KconfigInput, Symbol, SymbolTable,
+};
+
+/// Keywords starting an entry.
+pub const ENTRY_KEYWORDS: [&str; 11] = [
+    "config",
+    "menuconfig",
+    "choice",
+    "endchoice",
+    "comment",
+    "menu",
+    "endmenu",
+    "if",
+    "endif",
+    "source",
+    "mainmenu",
+];
+
+/// Keywords starting an attribute.
+pub const ATTRIBUTE_KEYWORDS: [&str; 17] = [
+    "bool",
+    "tristate",
+    "int",
+    "hex",
+    "string",
+    "prompt",
+    "default",
+    "def_bool",
+    "def_tristate",
+    "depends on",
+    "select",
+    "imply",
+    "visible if",
+    "range",
+    "help",
+    "modules",
+    "option",
+];
+
+/// The filesystem, where the documents opened in the editor replace the saved files.
+#[derive(Debug, Clone, Default)]
+pub struct OverlayFs {
+    documents: HashMap<PathBuf, String>,
+}
+
+impl OverlayFs {
+    pub fn new(documents: &HashMap<PathBuf, String>) -> Self {
+        Self {
+            documents: documents
+                .iter()
+                .map(|(path, content)| (normalize(path), content.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Vfs for OverlayFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self.documents.get(&normalize(path)) {
+            Some(content) => Ok(content.clone()),
+            None => RealFs.read_to_string(path),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.documents.contains_key(&normalize(path)) || RealFs.exists(path)
+    }
+
+    fn glob(&self, pattern: &Path) -> io::Result<Vec<PathBuf>> {
+        RealFs.glob(pattern)
+    }
+
+    fn canonicalize(&self, path: &Path) -> PathBuf {
+        RealFs.canonicalize(path)
+    }
+}
+
+/// An occurrence of a symbol in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reference {
+    symbol: String,
+    range: Range,
+    /// `true` for the name following `config`, `menuconfig` or `configdefault`.
+    definition: bool,
+}
+
+/// The occurrences of the symbols in the files of a tree, located with the spans of the entries and attributes.
+/// `contents` are the files of the tree, as read from the disk.
+fn references(
+    kconfig: &Kconfig,
+    contents: &HashMap<String, String>,
+) -> HashMap<String, Vec<Reference>> {
+    let mut references: HashMap<String, Vec<Reference>> = HashMap::new();
+    visit(kconfig, &mut |entry| {
+        let Some(content) = contents.get(&entry.span().file) else {
+            return;
+        };
+        let mut locate = |span: &Span, symbols: Vec<&str>, definition: bool, header: bool| {
+            let start = offset(
+                content,
+                Position::new(
+                    span.line.saturating_sub(1),
+                    span.column.saturating_sub(1) as u32,
+                ),
+            );
+            let text = content[start..]
+                .get(..span.length)
+                .unwrap_or_else(|| first_line(&content[start..]));
+            let text = match header {
+                true => first_line(text),
+                false => text,
+            };
+            let file = references.entry(span.file.clone()).or_default();
+            for symbol in symbols {
+                for index in occurrences(text, symbol) {
+                    let index = start + index;
+                    file.push(Reference {
+                        symbol: symbol.to_string(),
+                        range: Range::new(
+                            end_position(&content[..index]),
+                            end_position(&content[..index + symbol.len()]),
+                        ),
+                        definition,
+                    });
+                }
+            }
+        };
+        let attributes: Vec<Attribute> = match entry {
+            Entry::Config(config) | Entry::MenuConfig(config) => {
+                locate(&config.span, vec![&config.symbol], true, true);
+                config.attributes.clone()
+            }
+            #[cfg(feature = "kconfiglib")]
+            Entry::ConfigDefault(config_default) => {
+                locate(
+                    &config_default.span,
+                    vec![&config_default.symbol],
+                    true,
+                    true,
+                );
+                vec![Attribute::Default(config_default.default.clone())]
+            }
+            Entry::Choice(choice) => choice.options.clone(),
+            Entry::Menu(menu) => menu
+                .visible
+                .iter()
+                .cloned()
+                .map(Attribute::Visible)
+                .chain(menu.depends_on.iter().cloned().map(Attribute::DependsOn))
+                .collect(),
+            Entry::Comment(comment) => comment
+                .dependencies
+                .iter()
+                .cloned()
+                .map(Attribute::DependsOn)
+                .collect(),
+            Entry::If(r#if) => {
+                locate(&r#if.span, r#if.condition.symbols(), false, true);
+                vec![]
+            }
+            _ => vec![],
+        };
+        for attribute in &attributes {
+            locate(attribute.span(), attribute_symbols(attribute), false, false);
+        }
+    });
+    for file in references.values_mut() {
+        file.sort_by_key(|reference| (reference.range.start.line, reference.range.start.character));
+        file.dedup();
+    }
+    references
+}
+
+/// Names of the symbols referenced by an attribute, without duplicates.
+fn attribute_symbols(attribute: &Attribute) -> Vec<&str> {
+    let (symbols, condition): (Vec<&str>, &Option<Expression>) = match attribute {
+        Attribute::Prompt(prompt) => (vec![], &prompt.r#if),
+        Attribute::Select(select) => (vec![&select.symbol], &select.r#if),
+        Attribute::Imply(imply) => (
+            match &imply.symbol {
+                Symbol::NonConstant(name) => vec![name],
+                Symbol::Constant(_) => vec![],
+            },
+            &imply.r#if,
+        ),
+        Attribute::DependsOn(depends_on) => (depends_on.expression.symbols(), &depends_on.r#if),
+        Attribute::Range(range) => (
+            [&range.lower_bound, &range.upper_bound]
+                .into_iter()
+                .filter_map(|bound| match bound {
+                    RangeBound::Symbol(name) => Some(name.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            &range.r#if,
+        ),
+        Attribute::Visible(visible) => (vec![], &visible.r#if),
+        Attribute::Default(default) => (default.expression.symbols(), &default.r#if),
+        Attribute::Requires(expression, _) => (expression.symbols(), &None),
+        Attribute::Type(config_type) => (
+            match &config_type.r#type {
+                Type::DefBool(expression) | Type::DefTristate(expression) => expression.symbols(),
+                #[cfg(feature = "kconfiglib")]
+                Type::DefInt(expression)
+                | Type::DefHex(expression)
+                | Type::DefString(expression) => expression.symbols(),
+                _ => vec![],
+            },
+            &config_type.r#if,
+        ),
+        _ => (vec![], &None),
+    };
+    let mut symbols = symbols;
+    for symbol in condition.iter().flat_map(|e| e.symbols()) {
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+    symbols
+}
+
+/// The first line of a text, continued lines included.
+fn first_line(text: &str) -> &str {
+    let mut end = 0;
+    for line in text.split_inclusive('\n') {
+        end += line.len();
+        if !line.trim_end().ends_with('\\') {
+            break;
+        }
+    }
+    &text[..end]
+}
+
+/// Byte offsets of a word in a text, strings excluded.
+fn occurrences(text: &str, word: &str) -> Vec<usize> {
+    let mut occurrences = vec![];
+    let mut quote = None;
+    let mut previous = None;
+    for (index, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q && previous != Some('\\') => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if text[index..].starts_with(word)
+                && !previous.is_some_and(is_word)
+                && !text[index + word.len()..].starts_with(is_word) =>
+            {
+                occurrences.push(index)
+            }
+            None => {}
+        }
+        previous = Some(c);
+    }
+    occurrences
+}
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The position right after the last character.
+pub fn end_position(content: &str) -> Position {
+    let line = content.matches('\n').count();
+    let character = content
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count();
+    Position::new(line as u32, character as u32)
+}
+
+/// Formats a file, without its sourced files. The file is not preprocessed so that macro references are kept.
+/// Comments and blank lines are kept.
+pub fn format(kconfig_file: &KconfigFile, options: &FormatOptions) -> Result<String, String> {
+    let kconfig_file = KconfigFile {
+        global_vars: Default::default(),
+        local_vars: Default::default(),
+        source_mode: SourceMode::Lazy,
+        ..kconfig_file.clone()
+    };
+    let content = kconfig_file
+        .vfs
+        .read_to_string(&kconfig_file.full_path())
+        .map_err(|e| e.to_string())?;
+    let (_, cst) = parse_kconfig_lossless(KconfigInput::new_extra(&content, kconfig_file))
+        .map_err(|e| e.to_string())?;
+    Ok(format_cst(&cst, options))
+}
+
+/// A parsed tree.
+#[derive(Debug)]
+pub struct Analysis {
+    pub kconfig: Kconfig,
+    pub table: SymbolTable,
+    /// The problems of every file of the tree. Files without problems have an empty list.
+    pub diagnostics: HashMap<String, Vec<Diagnostic>>,
+    /// The content of every file of the tree.
+    contents: HashMap<String, String>,
+    /// The occurrences of the symbols in every file of the tree.
+    references: HashMap<String, Vec<Reference>>,
+}
+
+impl Analysis {
+    /// Parses the tree recovering from errors, then runs the lints and looks for recursive dependencies.
+    pub fn new(kconfig_file: &KconfigFile) -> io::Result<Self> {
+        let content = kconfig_file.read_to_string()?;
+        let result =
+            parse_kconfig_with_diagnostics(KconfigInput::new_extra(&content, kconfig_file.clone()));
+        let kconfig = result.kconfig;
+        let table = SymbolTable::new(&kconfig);
+        let contents: HashMap<String, String> = kconfig
+            .files()
+            .into_iter()
+            .filter_map(|file| {
+                let content = kconfig_file
+                    .vfs
+                    .read_to_string(&kconfig_file.root_dir.join(file))
+                    .ok()?;
+                Some((file.to_string(), content))
+            })
+            .collect();
+
+        let mut linter = Linter::default();
+        let mut problems: Vec<(nom_kconfig::diagnostic::Diagnostic, Option<String>)> = result
+            .diagnostics
+            .into_iter()
+            .chain(linter.read_directives_from(&kconfig, kconfig_file))
+            .map(|d| (d, None))
+            .collect();
+        problems.extend(linter.lint_table(&table).into_iter().map(|lint| {
+            let diagnostic = nom_kconfig::diagnostic::Diagnostic {
+                severity: lint.severity,
+                message: lint.message,
+                span: lint.span,
+                include_chain: vec![],
+            };
+            (diagnostic, Some(lint.rule.id().to_string()))
+        }));
+        problems.extend(
+            find_cycles(&table)
+                .iter()
+                .map(|c| (c.to_diagnostic(), None)),
+        );
+
+        let references = references(&kconfig, &contents);
+        let mut analysis = Self {
+            kconfig,
+            table,
+            diagnostics: HashMap::new(),
+            contents,
+            references,
+        };
+        for file in analysis.contents.keys() {
+            analysis.diagnostics.insert(file.clone(), vec![]);
+        }
+        for (problem, code) in problems {
+            let diagnostic = Diagnostic {
+                range: analysis.span_range(&problem.span),
+                severity: Some(match problem.severity {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                    Severity::Info => DiagnosticSeverity::INFORMATION,
+                }),
+                code: code.map(NumberOrString::String),
+                source: Some("nom-kconfig".to_string()),
+                message: problem.message,
+                ..Default::default()
+            };
+            analysis
+                .diagnostics
+                .entry(problem.span.file)
+                .or_default()
+                .push(diagnostic);
+        }
+        Ok(analysis)
+    }
+
+    /// Returns `true` if the file is part of the tree.
+    pub fn contains(&self, file: &str) -> bool {
+        self.contents.contains_key(file)
+    }
+
+    /// The content a file of the tree had when it was parsed.
+    pub fn content(&self, file: &str) -> Option<&str> {
+        self.contents.get(file).map(String::as_str)
+    }
+
+    /// The range of the first line of a span.
+    fn span_range(&self, span: &Span) -> Range {
+        let start = Position::new(
+            span.line.saturating_sub(1),
+            span.column.saturating_sub(1) as u32,
+        );
+        let Some(line) = self
+            .contents
+            .get(&span.file)
+            .and_then(|content| content.lines().nth(start.line as usize))
+        else {
+            return Range::new(start, start);
+        };
+        let mut length = 0;
+        let mut end = start.character;
+        for c in line.chars().skip(start.character as usize) {
+            if length >= span.length {
+                break;
+            }
+            length += c.len_utf8();
+            end += 1;
+        }
+        Range::new(start, Position::new(start.line, end))
+    }
+
+    /// The occurrences of the symbols in a file, empty if the file is not part of the tree.
+    fn file_references(&self, file: &str) -> &[Reference] {
+        self.references.get(file).map_or(&[], Vec::as_slice)
+    }
+
+    /// The definition of a symbol starting at a line.
+    fn definition_at(&self, file: &str, symbol: &str, line: u32) -> Option<&Reference> {
+        self.file_references(file)
+            .iter()
+            .find(|r| r.definition && r.symbol == symbol && r.range.start.line == line)
+    }
+
+    /// The symbol under the cursor.
+    pub fn symbol_at(&self, file: &str, position: Position) -> Option<(&SymbolRecord, Range)> {
+        let reference = self.file_references(file).iter().find(|reference| {
+            reference.range.start.line == position.line
+                && reference.range.start.character <= position.character
+                && position.character <= reference.range.end.character
+        })?;
+        let record = self.table.get(&reference.symbol)?;
+        Some((record, reference.range))
+    }
+
+    /// The definitions of the symbol under the cursor, or the files included by the `source` statement under the cursor.
+    pub fn definition(&self, file: &str, position: Position) -> Vec<(String, Range)> {
+        if let Some((record, _)) = self.symbol_at(file, position) {
+            return record
+                .definitions
+                .iter()
+                .map(|definition| {
+                    let line = definition.span.line.saturating_sub(1);
+                    let range = self
+                        .definition_at(&definition.file, &record.name, line)
+                        .map_or_else(|| self.span_range(&definition.span), |r| r.range);
+                    (definition.file.clone(), range)
+                })
+                .collect();
+        }
+        let mut files = vec![];
+        visit(&self.kconfig, &mut |entry| {
+            if let Some(source) = source(entry) {
+                if source.span.file == file && source.span.line == position.line + 1 {
+                    files.extend(
+                        source
+                            .kconfigs
+                            .iter()
+                            .map(|k| (k.file.clone(), Range::default())),
+                    );
+                }
+            }
+        });
+        files
+    }
+
+    /// Every occurrence of a symbol in the tree.
+    pub fn references(&self, name: &str, include_declaration: bool) -> Vec<(String, Range)> {
+        let mut files: Vec<&String> = self.contents.keys().collect();
+        files.sort();
+        let mut references = vec![];
+        for file in files {
+            references.extend(
+                self.file_references(file)
+                    .iter()
+                    .filter(|r| r.symbol == name && (include_declaration || !r.definition))
+                    .map(|r| (file.clone(), r.range)),
+            );
+        }
+        references
+    }
+
+    /// The type, prompt, dependencies, help and location of the symbol under the cursor, in markdown.
+    pub fn hover(&self, file: &str, position: Position) -> Option<(String, Range)> {
+        let (record, range) = self.symbol_at(file, position)?;
+        let mut definition = format!("config {}", record.name);
+        if let Some(r#type) = record.r#type() {
+            definition.push_str(&format!("\n\t{}", r#type));
+            if let Some(prompt) = record.prompts.first() {
+                definition.push_str(&format!(" \"{}\"", prompt.value.prompt));
+            }
+        }
+        if let Some(dependencies) = record.dependencies() {
+            definition.push_str(&format!("\n\tdepends on {}", dependencies));
+        }
+        let mut markdown = format!("```kconfig\n{}\n```", definition);
+        if let Some(help) = record.help.first() {
+            markdown.push_str(&format!("\n\n{}", help.value.trim()));
+        }
+        if !record.selected_by.is_empty() {
+            let symbols: Vec<String> = record
+                .selected_by
+                .iter()
+                .map(|s| format!("`{}`", s.symbol))
+                .collect();
+            markdown.push_str(&format!("\n\nSelected by {}", symbols.join(", ")));
+        }
+        let locations: Vec<String> = record
+            .definitions
+            .iter()
+            .map(|d| format!("{}:{}", d.file, d.span.line))
+            .collect();
+        if !locations.is_empty() {
+            markdown.push_str(&format!("\n\nDefined at {}", locations.join(", ")));
+        }
+        Some((markdown, range))
+    }
+
+    /// Keywords when typing the first word of a line, symbols otherwise.
+    pub fn completion(&self, file: &str, position: Position) -> Vec<CompletionItem> {
+        let line = self
+            .contents
+            .get(file)
+            .and_then(|content| content.lines().nth(position.line as usize))
+            .unwrap_or_default();
+        let before: String = line.chars().take(position.character as usize).collect();
+        if !before.trim_start().contains(char::is_whitespace) {
+            let keywords = match before.starts_with(char::is_whitespace) {
+                true => ATTRIBUTE_KEYWORDS.iter().chain(ENTRY_KEYWORDS.iter()),
+                false => ENTRY_KEYWORDS.iter().chain(ATTRIBUTE_KEYWORDS.iter()),
+            };
+            return keywords
+                .map(|keyword| CompletionItem {
+                    label: keyword.to_string(),
+                    kind: Some(CompletionItemKind::KEYWORD),
+                    ..Default::default()
+                })
+                .collect();
+        }
+        self.table
+            .symbols()
+            .map(|record| CompletionItem {
+                label: record.name.clone(),
+                kind: Some(CompletionItemKind::VARIABLE),
+                detail: record.r#type().map(|t| match record.prompts.first() {
+                    Some(prompt) => format!("{} \"{}\"", t, prompt.value.prompt),
+                    None => t.to_string(),
+                }),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// The menus, choices and configs of a file, nested like in the file. Entries of `if` blocks belong to the enclosing menu.
+    pub fn document_symbols(&self, file: &str) -> Vec<DocumentSymbol> {
+        let mut kconfig = None;
+        visit_files(&self.kconfig, &mut |k| {
+            if k.file == file && kconfig.is_none() {
+                kconfig = Some(k);
+            }
+        });
+        kconfig.map_or(vec![], |kconfig| self.symbols(&kconfig.entries))
+    }
+
+    fn symbols(&self, entries: &[Entry]) -> Vec<DocumentSymbol> {
+        let mut symbols = vec![];
+        for entry in entries {
+            let (name, kind, span, children) = match entry {
+                Entry::Config(config) | Entry::MenuConfig(config) => (
+                    config.symbol.clone(),
+                    SymbolKind::VARIABLE,
+                    &config.span,
+                    vec![],
+                ),
+                Entry::Menu(menu) => (
+                    menu.prompt.clone(),
+                    SymbolKind::NAMESPACE,
+                    &menu.span,
+                    self.symbols(&menu.entries),
+                ),
+                Entry::Choice(choice) => (
+                    choice_name(choice),
+                    SymbolKind::ENUM,
+                    &choice.span,
+                    self.symbols(&choice.entries),
+                ),
+                Entry::If(r#if) => {
+                    symbols.extend(self.symbols(&r#if.entries));
+                    continue;
+                }
+                _ => continue,
+            };
+            let start = self.span_range(span).start;
+            let selection_range = self
+                .definition_at(&span.file, &name, start.line)
+                .map_or_else(|| self.span_range(span), |r| r.range);
+            let end = self
+                .contents
+                .get(&span.file)
+                .and_then(|content| content.get(offset(content, start)..))
+                .and_then(|rest| rest.get(..span.length))
+                .map_or(selection_range.end, |text| {
+                    let end = end_position(text.trim_end());
+                    match end.line {
+                        0 => Position::new(start.line, start.character + end.character),
+                        _ => Position::new(start.line + end.line, end.character),
+                    }
+                });
+            #[allow(deprecated)]
+            symbols.push(DocumentSymbol {
+                name,
+                detail: None,
+                kind,
+                tags: None,
+                deprecated: None,
+                range: Range::new(start, end),
+                selection_range,
+                children: match children.is_empty() {
+                    true => None,
+                    false => Some(children),
+                },
+            });
+        }
+        symbols
+    }
+}
+
+#[cfg(feature = "named-choice")]
+fn choice_name(choice: &nom_kconfig::entry::Choice) -> String {
+    choice.name.clone().unwrap_or_else(|| "choice".to_string())
+}
+
+#[cfg(not(feature = "named-choice"))]
+fn choice_name(_choice: &nom_kconfig::entry::Choice) -> String {
+    "choice".to_string()
+}
+
+/// Byte offset of a position.
+fn offset(content: &str, position: Position) -> usize {
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    line_start
+        + content[line_start..]
+            .chars()
+            .take(position.character as usize)
+            .map(char::len_utf8)
+            .sum::<usize>()
+}
+
+fn source(entry: &Entry) -> Option<&nom_kconfig::entry::Source> {
+    match entry {
+        Entry::Source(source) => Some(source),
+        #[cfg(feature = "kconfiglib")]
+        Entry::OSource(source) | Entry::RSource(source) | Entry::OrSource(source) => Some(source),
+        _ => None,
+    }
+}
+
+/// Calls `f` on every entry of the tree, sourced files included.
+fn visit<'a>(kconfig: &'a Kconfig, f: &mut impl FnMut(&'a Entry)) {
+    fn walk<'a>(entries: &'a [Entry], f: &mut impl FnMut(&'a Entry)) {
+        for entry in entries {
+            f(entry);
+            match entry {
+                Entry::Menu(menu) => walk(&menu.entries, f),
+                Entry::Choice(choice) => walk(&choice.entries, f),
+                Entry::If(r#if) => walk(&r#if.entries, f),
+                _ => {
+                    if let Some(source) = source(entry) {
+                        for kconfig in &source.kconfigs {
+                            walk(&kconfig.entries, f);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    walk(&kconfig.entries, f);
+}
+
+/// Calls `f` on the tree and every sourced file.
+fn visit_files<'a>(kconfig: &'a Kconfig, f: &mut impl FnMut(&'a Kconfig)) {
+    f(kconfig);
+    visit(kconfig, &mut |entry| {
+        if let Some(source) = source(entry) {
+            source.kconfigs.iter().for_each(&mut *f);
+        }
+    });
+}
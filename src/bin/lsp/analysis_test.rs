@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use lsp_types::{
+    CompletionItemKind, DiagnosticSeverity, NumberOrString, Position, Range, SymbolKind,
+};
+use nom_kconfig::{format::FormatOptions, vfs::MemoryFs, KconfigFile};
+
+use crate::analysis::{format, Analysis};
+
+const KCONFIG: &str = r#"mainmenu "Demo"
+
+menu "Networking"
+
+config NET
+	bool "Networking support"
+	select CRC
+	help
+	  Enable NET, the network stack.
+
+if NET
+
+config INET
+	bool "TCP/IP"
+	depends on NET && !CRC
+
+endif
+
+endmenu
+
+source "drivers/Kconfig"
+"#;
+
+const DRIVERS: &str = r#"config CRC
+	tristate
+
+choice
+	prompt "Driver"
+
+config E1000
+	bool "e1000"
+	help
+	  Intel PRO/1000.
+
+endchoice
+"#;
+
+fn kconfig_file(kconfig: &str) -> KconfigFile {
+    let mut fs = MemoryFs::new();
+    fs.insert("/src/Kconfig", kconfig);
+    fs.insert("/src/drivers/Kconfig", DRIVERS);
+    KconfigFile::new(PathBuf::from("/src"), PathBuf::from("Kconfig")).with_vfs(fs)
+}
+
+fn analysis() -> Analysis {
+    Analysis::new(&kconfig_file(KCONFIG)).unwrap()
+}
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn test_references_outside_strings_comments_and_help() {
+    let input = "config FOO # comment BAR\n\tbool \"prompt BAR\" if BAR\n\thelp\n\t  BAR\n\nif FOO && \\\n\tBAR\nconfig BAR\n\tbool\n\tdefault y if FOO\nendif\n";
+    let analysis = Analysis::new(&kconfig_file(input)).unwrap();
+    assert_eq!(
+        analysis.references("BAR", true),
+        vec![
+            ("Kconfig".to_string(), range(1, 22, 25)),
+            ("Kconfig".to_string(), range(6, 1, 4)),
+            ("Kconfig".to_string(), range(7, 7, 10)),
+        ]
+    );
+    assert_eq!(
+        analysis.references("FOO", false),
+        vec![
+            ("Kconfig".to_string(), range(5, 3, 6)),
+            ("Kconfig".to_string(), range(9, 14, 17)),
+        ]
+    );
+}
+
+#[test]
+fn test_diagnostics() {
+    let analysis = analysis();
+    assert!(analysis.contains("Kconfig"));
+    assert!(analysis.contains("drivers/Kconfig"));
+    assert_eq!(analysis.diagnostics["drivers/Kconfig"], vec![]);
+    let diagnostics = &analysis.diagnostics["Kconfig"];
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].code,
+        Some(NumberOrString::String("missing_help".to_string()))
+    );
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+    assert_eq!(diagnostics[0].range.start, Position::new(12, 0));
+
+    let input = "config A\n\tbool\n\tselekt B\n";
+    let analysis = Analysis::new(&kconfig_file(input)).unwrap();
+    let diagnostics = &analysis.diagnostics["Kconfig"];
+    assert_eq!(diagnostics[0].message, "unknown keyword `selekt`");
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(diagnostics[0].range, range(2, 1, 9));
+}
+
+#[test]
+fn test_definition() {
+    let analysis = analysis();
+    // `select CRC`
+    assert_eq!(
+        analysis.definition("Kconfig", Position::new(6, 9)),
+        vec![("drivers/Kconfig".to_string(), range(0, 7, 10))]
+    );
+    // `depends on NET && !CRC`
+    assert_eq!(
+        analysis.definition("Kconfig", Position::new(14, 13)),
+        vec![("Kconfig".to_string(), range(4, 7, 10))]
+    );
+    assert_eq!(
+        analysis.definition("Kconfig", Position::new(20, 3)),
+        vec![("drivers/Kconfig".to_string(), Range::default())]
+    );
+    // help text
+    assert_eq!(analysis.definition("Kconfig", Position::new(8, 11)), vec![]);
+}
+
+#[test]
+fn test_references() {
+    let analysis = analysis();
+    assert_eq!(
+        analysis.references("CRC", true),
+        vec![
+            ("Kconfig".to_string(), range(6, 8, 11)),
+            ("Kconfig".to_string(), range(14, 20, 23)),
+            ("drivers/Kconfig".to_string(), range(0, 7, 10)),
+        ]
+    );
+    assert_eq!(
+        analysis.references("NET", false),
+        vec![
+            ("Kconfig".to_string(), range(10, 3, 6)),
+            ("Kconfig".to_string(), range(14, 12, 15)),
+        ]
+    );
+}
+
+#[test]
+fn test_hover() {
+    let analysis = analysis();
+    let (markdown, hover_range) = analysis.hover("Kconfig", Position::new(10, 4)).unwrap();
+    assert_eq!(hover_range, range(10, 3, 6));
+    assert_eq!(
+        markdown,
+        "```kconfig\nconfig NET\n\tbool \"Networking support\"\n```\n\nEnable NET, the network stack.\n\nDefined at Kconfig:5"
+    );
+    let (markdown, _) = analysis
+        .hover("drivers/Kconfig", Position::new(0, 8))
+        .unwrap();
+    assert!(markdown.contains("\n\nSelected by `NET`\n\n"));
+    assert_eq!(analysis.hover("Kconfig", Position::new(5, 2)), None);
+}
+
+#[test]
+fn test_completion() {
+    let analysis = analysis();
+    let items = analysis.completion("Kconfig", Position::new(6, 2));
+    assert_eq!(items[0].label, "bool");
+    assert_eq!(items[0].kind, Some(CompletionItemKind::KEYWORD));
+    let items = analysis.completion("Kconfig", Position::new(2, 2));
+    assert_eq!(items[0].label, "config");
+
+    let items = analysis.completion("Kconfig", Position::new(6, 9));
+    let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+    assert_eq!(labels, vec!["NET", "INET", "CRC", "E1000"]);
+    assert_eq!(items[0].kind, Some(CompletionItemKind::VARIABLE));
+    assert_eq!(
+        items[0].detail.as_deref(),
+        Some("bool \"Networking support\"")
+    );
+}
+
+#[test]
+fn test_document_symbols() {
+    let analysis = analysis();
+    let symbols = analysis.document_symbols("Kconfig");
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "Networking");
+    assert_eq!(symbols[0].kind, SymbolKind::NAMESPACE);
+    assert_eq!(symbols[0].range.start, Position::new(2, 0));
+    assert_eq!(symbols[0].range.end, Position::new(18, 7));
+    let children = symbols[0].children.as_ref().unwrap();
+    let names: Vec<&str> = children.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["NET", "INET"]);
+    assert_eq!(children[0].selection_range, range(4, 7, 10));
+
+    let symbols = analysis.document_symbols("drivers/Kconfig");
+    assert_eq!(symbols[1].kind, SymbolKind::ENUM);
+    assert_eq!(symbols[1].children.as_ref().unwrap()[0].name, "E1000");
+}
+
+#[test]
+fn test_format() {
+    let input = "config NET\n    bool \"Networking\"\nsource \"$(SRCARCH)/Kconfig\"\n";
+    assert_eq!(
+        format(&kconfig_file(input), &FormatOptions::default()),
+        Ok("config NET\n\tbool \"Networking\"\n\nsource \"$(SRCARCH)/Kconfig\"\n".to_string())
+    );
+    assert_eq!(
+        format(
+            &kconfig_file("# NET\nconfig NET\n    bool \"net\" # switch\n"),
+            &FormatOptions::default()
+        ),
+        Ok("# NET\nconfig NET\n\tbool \"net\" # switch\n".to_string())
+    );
+    assert!(format(&kconfig_file("config\n"), &FormatOptions::default()).is_err());
+}
@@ -0,0 +1,72 @@
+//! A language server for Kconfig files: diagnostics, go-to-definition, references, hover, completion, document symbols and formatting.
+//!
+//! The server talks on stdio. The tree is read from the workspace root, or from `--root`:
+//!
+//! ```shell
+//! cargo install nom-kconfig --features lsp
+//! nom-kconfig-lsp --srcarch x86
+//! ```
+
+use std::{collections::HashMap, error::Error, path::PathBuf};
+
+use clap::Parser;
+use lsp_server::Connection;
+use lsp_types::InitializeParams;
+use nom_kconfig::{
+    kconfig_file::{command_line_variables, parse_variable},
+    KconfigFile,
+};
+use serde_json::json;
+
+mod analysis;
+#[cfg(test)]
+mod analysis_test;
+mod server;
+
+#[derive(Parser)]
+#[command(author, version, about = "Language server for Kconfig files, on stdio")]
+struct Cli {
+    /// The main Kconfig file, relative to the root directory.
+    #[arg(long, default_value = "Kconfig")]
+    kconfig: PathBuf,
+    /// Root directory of the tree, `source` statements are relative to it. Defaults to the root of the workspace.
+    #[arg(long)]
+    root: Option<PathBuf>,
+    /// A variable of the Kconfig files, can be repeated.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+    /// Shortcut for `--var SRCARCH=<ARCH> --var ARCH=<ARCH>`.
+    #[arg(long)]
+    srcarch: Option<String>,
+}
+
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let cli = Cli::parse();
+    let (connection, io_threads) = Connection::stdio();
+    let (id, params) = connection.initialize_start()?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    #[allow(deprecated)]
+    let workspace = params
+        .workspace_folders
+        .and_then(|folders| folders.into_iter().next())
+        .map(|folder| folder.uri)
+        .or(params.root_uri)
+        .and_then(|uri| uri.to_file_path().ok());
+    let root = match cli.root.or(workspace) {
+        Some(root) => root,
+        None => std::env::current_dir()?,
+    };
+    connection.initialize_finish(
+        id,
+        json!({
+            "capabilities": server::capabilities(),
+            "serverInfo": {"name": "nom-kconfig-lsp", "version": env!("CARGO_PKG_VERSION")},
+        }),
+    )?;
+
+    let variables = command_line_variables(cli.srcarch.as_deref(), &cli.variables);
+    let kconfig_file = KconfigFile::new_with_vars(root, cli.kconfig, &variables, &HashMap::new());
+    server::Server::new(connection, kconfig_file).run()?;
+    io_threads.join()?;
+    Ok(())
+}
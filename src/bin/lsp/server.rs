@@ -0,0 +1,377 @@
+//! The message loop: keeps the open documents, analyzes the tree when they change and answers the requests.
+//!
+//! Changes are not analyzed right away: the analysis runs once the client has been quiet for [DEBOUNCE],
+//! or before answering a request. Only the trees containing a changed file are parsed again.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, ProtocolError, Request, Response};
+use lsp_types::{
+    CompletionParams, CompletionResponse, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentFormattingParams, DocumentSymbolParams,
+    DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf,
+    PublishDiagnosticsParams, Range, ReferenceParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+};
+use nom_kconfig::{format::FormatOptions, KconfigFile};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::analysis::{self, Analysis, OverlayFs};
+
+/// How long the client must be quiet before the changes are analyzed.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(Default::default()),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+pub struct Server {
+    connection: Connection,
+    /// The main Kconfig file. `source` statements are relative to its root directory.
+    kconfig_file: KconfigFile,
+    /// The documents opened in the editor, by absolute path.
+    documents: HashMap<PathBuf, String>,
+    /// The main tree.
+    tree: Option<Analysis>,
+    /// The open documents that are not part of the main tree, with the files they include, by file.
+    standalone: BTreeMap<String, Analysis>,
+    /// The files changed since the last analysis, relative to the root directory.
+    changed: HashSet<String>,
+    /// The diagnostics sent to the client, by file.
+    published: HashMap<String, Vec<lsp_types::Diagnostic>>,
+}
+
+impl Server {
+    pub fn new(connection: Connection, kconfig_file: KconfigFile) -> Self {
+        Self {
+            connection,
+            kconfig_file,
+            documents: HashMap::new(),
+            tree: None,
+            standalone: BTreeMap::new(),
+            changed: HashSet::new(),
+            published: HashMap::new(),
+        }
+    }
+
+    /// Handles the messages until the client asks to shut down.
+    pub fn run(mut self) -> Result<(), ProtocolError> {
+        self.analyze();
+        let receiver = self.connection.receiver.clone();
+        loop {
+            let message = match self.changed.is_empty() {
+                true => receiver.recv().ok(),
+                false => match receiver.recv_timeout(DEBOUNCE) {
+                    Err(e) if e.is_timeout() => {
+                        self.analyze();
+                        continue;
+                    }
+                    message => message.ok(),
+                },
+            };
+            let Some(message) = message else {
+                break;
+            };
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.analyze();
+                    let response = self.handle(request);
+                    self.send(response.into());
+                }
+                Message::Notification(notification) => self.notify(notification),
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn send(&self, message: Message) {
+        // the client is gone when sending fails, the loop ends with the connection
+        let _ = self.connection.sender.send(message);
+    }
+
+    /// Records the changes of the documents, they are analyzed later.
+    fn notify(&mut self, notification: Notification) {
+        match notification.method.as_str() {
+            "textDocument/didOpen" => {
+                if let Ok(params) = params::<DidOpenTextDocumentParams>(notification.params) {
+                    let uri = params.text_document.uri;
+                    let text = params.text_document.text;
+                    if let (Ok(path), Some(file)) = (uri.to_file_path(), self.file(&uri)) {
+                        // opening a file of the tree without modifying it changes nothing
+                        if self.tree.as_ref().and_then(|t| t.content(&file)) != Some(&text) {
+                            self.changed.insert(file);
+                        }
+                        self.documents.insert(path, text);
+                    }
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(mut params) = params::<DidChangeTextDocumentParams>(notification.params) {
+                    let uri = params.text_document.uri;
+                    if let (Ok(path), Some(file), Some(change)) = (
+                        uri.to_file_path(),
+                        self.file(&uri),
+                        params.content_changes.pop(),
+                    ) {
+                        self.documents.insert(path, change.text);
+                        self.changed.insert(file);
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = params::<DidCloseTextDocumentParams>(notification.params) {
+                    let uri = params.text_document.uri;
+                    if let (Ok(path), Some(file)) = (uri.to_file_path(), self.file(&uri)) {
+                        self.documents.remove(&path);
+                        self.changed.insert(file);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn handle(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            "textDocument/definition" => params(request.params).map(|p| self.definition(p)),
+            "textDocument/references" => params(request.params).map(|p| self.references(p)),
+            "textDocument/hover" => params(request.params).map(|p| self.hover(p)),
+            "textDocument/completion" => params(request.params).map(|p| self.completion(p)),
+            "textDocument/documentSymbol" => {
+                params(request.params).map(|p| self.document_symbols(p))
+            }
+            "textDocument/formatting" => params(request.params).and_then(|p| self.format(p)),
+            _ => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request {}", request.method),
+                )
+            }
+        };
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(e) => Response::new_err(id, ErrorCode::RequestFailed as i32, e),
+        }
+    }
+
+    fn root_dir(&self) -> &Path {
+        &self.kconfig_file.root_dir
+    }
+
+    /// The path of a document relative to the root directory.
+    fn file(&self, uri: &Url) -> Option<String> {
+        let path = uri.to_file_path().ok()?;
+        let file = path.strip_prefix(self.root_dir()).ok()?;
+        Some(file.to_string_lossy().to_string())
+    }
+
+    fn uri(&self, file: &str) -> Option<Url> {
+        Url::from_file_path(self.root_dir().join(file)).ok()
+    }
+
+    fn location(&self, (file, range): (String, Range)) -> Option<Location> {
+        Some(Location::new(self.uri(&file)?, range))
+    }
+
+    /// The main tree, followed by the open documents that are not part of it.
+    fn analyses(&self) -> impl Iterator<Item = &Analysis> {
+        self.tree.iter().chain(self.standalone.values())
+    }
+
+    /// The analysis containing a document.
+    fn analysis(&self, uri: &Url) -> Option<(&Analysis, String)> {
+        let file = self.file(uri)?;
+        let analysis = self.analyses().find(|a| a.contains(&file))?;
+        Some((analysis, file))
+    }
+
+    /// Parses again the trees containing a changed file, then the open documents that are not part of the main tree,
+    /// and publishes the diagnostics that changed. Does nothing when no file changed since the last analysis.
+    fn analyze(&mut self) {
+        if self.tree.is_some() && self.changed.is_empty() {
+            return;
+        }
+        let changed = std::mem::take(&mut self.changed);
+        let is_stale = |analysis: &Analysis| changed.iter().any(|file| analysis.contains(file));
+        let main = self
+            .kconfig_file
+            .clone()
+            .with_vfs(OverlayFs::new(&self.documents));
+        if self.tree.as_ref().map_or(true, is_stale) {
+            self.tree = Analysis::new(&main).ok();
+        }
+
+        let mut files: Vec<String> = self
+            .documents
+            .keys()
+            .filter_map(|path| Url::from_file_path(path).ok())
+            .filter_map(|uri| self.file(&uri))
+            .filter(|file| !self.tree.as_ref().is_some_and(|t| t.contains(file)))
+            .collect();
+        files.sort();
+        let mut standalone = std::mem::take(&mut self.standalone);
+        for file in files {
+            let analysis = match standalone.remove(&file) {
+                Some(analysis) if !is_stale(&analysis) => Some(analysis),
+                _ => Analysis::new(&KconfigFile {
+                    file: PathBuf::from(&file),
+                    ..main.clone()
+                })
+                .ok(),
+            };
+            if let Some(analysis) = analysis {
+                self.standalone.insert(file, analysis);
+            }
+        }
+        self.publish_diagnostics();
+    }
+
+    /// Sends the diagnostics that changed since they were last published.
+    fn publish_diagnostics(&mut self) {
+        let mut diagnostics: HashMap<String, Vec<lsp_types::Diagnostic>> = HashMap::new();
+        for analysis in self.analyses() {
+            for (file, list) in &analysis.diagnostics {
+                diagnostics
+                    .entry(file.clone())
+                    .or_insert_with(|| list.clone());
+            }
+        }
+        let mut files: Vec<String> = diagnostics
+            .keys()
+            .chain(self.published.keys())
+            .cloned()
+            .collect();
+        files.sort();
+        files.dedup();
+        for file in files {
+            let list = diagnostics.remove(&file).unwrap_or_default();
+            if self.published.get(&file).map_or(&vec![], |l| l) == &list {
+                continue;
+            }
+            if let Some(uri) = self.uri(&file) {
+                let params = PublishDiagnosticsParams::new(uri, list.clone(), None);
+                self.send(
+                    Notification::new("textDocument/publishDiagnostics".to_string(), params).into(),
+                );
+            }
+            match list.is_empty() {
+                true => self.published.remove(&file),
+                false => self.published.insert(file, list),
+            };
+        }
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Value {
+        let position = params.text_document_position_params;
+        let Some((analysis, file)) = self.analysis(&position.text_document.uri) else {
+            return Value::Null;
+        };
+        let locations: Vec<Location> = analysis
+            .definition(&file, position.position)
+            .into_iter()
+            .filter_map(|l| self.location(l))
+            .collect();
+        serde_json::to_value(GotoDefinitionResponse::Array(locations)).unwrap_or_default()
+    }
+
+    fn references(&self, params: ReferenceParams) -> Value {
+        let position = params.text_document_position;
+        let Some((analysis, file)) = self.analysis(&position.text_document.uri) else {
+            return Value::Null;
+        };
+        let Some((record, _)) = analysis.symbol_at(&file, position.position) else {
+            return Value::Null;
+        };
+        let locations: Vec<Location> = analysis
+            .references(&record.name, params.context.include_declaration)
+            .into_iter()
+            .filter_map(|l| self.location(l))
+            .collect();
+        serde_json::to_value(locations).unwrap_or_default()
+    }
+
+    fn hover(&self, params: HoverParams) -> Value {
+        let position = params.text_document_position_params;
+        let hover = self
+            .analysis(&position.text_document.uri)
+            .and_then(|(analysis, file)| analysis.hover(&file, position.position))
+            .map(|(value, range)| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value,
+                }),
+                range: Some(range),
+            });
+        serde_json::to_value(hover).unwrap_or_default()
+    }
+
+    fn completion(&self, params: CompletionParams) -> Value {
+        let position = params.text_document_position;
+        let items = self
+            .analysis(&position.text_document.uri)
+            .map(|(analysis, file)| analysis.completion(&file, position.position))
+            .unwrap_or_default();
+        serde_json::to_value(CompletionResponse::Array(items)).unwrap_or_default()
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Value {
+        let symbols = self
+            .analysis(&params.text_document.uri)
+            .map(|(analysis, file)| analysis.document_symbols(&file))
+            .unwrap_or_default();
+        serde_json::to_value(DocumentSymbolResponse::Nested(symbols)).unwrap_or_default()
+    }
+
+    /// Formats in the style of the Linux kernel, whatever the indentation settings of the editor.
+    fn format(&self, params: DocumentFormattingParams) -> Result<Value, String> {
+        let uri = params.text_document.uri;
+        let file = self
+            .file(&uri)
+            .ok_or("the file is outside the root directory")?;
+        let kconfig_file = KconfigFile {
+            file: PathBuf::from(file),
+            ..self
+                .kconfig_file
+                .clone()
+                .with_vfs(OverlayFs::new(&self.documents))
+        };
+        let formatted = analysis::format(&kconfig_file, &FormatOptions::default())?;
+        let content = match uri.to_file_path().ok().and_then(|p| self.documents.get(&p)) {
+            Some(content) => content.clone(),
+            None => std::fs::read_to_string(kconfig_file.full_path()).map_err(|e| e.to_string())?,
+        };
+        let edits = match formatted == content {
+            true => vec![],
+            false => vec![TextEdit::new(
+                Range::new(Default::default(), analysis::end_position(&content)),
+                formatted,
+            )],
+        };
+        serde_json::to_value(edits).map_err(|e| e.to_string())
+    }
+}
+
+fn params<P: DeserializeOwned>(params: Value) -> Result<P, String> {
+    serde_json::from_value(params).map_err(|e| e.to_string())
+}
@@ -0,0 +1,550 @@
+//! The state of the menuconfig, independent of the terminal.
+
+use std::{io, path::PathBuf};
+
+use nom_kconfig::{
+    attribute::{evaluate, SymbolValue},
+    dotconfig::{DotConfig, DEFAULT_PREFIX},
+    menu_tree::{MenuNode, MenuNodeKind, MenuTree},
+    resolve::{resolve_table, Configuration, ResolvedSymbol},
+    symbol_table::SymbolType,
+    tristate::Tristate,
+    Kconfig, SymbolTable,
+};
+
+/// A line of the current menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Indices of the node from the root of the menu tree.
+    pub path: Vec<usize>,
+    /// Indentation of nodes displayed below the symbol they depend on.
+    pub depth: usize,
+}
+
+/// A symbol or a prompt matching a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub path: Vec<usize>,
+    pub symbol: Option<String>,
+    pub prompt: Option<String>,
+    /// Prompts of the menus leading to the node.
+    pub location: Vec<String>,
+}
+
+pub struct App {
+    pub tree: MenuTree,
+    pub table: SymbolTable,
+    /// Values set by the user, the content of the `.config` file at the beginning.
+    pub user: DotConfig,
+    pub configuration: Configuration,
+    pub config_path: PathBuf,
+    /// Path of the menu displayed.
+    pub menu: Vec<usize>,
+    pub cursor: usize,
+    /// Whether the configuration changed since it was saved.
+    pub changed: bool,
+}
+
+impl App {
+    pub fn new(kconfig: &Kconfig, user: DotConfig, config_path: PathBuf) -> Self {
+        let table = SymbolTable::new(kconfig);
+        let configuration = resolve_table(&table, Some(&user));
+        Self {
+            tree: MenuTree::new(kconfig),
+            table,
+            user,
+            configuration,
+            config_path,
+            menu: vec![],
+            cursor: 0,
+            changed: false,
+        }
+    }
+
+    pub fn node(&self, path: &[usize]) -> &MenuNode {
+        path.iter()
+            .fold(&self.tree.root, |node, index| &node.children[*index])
+    }
+
+    pub fn current_menu(&self) -> &MenuNode {
+        self.node(&self.menu)
+    }
+
+    pub fn is_visible(&self, node: &MenuNode) -> bool {
+        node.prompt.is_some() && self.eval(&node.visibility) != Tristate::No
+    }
+
+    fn eval(&self, expression: &Option<nom_kconfig::attribute::Expression>) -> Tristate {
+        expression
+            .as_ref()
+            .map_or(Tristate::Yes, |e| evaluate(e, &self.configuration))
+    }
+
+    /// The lowest value the `select` attributes targeting the symbol force it to.
+    fn selected_value(&self, name: &str) -> Tristate {
+        self.table.get(name).map_or(Tristate::No, |record| {
+            record.selected_by.iter().fold(Tristate::No, |acc, r| {
+                let selector = self
+                    .configuration
+                    .get(&r.symbol)
+                    .map_or(Tristate::No, SymbolValue::tristate);
+                acc.or(selector.and(self.eval(&r.r#if)))
+            })
+        })
+    }
+
+    /// The visible nodes of the current menu. Children of nodes that are not menus are displayed below them.
+    pub fn rows(&self) -> Vec<Row> {
+        let mut rows = vec![];
+        self.collect_rows(&self.menu.clone(), 0, &mut rows);
+        rows
+    }
+
+    fn collect_rows(&self, path: &[usize], depth: usize, rows: &mut Vec<Row>) {
+        for (index, child) in self.node(path).children.iter().enumerate() {
+            if !self.is_visible(child) {
+                continue;
+            }
+            let mut child_path = path.to_vec();
+            child_path.push(index);
+            rows.push(Row {
+                path: child_path.clone(),
+                depth,
+            });
+            if !child.is_menu() {
+                self.collect_rows(&child_path, depth + 1, rows);
+            }
+        }
+    }
+
+    pub fn selected(&self) -> Option<Row> {
+        self.rows().get(self.cursor).cloned()
+    }
+
+    pub fn symbol(&self, node: &MenuNode) -> Option<&ResolvedSymbol> {
+        self.configuration.symbol(node.symbol()?)
+    }
+
+    fn in_choice(&self, path: &[usize]) -> bool {
+        !path.is_empty()
+            && matches!(
+                self.node(&path[..path.len() - 1]).kind,
+                MenuNodeKind::Choice(_)
+            )
+    }
+
+    /// The text of a row, like `[*] Prompt  --->`.
+    pub fn label(&self, row: &Row) -> String {
+        let node = self.node(&row.path);
+        let prompt = node.prompt.clone().unwrap_or_default();
+        let indent = "  ".repeat(row.depth);
+        let submenu = match node.is_menu() && !node.children.is_empty() {
+            true => "  --->",
+            false => "",
+        };
+        let label = match &node.kind {
+            MenuNodeKind::Menu => format!("    {}", prompt),
+            MenuNodeKind::Comment => format!("    *** {} ***", prompt),
+            MenuNodeKind::Choice(_) => {
+                let selection = self
+                    .choice_selection(&row.path)
+                    .and_then(|path| self.node(&path).prompt.clone())
+                    .map(|p| format!(" ({})", p))
+                    .unwrap_or_default();
+                format!("    {}{}", prompt, selection)
+            }
+            MenuNodeKind::Config(_) | MenuNodeKind::MenuConfig(_) => match self.symbol(node) {
+                Some(symbol) if self.in_choice(&row.path) => {
+                    let mark = match symbol.value.tristate() {
+                        Tristate::Yes => 'X',
+                        _ => ' ',
+                    };
+                    format!("({}) {}", mark, prompt)
+                }
+                Some(symbol) => format!(
+                    "{} {}",
+                    value_label(symbol, self.selected_value(&symbol.name)),
+                    prompt
+                ),
+                None => format!("    {}", prompt),
+            },
+        };
+        format!("{}{}{}", indent, label, submenu)
+    }
+
+    /// The member of a choice set to `y`.
+    pub fn choice_selection(&self, choice: &[usize]) -> Option<Vec<usize>> {
+        self.node(choice)
+            .children
+            .iter()
+            .position(|member| {
+                self.symbol(member)
+                    .is_some_and(|s| s.value.tristate() == Tristate::Yes)
+            })
+            .map(|index| {
+                let mut path = choice.to_vec();
+                path.push(index);
+                path
+            })
+    }
+
+    /// Opens the selected menu. Returns `false` when the selected row is not a menu.
+    pub fn enter(&mut self) -> bool {
+        match self.selected() {
+            Some(row) if self.node(&row.path).is_menu() => {
+                self.menu = row.path;
+                self.cursor = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Goes back to the parent menu. Returns `false` in the main menu.
+    pub fn back(&mut self) -> bool {
+        let Some(menu) = self.enclosing_menu(&self.menu) else {
+            return false;
+        };
+        let previous = std::mem::replace(&mut self.menu, menu);
+        self.cursor = self
+            .rows()
+            .iter()
+            .position(|row| row.path == previous)
+            .unwrap_or(0);
+        true
+    }
+
+    /// The closest ancestor of `path` displayed as a menu.
+    fn enclosing_menu(&self, path: &[usize]) -> Option<Vec<usize>> {
+        let mut path = path.to_vec();
+        path.pop()?;
+        while !self.node(&path).is_menu() {
+            path.pop();
+        }
+        Some(path)
+    }
+
+    fn modules_enabled(&self) -> bool {
+        self.table
+            .symbols()
+            .filter(|record| record.modules)
+            .any(|record| {
+                self.configuration
+                    .get(&record.name)
+                    .is_some_and(|v| v.tristate() != Tristate::No)
+            })
+    }
+
+    /// The values a bool or tristate symbol can take, limited by its visibility.
+    fn allowed_values(&self, symbol: &ResolvedSymbol) -> Vec<Tristate> {
+        let mut values = vec![Tristate::No];
+        if symbol.r#type == SymbolType::Tristate
+            && symbol.visibility != Tristate::No
+            && self.modules_enabled()
+        {
+            values.push(Tristate::Module);
+        }
+        if symbol.visibility == Tristate::Yes
+            || (symbol.visibility == Tristate::Module && !self.modules_enabled())
+        {
+            values.push(Tristate::Yes);
+        }
+        values
+    }
+
+    /// Toggles the selected symbol: `n`, `m`, `y` and back to `n`, or selects a member of a choice.
+    pub fn toggle(&mut self) -> Result<(), String> {
+        let row = self.selected().ok_or("nothing selected")?;
+        let node = self.node(&row.path);
+        let symbol = self.symbol(node).ok_or("not a symbol")?;
+        if !matches!(symbol.r#type, SymbolType::Bool | SymbolType::Tristate) {
+            return Err(format!("{} is not a bool or a tristate", symbol.name));
+        }
+        if self.in_choice(&row.path) {
+            return self.set_tristate(Tristate::Yes);
+        }
+        let allowed = self.allowed_values(symbol);
+        let current = symbol.value.tristate();
+        let next = allowed
+            .iter()
+            .position(|t| *t == current)
+            .map_or(allowed[0], |i| allowed[(i + 1) % allowed.len()]);
+        self.set_tristate(next)
+    }
+
+    /// Sets the selected bool or tristate symbol.
+    pub fn set_tristate(&mut self, value: Tristate) -> Result<(), String> {
+        let row = self.selected().ok_or("nothing selected")?;
+        let node = self.node(&row.path);
+        let symbol = self.symbol(node).ok_or("not a symbol")?.clone();
+        if symbol.visibility == Tristate::No {
+            return Err(format!("{} cannot be changed", symbol.name));
+        }
+        if self.in_choice(&row.path) {
+            // the members of a choice are exclusive
+            let choice = row.path[..row.path.len() - 1].to_vec();
+            let members: Vec<String> = self
+                .node(&choice)
+                .children
+                .iter()
+                .filter_map(|m| m.symbol().map(str::to_string))
+                .collect();
+            for member in members {
+                let value = match member == symbol.name {
+                    true => Tristate::Yes,
+                    false => Tristate::No,
+                };
+                self.user.set(&member, SymbolValue::Tristate(value));
+            }
+        } else {
+            if !self.allowed_values(&symbol).contains(&value) {
+                return Err(format!(
+                    "{} cannot be set to {}",
+                    symbol.name,
+                    SymbolValue::Tristate(value)
+                ));
+            }
+            self.user.set(&symbol.name, SymbolValue::Tristate(value));
+        }
+        self.update();
+        match self
+            .configuration
+            .get(&symbol.name)
+            .map(SymbolValue::tristate)
+        {
+            Some(actual) if actual != value => Err(format!(
+                "{} is forced to {} by a select",
+                symbol.name,
+                SymbolValue::Tristate(actual)
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the value of the selected int, hex or string symbol, checking the type and the range.
+    pub fn set_value(&mut self, input: &str) -> Result<(), String> {
+        let row = self.selected().ok_or("nothing selected")?;
+        let node = self.node(&row.path);
+        let symbol = self.symbol(node).ok_or("not a symbol")?.clone();
+        if symbol.visibility == Tristate::No {
+            return Err(format!("{} cannot be changed", symbol.name));
+        }
+        let input = input.trim();
+        let (value, number) = match symbol.r#type {
+            SymbolType::Int => {
+                let number = input
+                    .parse::<i64>()
+                    .map_err(|_| format!("'{}' is not a decimal number", input))?;
+                (SymbolValue::Int(number), number as i128)
+            }
+            SymbolType::Hex => {
+                let digits = input
+                    .strip_prefix("0x")
+                    .or_else(|| input.strip_prefix("0X"))
+                    .unwrap_or(input);
+                let number = i128::from_str_radix(digits, 16)
+                    .ok()
+                    .filter(|_| !digits.starts_with(['+', '-']))
+                    .ok_or_else(|| format!("'{}' is not a hexadecimal number", input))?;
+                (SymbolValue::Hex(format!("0x{}", digits)), number)
+            }
+            SymbolType::String => {
+                self.user
+                    .set(&symbol.name, SymbolValue::String(input.to_string()));
+                self.update();
+                return Ok(());
+            }
+            _ => return Err(format!("{} is not an int, a hex or a string", symbol.name)),
+        };
+        if let Some((lower, upper)) = symbol.range {
+            if number < lower || number > upper {
+                return Err(match symbol.r#type {
+                    SymbolType::Hex => {
+                        format!("{} is not in the range {:#x}-{:#x}", input, lower, upper)
+                    }
+                    _ => format!("{} is not in the range {}-{}", input, lower, upper),
+                });
+            }
+        }
+        self.user.set(&symbol.name, value);
+        self.update();
+        Ok(())
+    }
+
+    fn update(&mut self) {
+        self.configuration = resolve_table(&self.table, Some(&self.user));
+        self.changed = true;
+        let rows = self.rows().len();
+        if self.cursor >= rows {
+            self.cursor = rows.saturating_sub(1);
+        }
+    }
+
+    /// Nodes whose symbol or prompt contains `query`, ignoring the case. The `CONFIG_` prefix is optional.
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim().to_uppercase();
+        let query = query.strip_prefix(DEFAULT_PREFIX).unwrap_or(&query);
+        let mut results = vec![];
+        self.search_node(&mut vec![], query, &mut vec![], &mut results);
+        results
+    }
+
+    fn search_node(
+        &self,
+        path: &mut Vec<usize>,
+        query: &str,
+        location: &mut Vec<String>,
+        results: &mut Vec<SearchResult>,
+    ) {
+        let node = self.node(path);
+        let matches = !path.is_empty()
+            && !query.is_empty()
+            && (node.symbol().is_some_and(|s| s.contains(query))
+                || node
+                    .prompt
+                    .as_ref()
+                    .is_some_and(|p| p.to_uppercase().contains(query)));
+        if matches && !matches!(node.kind, MenuNodeKind::Comment) {
+            results.push(SearchResult {
+                path: path.clone(),
+                symbol: node.symbol().map(str::to_string),
+                prompt: node.prompt.clone(),
+                location: location.clone(),
+            });
+        }
+        let prompt = node.prompt.clone().unwrap_or_default();
+        if node.is_menu() {
+            location.push(prompt);
+        }
+        for index in 0..node.children.len() {
+            path.push(index);
+            self.search_node(path, query, location, results);
+            path.pop();
+        }
+        if node.is_menu() {
+            location.pop();
+        }
+    }
+
+    /// Opens the menu containing `path` and moves the cursor to it. Returns `false` when the node is not visible.
+    pub fn jump(&mut self, path: &[usize]) -> bool {
+        let Some(menu) = self.enclosing_menu(path) else {
+            return false;
+        };
+        let previous = std::mem::replace(&mut self.menu, menu);
+        match self.rows().iter().position(|row| row.path == path) {
+            Some(cursor) => {
+                self.cursor = cursor;
+                true
+            }
+            None => {
+                self.menu = previous;
+                false
+            }
+        }
+    }
+
+    /// The help of a node: its symbol, type, value, help text, dependencies and location.
+    pub fn help(&self, path: &[usize]) -> Vec<String> {
+        let node = self.node(path);
+        let mut lines = vec![];
+        if let Some(symbol) = self.symbol(node) {
+            lines.push(format!("Symbol: {} [={}]", symbol.name, symbol.value.raw()));
+            lines.push(format!("Type  : {}", symbol.r#type));
+            if let Some((lower, upper)) = symbol.range {
+                lines.push(match symbol.r#type {
+                    SymbolType::Hex => format!("Range : [{:#x} {:#x}]", lower, upper),
+                    _ => format!("Range : [{} {}]", lower, upper),
+                });
+            }
+        }
+        if let Some(prompt) = &node.prompt {
+            lines.push(format!("Prompt: {}", prompt));
+        }
+        if let Some(help) = &node.help {
+            lines.push(String::new());
+            lines.extend(help.lines().map(|l| format!("  {}", l)));
+        }
+        lines.push(String::new());
+        if let Some(dependencies) = &node.dependencies {
+            lines.push(format!(
+                "Depends on: {} [={}]",
+                dependencies,
+                SymbolValue::Tristate(self.eval(&node.dependencies))
+            ));
+        }
+        if node.visibility != node.dependencies {
+            if let Some(visibility) = &node.visibility {
+                lines.push(format!(
+                    "Visible if: {} [={}]",
+                    visibility,
+                    SymbolValue::Tristate(self.eval(&node.visibility))
+                ));
+            }
+        }
+        if let Some(record) = node.symbol().and_then(|s| self.table.get(s)) {
+            for (title, reverse) in [
+                ("Selected by", &record.selected_by),
+                ("Implied by", &record.implied_by),
+            ] {
+                if !reverse.is_empty() {
+                    lines.push(format!("{}:", title));
+                    for r in reverse {
+                        let value = self
+                            .configuration
+                            .get(&r.symbol)
+                            .map(SymbolValue::raw)
+                            .unwrap_or_default();
+                        lines.push(format!("  - {} [={}]", r.symbol, value));
+                    }
+                }
+            }
+        }
+        let location: Vec<String> = (0..path.len())
+            .map(|end| &path[..end])
+            .filter(|p| self.node(p).is_menu())
+            .filter_map(|p| self.node(p).prompt.clone())
+            .collect();
+        lines.push(format!("Location: -> {}", location.join(" -> ")));
+        if !node.span.file.is_empty() || node.span.line > 0 {
+            lines.push(format!("Defined at {}:{}", node.span.file, node.span.line));
+        }
+        lines
+    }
+
+    /// Writes the configuration to [App::config_path].
+    pub fn save(&mut self) -> io::Result<()> {
+        self.configuration
+            .to_dotconfig(DEFAULT_PREFIX)
+            .write_file(&self.config_path)?;
+        self.changed = false;
+        Ok(())
+    }
+}
+
+/// `[*]`, `<M>`, `-*-` for a symbol forced by a `select`, `(value)` for other types.
+fn value_label(symbol: &ResolvedSymbol, selected: Tristate) -> String {
+    match symbol.r#type {
+        SymbolType::Bool | SymbolType::Tristate => {
+            let value = symbol.value.tristate();
+            let mark = match value {
+                Tristate::Yes => '*',
+                Tristate::Module => 'M',
+                Tristate::No => ' ',
+            };
+            // forced by a select, or beyond what the user could choose
+            if (value != Tristate::No && selected == value) || symbol.visibility.and(value) != value
+            {
+                return match value {
+                    Tristate::No => "    ".to_string(),
+                    _ => format!("-{}-", mark),
+                };
+            }
+            match symbol.r#type {
+                SymbolType::Tristate => format!("<{}>", mark),
+                _ => format!("[{}]", mark),
+            }
+        }
+        _ => format!("({})", symbol.value.raw()),
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+use nom_kconfig::{
+    attribute::SymbolValue, dotconfig::DotConfig, parse_kconfig, tristate::Tristate, KconfigInput,
+};
+
+use crate::app::App;
+
+const INPUT: &str = r#"mainmenu "Test Configuration"
+
+config MODULES
+    bool "Enable loadable module support"
+    modules
+
+menu "Drivers"
+
+menuconfig NETDEVICES
+    bool "Network device support"
+    default y
+
+if NETDEVICES
+
+config DUMMY
+    tristate "Dummy net driver support"
+    help
+      A dummy driver.
+
+config DUMMY_DEBUG
+    bool "Debug"
+    depends on DUMMY
+
+endif
+
+config HZ
+    int "Timer frequency"
+    range 100 1000
+    default 250
+
+config PHYS
+    hex "Physical address"
+    default 0x1000
+
+config NAME
+    string "Name"
+
+config CRYPTO
+    bool "Crypto"
+    select HASH
+
+config HASH
+    bool "Hash"
+
+endmenu
+
+choice
+    prompt "Compression"
+
+config GZIP
+    bool "Gzip"
+
+config XZ
+    bool "XZ"
+
+endchoice
+"#;
+
+fn app(config: &str) -> App {
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(INPUT, Default::default())).unwrap();
+    App::new(&kconfig, DotConfig::parse(config), PathBuf::from(".config"))
+}
+
+fn labels(app: &App) -> Vec<String> {
+    app.rows().iter().map(|row| app.label(row)).collect()
+}
+
+fn select(app: &mut App, prompt: &str) {
+    app.cursor = app
+        .rows()
+        .iter()
+        .position(|row| app.node(&row.path).prompt.as_deref() == Some(prompt))
+        .unwrap();
+}
+
+#[test]
+fn test_app_navigation() {
+    let mut app = app("CONFIG_MODULES=y\n");
+    assert_eq!(
+        app.current_menu().prompt.as_deref(),
+        Some("Test Configuration")
+    );
+    assert_eq!(
+        labels(&app),
+        vec![
+            "[*] Enable loadable module support",
+            "    Drivers  --->",
+            "    Compression (Gzip)  --->"
+        ]
+    );
+    select(&mut app, "Drivers");
+    assert!(app.enter());
+    select(&mut app, "Network device support");
+    assert!(app.enter());
+    assert_eq!(labels(&app), vec!["< > Dummy net driver support"]);
+    assert!(app.back());
+    assert_eq!(app.cursor, 0);
+    assert!(app.back());
+    assert_eq!(app.cursor, 1);
+    assert!(!app.back());
+}
+
+#[test]
+fn test_app_toggle() {
+    let mut app = app("CONFIG_MODULES=y\n");
+    app.menu = vec![1, 0];
+    app.cursor = 0;
+    app.toggle().unwrap();
+    assert_eq!(
+        app.configuration.get("DUMMY"),
+        Some(&SymbolValue::Tristate(Tristate::Module))
+    );
+    // DUMMY_DEBUG is displayed below DUMMY
+    assert_eq!(
+        labels(&app),
+        vec!["<M> Dummy net driver support", "  [ ] Debug"]
+    );
+    app.toggle().unwrap();
+    assert_eq!(
+        app.configuration.get("DUMMY"),
+        Some(&SymbolValue::Tristate(Tristate::Yes))
+    );
+    app.toggle().unwrap();
+    assert_eq!(
+        app.configuration.get("DUMMY"),
+        Some(&SymbolValue::Tristate(Tristate::No))
+    );
+    assert!(app.changed);
+
+    // without module support, a tristate is a bool
+    app.menu = vec![];
+    app.cursor = 0;
+    app.set_tristate(Tristate::No).unwrap();
+    app.menu = vec![1, 0];
+    app.cursor = 0;
+    assert!(app.set_tristate(Tristate::Module).is_err());
+}
+
+#[test]
+fn test_app_select() {
+    let mut app = app("");
+    app.menu = vec![1];
+    select(&mut app, "Crypto");
+    app.toggle().unwrap();
+    select(&mut app, "Hash");
+    assert_eq!(app.label(&app.selected().unwrap()), "-*- Hash");
+    assert_eq!(
+        app.set_tristate(Tristate::No),
+        Err("HASH is forced to y by a select".to_string())
+    );
+}
+
+#[test]
+fn test_app_choice() {
+    let mut app = app("");
+    select(&mut app, "Compression");
+    assert!(app.enter());
+    assert_eq!(labels(&app), vec!["(X) Gzip", "( ) XZ"]);
+    app.cursor = 1;
+    app.toggle().unwrap();
+    assert_eq!(labels(&app), vec!["( ) Gzip", "(X) XZ"]);
+}
+
+#[test]
+fn test_app_set_value() {
+    let mut app = app("");
+    app.menu = vec![1];
+    select(&mut app, "Timer frequency");
+    assert_eq!(
+        app.set_value("50"),
+        Err("50 is not in the range 100-1000".to_string())
+    );
+    assert_eq!(
+        app.set_value("abc"),
+        Err("'abc' is not a decimal number".to_string())
+    );
+    app.set_value("300").unwrap();
+    assert_eq!(app.configuration.get("HZ"), Some(&SymbolValue::Int(300)));
+
+    select(&mut app, "Physical address");
+    assert!(app.set_value("xyz").is_err());
+    app.set_value("ff").unwrap();
+    assert_eq!(
+        app.label(&app.selected().unwrap()),
+        "(0xff) Physical address"
+    );
+
+    select(&mut app, "Name");
+    app.set_value("linux").unwrap();
+    assert_eq!(
+        app.configuration.get("NAME"),
+        Some(&SymbolValue::String("linux".to_string()))
+    );
+}
+
+#[test]
+fn test_app_search_and_help() {
+    let mut app = app("");
+    let results = app.search("config_dummy");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].symbol.as_deref(), Some("DUMMY"));
+    assert_eq!(
+        results[0].location,
+        vec!["Test Configuration", "Drivers", "Network device support"]
+    );
+    assert_eq!(app.search("gzip")[0].symbol.as_deref(), Some("GZIP"));
+
+    assert!(app.jump(&results[0].path));
+    assert_eq!(app.menu, vec![1, 0]);
+    let help = app.help(&results[0].path);
+    assert_eq!(help[0], "Symbol: DUMMY [=n]");
+    assert_eq!(help[1], "Type  : tristate");
+    assert!(help.contains(&"  A dummy driver.".to_string()));
+    assert!(help.contains(&"Depends on: NETDEVICES [=y]".to_string()));
+    assert!(help.contains(
+        &"Location: -> Test Configuration -> Drivers -> Network device support".to_string()
+    ));
+
+    // DUMMY_DEBUG is hidden
+    assert!(!app.jump(&results[1].path));
+}
+
+#[test]
+fn test_app_save() {
+    let mut app = app("CONFIG_MODULES=y\n");
+    app.config_path = std::env::temp_dir().join(format!("nom-menuconfig-{}", std::process::id()));
+    app.menu = vec![1];
+    select(&mut app, "Timer frequency");
+    app.set_value("500").unwrap();
+    app.save().unwrap();
+    let content = std::fs::read_to_string(&app.config_path);
+    std::fs::remove_file(&app.config_path).unwrap();
+    assert!(content.unwrap().contains("CONFIG_HZ=500\n"));
+    assert!(!app.changed);
+}
@@ -0,0 +1,60 @@
+//! A terminal interface to edit a `.config` file, like `make menuconfig`.
+//!
+//! ```shell
+//! cargo run --features menuconfig --bin nom-menuconfig -- --root /tmp/linux-6.4.9 --srcarch x86
+//! ```
+
+use std::{collections::HashMap, path::PathBuf};
+
+use clap::Parser;
+use nom_kconfig::{
+    dotconfig::{DotConfig, DEFAULT_PREFIX},
+    kconfig_file::{command_line_variables, parse_variable},
+    parse_kconfig, KconfigFile, KconfigInput,
+};
+
+mod app;
+#[cfg(test)]
+mod app_test;
+mod ui;
+
+#[derive(Parser)]
+#[command(
+    author,
+    version,
+    about = "Edit the configuration of a Kconfig tree in the terminal"
+)]
+struct Cli {
+    /// The Kconfig file, relative to the root directory.
+    #[arg(default_value = "Kconfig")]
+    kconfig: PathBuf,
+    /// Root directory of the tree, `source` statements are relative to it.
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    /// The configuration file to read and write.
+    #[arg(long, env = "KCONFIG_CONFIG", default_value = ".config")]
+    config: PathBuf,
+    /// A variable of the Kconfig files, can be repeated.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+    /// Shortcut for `--var SRCARCH=<ARCH> --var ARCH=<ARCH>`.
+    #[arg(long)]
+    srcarch: Option<String>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let variables = command_line_variables(cli.srcarch.as_deref(), &cli.variables);
+    let kconfig_file =
+        KconfigFile::new_with_vars(cli.root, cli.kconfig, &variables, &HashMap::new());
+    let content = kconfig_file.read_to_string()?;
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file))?;
+    let user = match cli.config.exists() {
+        true => DotConfig::from_file(&cli.config, DEFAULT_PREFIX)?,
+        false => DotConfig::parse(""),
+    };
+
+    let mut app = app::App::new(&kconfig, user, cli.config);
+    ui::run(&mut app)?;
+    Ok(())
+}
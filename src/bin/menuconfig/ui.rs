@@ -0,0 +1,375 @@
+//! Drawing and key bindings.
+
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+};
+use nom_kconfig::{symbol_table::SymbolType, tristate::Tristate};
+
+use crate::app::{App, SearchResult};
+
+const KEYS: &str = "Arrows/jk move  Enter open  Esc back  Space toggle  y/m/n set  / search  ? help  s save  q quit";
+
+enum Mode {
+    Browse,
+    /// Editing the value of an int, hex or string symbol.
+    Input {
+        buffer: String,
+    },
+    Search {
+        query: String,
+        results: Vec<SearchResult>,
+        cursor: usize,
+    },
+    Text {
+        title: String,
+        lines: Vec<String>,
+        scroll: usize,
+    },
+    ConfirmQuit,
+}
+
+/// Runs the interface until the user quits.
+pub fn run(app: &mut App) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = event_loop(app, &mut stdout);
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn event_loop(app: &mut App, stdout: &mut io::Stdout) -> io::Result<()> {
+    let mut mode = Mode::Browse;
+    let mut message = String::new();
+    loop {
+        draw(app, &mode, &message, stdout)?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        message.clear();
+        let (next, quit) = handle_key(app, mode, key, &mut message);
+        if quit {
+            return Ok(());
+        }
+        mode = next;
+    }
+}
+
+/// Returns the next mode and whether to quit.
+fn handle_key(app: &mut App, mode: Mode, key: KeyEvent, message: &mut String) -> (Mode, bool) {
+    let rows = app.rows().len();
+    match mode {
+        Mode::Browse => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => app.cursor = app.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.cursor = (app.cursor + 1).min(rows.saturating_sub(1))
+            }
+            KeyCode::PageUp => app.cursor = app.cursor.saturating_sub(10),
+            KeyCode::PageDown => app.cursor = (app.cursor + 10).min(rows.saturating_sub(1)),
+            KeyCode::Home => app.cursor = 0,
+            KeyCode::End => app.cursor = rows.saturating_sub(1),
+            // enter a menu, or edit the selected symbol when it is not a menu
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') if !app.enter() => {
+                return edit(app, message)
+            }
+            // go back to the parent menu, or quit from the main menu
+            KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace
+                if !app.back() =>
+            {
+                return quit(app)
+            }
+            KeyCode::Char(' ') => report(app.toggle(), message),
+            KeyCode::Char('y') => report(app.set_tristate(Tristate::Yes), message),
+            KeyCode::Char('m') => report(app.set_tristate(Tristate::Module), message),
+            KeyCode::Char('n') => report(app.set_tristate(Tristate::No), message),
+            KeyCode::Char('/') => {
+                return (
+                    Mode::Search {
+                        query: String::new(),
+                        results: vec![],
+                        cursor: 0,
+                    },
+                    false,
+                )
+            }
+            KeyCode::Char('?') => {
+                if let Some(row) = app.selected() {
+                    let title = app.node(&row.path).prompt.clone().unwrap_or_default();
+                    let lines = app.help(&row.path);
+                    return (
+                        Mode::Text {
+                            title,
+                            lines,
+                            scroll: 0,
+                        },
+                        false,
+                    );
+                }
+            }
+            KeyCode::Char('s') => match app.save() {
+                Ok(()) => {
+                    *message = format!("Configuration written to {}", app.config_path.display())
+                }
+                Err(e) => *message = format!("Cannot write {}: {}", app.config_path.display(), e),
+            },
+            KeyCode::Char('q') => return quit(app),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return (Mode::Browse, true)
+            }
+            _ => (),
+        },
+        Mode::Input { mut buffer } => match key.code {
+            KeyCode::Enter => match app.set_value(&buffer) {
+                Ok(()) => return (Mode::Browse, false),
+                Err(e) => {
+                    *message = e;
+                    return (Mode::Input { buffer }, false);
+                }
+            },
+            KeyCode::Esc => return (Mode::Browse, false),
+            KeyCode::Backspace => {
+                buffer.pop();
+                return (Mode::Input { buffer }, false);
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                return (Mode::Input { buffer }, false);
+            }
+            _ => return (Mode::Input { buffer }, false),
+        },
+        Mode::Search {
+            mut query,
+            mut results,
+            mut cursor,
+        } => {
+            match key.code {
+                KeyCode::Esc => return (Mode::Browse, false),
+                KeyCode::Up => cursor = cursor.saturating_sub(1),
+                KeyCode::Down => cursor = (cursor + 1).min(results.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    if let Some(result) = results.get(cursor) {
+                        if app.jump(&result.path) {
+                            return (Mode::Browse, false);
+                        }
+                        *message = "This entry is not visible".to_string();
+                    }
+                }
+                KeyCode::Char('?') if !results.is_empty() => {
+                    let result = &results[cursor];
+                    let title = result.prompt.clone().unwrap_or_default();
+                    let lines = app.help(&result.path);
+                    return (
+                        Mode::Text {
+                            title,
+                            lines,
+                            scroll: 0,
+                        },
+                        false,
+                    );
+                }
+                KeyCode::Backspace => {
+                    query.pop();
+                    results = app.search(&query);
+                    cursor = 0;
+                }
+                KeyCode::Char(c) => {
+                    query.push(c);
+                    results = app.search(&query);
+                    cursor = 0;
+                }
+                _ => (),
+            }
+            return (
+                Mode::Search {
+                    query,
+                    results,
+                    cursor,
+                },
+                false,
+            );
+        }
+        Mode::Text {
+            title,
+            lines,
+            mut scroll,
+        } => {
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => scroll = scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    scroll = (scroll + 1).min(lines.len().saturating_sub(1))
+                }
+                _ => return (Mode::Browse, false),
+            }
+            return (
+                Mode::Text {
+                    title,
+                    lines,
+                    scroll,
+                },
+                false,
+            );
+        }
+        Mode::ConfirmQuit => match key.code {
+            KeyCode::Char('y') => match app.save() {
+                Ok(()) => return (Mode::Browse, true),
+                Err(e) => *message = format!("Cannot write {}: {}", app.config_path.display(), e),
+            },
+            KeyCode::Char('n') => return (Mode::Browse, true),
+            _ => (),
+        },
+    }
+    (Mode::Browse, false)
+}
+
+/// Starts editing the selected int, hex or string symbol.
+fn edit(app: &App, message: &mut String) -> (Mode, bool) {
+    let Some(symbol) = app
+        .selected()
+        .and_then(|row| app.symbol(app.node(&row.path)).cloned())
+    else {
+        return (Mode::Browse, false);
+    };
+    match symbol.r#type {
+        SymbolType::Int | SymbolType::Hex | SymbolType::String => (
+            Mode::Input {
+                buffer: symbol.value.raw(),
+            },
+            false,
+        ),
+        _ => {
+            report(Err("Press space to change the value".to_string()), message);
+            (Mode::Browse, false)
+        }
+    }
+}
+
+fn quit(app: &App) -> (Mode, bool) {
+    match app.changed {
+        true => (Mode::ConfirmQuit, false),
+        false => (Mode::Browse, true),
+    }
+}
+
+fn report(result: Result<(), String>, message: &mut String) {
+    if let Err(e) = result {
+        *message = e;
+    }
+}
+
+fn draw(app: &App, mode: &Mode, message: &str, stdout: &mut io::Stdout) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let (width, height) = (width as usize, height as usize);
+    // title, blank line, ..., message, keys
+    let list_height = height.saturating_sub(4).max(1);
+    queue!(
+        stdout,
+        terminal::Clear(ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+
+    let (title, lines, selected): (String, Vec<String>, Option<usize>) = match mode {
+        Mode::Search {
+            query,
+            results,
+            cursor,
+        } => (
+            format!("Search: {}_", query),
+            results
+                .iter()
+                .map(|r| {
+                    format!(
+                        "{}{} ({})",
+                        r.symbol
+                            .as_ref()
+                            .map(|s| format!("{} ", s))
+                            .unwrap_or_default(),
+                        r.prompt.clone().unwrap_or_default(),
+                        r.location.join(" -> ")
+                    )
+                })
+                .collect(),
+            Some(*cursor),
+        ),
+        Mode::Text {
+            title,
+            lines,
+            scroll,
+        } => (
+            title.clone(),
+            lines.iter().skip(*scroll).cloned().collect(),
+            None,
+        ),
+        _ => {
+            let menu = app.current_menu();
+            (
+                menu.prompt.clone().unwrap_or_default(),
+                app.rows().iter().map(|row| app.label(row)).collect(),
+                Some(app.cursor),
+            )
+        }
+    };
+
+    print_line(stdout, 0, &title, width, true)?;
+    let offset = match selected {
+        Some(selected) if selected >= list_height => selected + 1 - list_height,
+        _ => 0,
+    };
+    for (i, line) in lines.iter().skip(offset).take(list_height).enumerate() {
+        queue!(stdout, cursor::MoveTo(0, (i + 2) as u16))?;
+        let highlighted = selected == Some(i + offset);
+        if highlighted {
+            queue!(stdout, SetAttribute(Attribute::Reverse))?;
+        }
+        queue!(stdout, Print(truncate(line, width)))?;
+        if highlighted {
+            queue!(stdout, SetAttribute(Attribute::Reset))?;
+        }
+    }
+
+    let status = match mode {
+        Mode::Input { buffer } => format!("Value: {}_", buffer),
+        Mode::ConfirmQuit => "Save the configuration? (y/n, any other key to cancel)".to_string(),
+        _ => message.to_string(),
+    };
+    print_line(stdout, height.saturating_sub(2), &status, width, false)?;
+    let keys = match mode {
+        Mode::Search { .. } => "Type to search  Enter go to  ? help  Esc back",
+        Mode::Text { .. } => "Arrows scroll  any other key to close",
+        Mode::Input { .. } => "Enter confirm  Esc cancel",
+        _ => KEYS,
+    };
+    print_line(stdout, height.saturating_sub(1), keys, width, true)?;
+    stdout.flush()
+}
+
+fn print_line(
+    stdout: &mut io::Stdout,
+    row: usize,
+    text: &str,
+    width: usize,
+    bold: bool,
+) -> io::Result<()> {
+    queue!(stdout, cursor::MoveTo(0, row as u16))?;
+    if bold {
+        queue!(stdout, SetAttribute(Attribute::Bold))?;
+    }
+    queue!(
+        stdout,
+        Print(truncate(text, width)),
+        SetAttribute(Attribute::Reset)
+    )
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
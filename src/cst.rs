@@ -0,0 +1,296 @@
+//! Lossless concrete syntax tree.
+//!
+//! [parse_kconfig](crate::parse_kconfig) drops comments, blank lines and the original formatting.
+//! In this mode, every entry and attribute keeps the trivia preceding it (comments, blank lines, spaces) and its exact source text,
+//! so that printing the tree gives back the original file, byte for byte.
+//!
+//! ```
+//! use nom_kconfig::{cst::parse_kconfig_lossless, KconfigInput};
+//!
+//! let input = r#"# Network options
+//! menu "Networking"
+//!
+//! config NET
+//!     bool "Networking support"  # the main switch
+//!     help
+//!       Say Y.
+//! endmenu
+//! "#;
+//! let (_, cst) = parse_kconfig_lossless(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! assert_eq!(cst.entries[0].leading_trivia, "# Network options\n");
+//! assert_eq!(cst.entries[0].children[0].header, "config NET");
+//! assert_eq!(cst.to_string(), input);
+//! ```
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    error::Error, kconfig::parse_kconfig, span::Span, Attribute, Entry, Kconfig, KconfigInput,
+};
+
+/// A node of the tree along with the text it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct CstNode<T> {
+    /// Comments, blank lines and spaces preceding the node.
+    pub leading_trivia: String,
+    /// The exact source text of the node.
+    pub text: String,
+    pub node: T,
+}
+
+pub type CstAttribute = CstNode<Attribute>;
+
+/// An entry of the lossless tree.
+///
+/// The source text of an entry is made of its `header`, its `attributes`, its `children` and its `footer`.
+/// Entries without attributes or children (`source`, `mainmenu`, variable assignments...) are entirely stored in the header.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct CstEntry {
+    pub leading_trivia: String,
+    /// The keyword and its arguments, for instance `config FOO`, `menu "Networking"` or `if NET`.
+    pub header: String,
+    pub attributes: Vec<CstAttribute>,
+    /// Entries nested in a `menu`, `choice` or `if` block.
+    pub children: Vec<CstEntry>,
+    /// `endmenu`, `endchoice` or `endif` with its leading trivia.
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub footer: Option<CstNode<()>>,
+    /// The abstract syntax tree of the entry, as returned by [parse_entry](crate::entry::parse_entry).
+    pub entry: Entry,
+}
+
+/// A Kconfig file parsed in lossless mode.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct CstKconfig {
+    pub file: String,
+    pub entries: Vec<CstEntry>,
+    /// Comments and blank lines at the end of the file.
+    pub trailing_trivia: String,
+}
+
+impl CstEntry {
+    /// The exact source text of the entry, leading trivia excluded.
+    pub fn text(&self) -> String {
+        let mut text = self.header.clone();
+        self.write_body(&mut text);
+        text
+    }
+
+    fn write(&self, out: &mut String) {
+        out.push_str(&self.leading_trivia);
+        out.push_str(&self.header);
+        self.write_body(out);
+    }
+
+    fn write_body(&self, out: &mut String) {
+        for attribute in &self.attributes {
+            out.push_str(&attribute.leading_trivia);
+            out.push_str(&attribute.text);
+        }
+        for child in &self.children {
+            child.write(out);
+        }
+        if let Some(footer) = &self.footer {
+            out.push_str(&footer.leading_trivia);
+            out.push_str(&footer.text);
+        }
+    }
+}
+
+impl CstKconfig {
+    /// Prints the tree back, the result is identical to the parsed input.
+    pub fn source(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            entry.write(&mut out);
+        }
+        out.push_str(&self.trailing_trivia);
+        out
+    }
+
+    /// Drops the trivia and returns the abstract syntax tree.
+    pub fn to_kconfig(&self) -> Kconfig {
+        Kconfig {
+            file: self.file.clone(),
+            entries: self.entries.iter().map(|e| e.entry.clone()).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for CstEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out);
+        write!(f, "{}", out)
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for CstKconfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source())
+    }
+}
+
+/// Parses a kconfig input in lossless mode.
+///
+/// The input is parsed by [parse_kconfig], the tree is then rebuilt from the [spans](crate::span) of the entries and attributes:
+/// everything between two nodes is trivia.
+pub fn parse_kconfig_lossless(input: KconfigInput) -> Result<(KconfigInput, CstKconfig), Error> {
+    let source = *input.fragment();
+    let base = input.location_offset();
+    let (input, kconfig) = parse_kconfig(input)?;
+    let mut builder = Builder {
+        source,
+        base,
+        position: 0,
+    };
+    let entries = kconfig.entries.iter().map(|e| builder.entry(e)).collect();
+    Ok((
+        input,
+        CstKconfig {
+            file: kconfig.file,
+            entries,
+            trailing_trivia: source[builder.position..].to_string(),
+        },
+    ))
+}
+
+/// Slices the source text along the spans of the nodes, in order.
+struct Builder<'a> {
+    source: &'a str,
+    /// Offset of the source in the file.
+    base: usize,
+    /// End of the last node, in the source.
+    position: usize,
+}
+
+impl Builder<'_> {
+    fn bounds(&self, span: &Span) -> (usize, usize) {
+        let start = span.offset - self.base;
+        (start, start + span.length)
+    }
+
+    /// The trivia before the span and the text of the span.
+    fn node(&mut self, span: &Span) -> (String, String) {
+        let (start, end) = self.bounds(span);
+        let trivia = self.source[self.position..start].to_string();
+        self.position = end;
+        (trivia, self.source[start..end].to_string())
+    }
+
+    fn entry(&mut self, entry: &Entry) -> CstEntry {
+        let (start, end) = self.bounds(entry.span());
+        let leading_trivia = self.source[self.position..start].to_string();
+        let (mut attributes, children, footer): (Vec<Attribute>, &[Entry], _) = match entry {
+            Entry::Config(config) | Entry::MenuConfig(config) => {
+                (config.attributes.clone(), &[], None)
+            }
+            Entry::Choice(choice) => (choice.options.clone(), &choice.entries, Some("endchoice")),
+            Entry::Menu(menu) => (
+                menu.depends_on
+                    .iter()
+                    .cloned()
+                    .map(Attribute::DependsOn)
+                    .chain(menu.visible.clone().map(Attribute::Visible))
+                    .collect(),
+                &menu.entries,
+                Some("endmenu"),
+            ),
+            Entry::If(r#if) => (vec![], &r#if.entries, Some("endif")),
+            Entry::Comment(comment) => (
+                comment
+                    .dependencies
+                    .iter()
+                    .cloned()
+                    .map(Attribute::DependsOn)
+                    .collect(),
+                &[],
+                None,
+            ),
+            _ => {
+                self.position = end;
+                return CstEntry {
+                    leading_trivia,
+                    header: self.source[start..end].to_string(),
+                    attributes: vec![],
+                    children: vec![],
+                    footer: None,
+                    entry: entry.clone(),
+                };
+            }
+        };
+        attributes.sort_by_key(|a| a.span().offset);
+
+        let header = header_length(&self.source[start..end]);
+        self.position = start + header;
+        let attributes = attributes
+            .into_iter()
+            .map(|node| {
+                let (leading_trivia, text) = self.node(node.span());
+                CstNode {
+                    leading_trivia,
+                    text,
+                    node,
+                }
+            })
+            .collect();
+        let children = children.iter().map(|c| self.entry(c)).collect();
+        let footer = footer.map(|keyword| {
+            let leading_trivia = self.source[self.position..end - keyword.len()].to_string();
+            CstNode {
+                leading_trivia,
+                text: keyword.to_string(),
+                node: (),
+            }
+        });
+        self.position = end;
+        CstEntry {
+            leading_trivia,
+            header: self.source[start..start + header].to_string(),
+            attributes,
+            children,
+            footer,
+            entry: entry.clone(),
+        }
+    }
+}
+
+/// The length of the first line of an entry (`config FOO`, `if A && \` + `B`...), its trailing comment excluded.
+fn header_length(text: &str) -> usize {
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    let mut end = text.len();
+    while let Some((index, c)) = chars.next() {
+        match (quote, c) {
+            // escaped character or line continuation
+            (_, '\\') if chars.next().is_some_and(|(_, c)| c == '\r') => {
+                chars.next_if(|(_, c)| *c == '\n');
+            }
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (_, '\n') | (None, '#') => {
+                end = index;
+                break;
+            }
+            _ => (),
+        }
+    }
+    text[..end].trim_end().len()
+}
@@ -0,0 +1,129 @@
+use crate::{
+    attribute::{Attribute, Prompt},
+    cst::parse_kconfig_lossless,
+    lib_test::{linux_files, parse},
+    Entry, KconfigInput,
+};
+
+fn assert_lossless(input: &str) {
+    let (_, cst) =
+        parse_kconfig_lossless(KconfigInput::new_extra(input, Default::default())).unwrap();
+    assert_eq!(cst.to_string(), input);
+    assert_eq!(cst.to_kconfig(), parse(input));
+}
+
+#[test]
+fn test_cst_keeps_trivia() {
+    let input = r#"
+# SPDX-License-Identifier: GPL-2.0
+
+mainmenu "Linux"
+
+config FOO
+	bool "foo"   # trailing comment
+
+	# comment between attributes
+	default y
+	help
+	  Indented help.
+
+	    More indented line.
+
+endmenu_is_not_a_keyword_here := 1
+"#;
+    let (_, cst) =
+        parse_kconfig_lossless(KconfigInput::new_extra(input, Default::default())).unwrap();
+    assert_eq!(cst.to_string(), input);
+    assert_eq!(cst.entries.len(), 3);
+    assert_eq!(
+        cst.entries[0].leading_trivia,
+        "\n# SPDX-License-Identifier: GPL-2.0\n\n"
+    );
+    let config = &cst.entries[1];
+    assert_eq!(config.header, "config FOO");
+    assert_eq!(config.attributes[0].leading_trivia, "\n\t");
+    assert_eq!(
+        config.attributes[0].node,
+        Attribute::Type(crate::attribute::r#type::ConfigType {
+            r#type: crate::attribute::r#type::Type::Bool(Some("foo".to_string())),
+            r#if: None,
+            span: Default::default()
+        })
+    );
+    assert_eq!(
+        config.attributes[1].leading_trivia,
+        "   # trailing comment\n\n\t# comment between attributes\n\t"
+    );
+    assert_eq!(config.attributes[1].text, "default y");
+}
+
+#[test]
+fn test_cst_blocks() {
+    let input = r#"menu "Networking"
+    depends on NET
+    visible if EXPERT
+
+if PCI
+config A
+    prompt "a"
+    tristate
+endif # PCI
+
+choice
+    prompt "choice"
+config B
+    bool "b"
+   # before endchoice
+endchoice
+endmenu
+"#;
+    assert_lossless(input);
+    let (_, cst) =
+        parse_kconfig_lossless(KconfigInput::new_extra(input, Default::default())).unwrap();
+    let menu = &cst.entries[0];
+    assert_eq!(menu.header, "menu \"Networking\"");
+    assert_eq!(menu.attributes.len(), 2);
+    assert_eq!(menu.children.len(), 2);
+    assert_eq!(menu.footer.as_ref().unwrap().text, "endmenu");
+    let r#if = &menu.children[0];
+    assert_eq!(r#if.header, "if PCI");
+    assert_eq!(r#if.footer.as_ref().unwrap().leading_trivia, "\n");
+    let choice = &menu.children[1];
+    assert_eq!(choice.leading_trivia, " # PCI\n\n");
+    assert_eq!(
+        choice.footer.as_ref().unwrap().leading_trivia,
+        "\n   # before endchoice\n"
+    );
+    match &choice.children[0].entry {
+        Entry::Config(config) => assert_eq!(
+            config.attributes,
+            vec![Attribute::Type(crate::attribute::r#type::ConfigType {
+                r#type: crate::attribute::r#type::Type::Bool(Some("b".to_string())),
+                r#if: None,
+                span: Default::default()
+            })]
+        ),
+        e => panic!("unexpected entry {:?}", e),
+    }
+    match &r#if.children[0].attributes[0].node {
+        Attribute::Prompt(Prompt { prompt, .. }) => assert_eq!(prompt, "a"),
+        a => panic!("unexpected attribute {:?}", a),
+    }
+}
+
+#[test]
+fn test_cst_line_continuations_and_crlf() {
+    assert_lossless(
+        "config A\r\n\tbool \"a\"\r\n\tdepends on B && \\\n\t\tC\r\n\r\nsource \"empty\"\n",
+    );
+}
+
+#[test]
+fn test_cst_linux_files() {
+    for (kconfig_file, input) in linux_files() {
+        let file = kconfig_file.file.display().to_string();
+        let (_, cst) = parse_kconfig_lossless(KconfigInput::new_extra(&input, kconfig_file))
+            .unwrap_or_else(|e| panic!("{}: {:?}", file, e));
+        assert_eq!(cst.source(), input, "{}", file);
+    }
+}
@@ -0,0 +1,266 @@
+//! Detection of recursive dependencies.
+//!
+//! `scripts/kconfig` refuses a tree where the value of a symbol depends on itself, through `depends on`, `select`, `imply` or choices.
+//! [find_cycles] walks the same relations and reports every cycle with the messages of the C implementation:
+//!
+//! ```
+//! use nom_kconfig::{cycle::find_cycles, parse_kconfig, KconfigInput, SymbolTable};
+//!
+//! let input = "config A\n    bool\n    depends on B\n    select B\n\nconfig B\n    bool\n";
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let cycles = find_cycles(&SymbolTable::new(&kconfig));
+//! assert_eq!(cycles.len(), 1);
+//! assert_eq!(cycles[0].steps[0].to_string(), "symbol A depends on B");
+//! assert_eq!(cycles[0].steps[1].to_string(), "symbol B is selected by A");
+//! ```
+
+use std::collections::HashMap;
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{diagnostic::Diagnostic, span::Span, symbol_table::SymbolTable};
+
+/// How a symbol depends on the next one of a cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum Relation {
+    /// The next symbol appears in the dependencies of the symbol, inherited ones included.
+    DependsOn,
+    /// The next symbol selects the symbol, or appears in the `if` condition of the `select`.
+    SelectedBy,
+    /// The next symbol implies the symbol, or appears in the `if` condition of the `imply`.
+    ImpliedBy,
+    /// The symbol belongs to the next choice.
+    PartOfChoice,
+    /// The choice contains the next symbol.
+    ContainsSymbol,
+}
+
+impl Relation {
+    fn is_membership(&self) -> bool {
+        matches!(self, Relation::PartOfChoice | Relation::ContainsSymbol)
+    }
+}
+
+/// One edge of a cycle: `from` depends on `to`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct CycleStep {
+    /// The name of the symbol, `<choice>` for anonymous choices.
+    pub from: String,
+    pub relation: Relation,
+    pub to: String,
+    /// Location of the definition, or of the `select`/`imply` for reverse dependencies.
+    pub span: Span,
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for CycleStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (from, to) = (&self.from, &self.to);
+        match self.relation {
+            Relation::DependsOn => write!(f, "symbol {} depends on {}", from, to),
+            Relation::SelectedBy => write!(f, "symbol {} is selected by {}", from, to),
+            Relation::ImpliedBy => write!(f, "symbol {} is implied by {}", from, to),
+            Relation::PartOfChoice => write!(f, "symbol {} is part of choice {}", from, to),
+            Relation::ContainsSymbol => write!(f, "choice {} contains symbol {}", from, to),
+        }
+    }
+}
+
+/// A recursive dependency: the last step leads back to the first symbol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Cycle {
+    pub steps: Vec<CycleStep>,
+}
+
+impl Cycle {
+    /// The symbols of the cycle, the first one being repeated at the end: `["A", "B", "A"]`.
+    pub fn path(&self) -> Vec<&str> {
+        let mut path: Vec<&str> = self.steps.iter().map(|s| s.from.as_str()).collect();
+        path.extend(self.steps.first().map(|s| s.from.as_str()));
+        path
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(
+            format!(
+                "recursive dependency detected: {}",
+                self.path().join(" -> ")
+            ),
+            self.steps[0].span.clone(),
+        )
+    }
+}
+
+/// Prints the cycle like `scripts/kconfig` does.
+#[cfg(feature = "display")]
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = |span: &Span| format!("{}:{}", span.file, span.line);
+        writeln!(
+            f,
+            "{}:error: recursive dependency detected!",
+            location(&self.steps[0].span)
+        )?;
+        for step in &self.steps {
+            writeln!(f, "{}:\t{}", location(&step.span), step)?;
+        }
+        writeln!(
+            f,
+            "For a resolution refer to Documentation/kbuild/kconfig-language.rst"
+        )?;
+        write!(f, "subsection \"Kconfig recursive dependency limitations\"")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Unvisited,
+    OnStack,
+    Done,
+}
+
+struct Edge<'a> {
+    to: usize,
+    relation: Relation,
+    span: &'a Span,
+}
+
+/// Returns the recursive dependencies of the table, in the order they are found.
+/// A cycle made only of choice memberships is not a recursive dependency and is ignored.
+pub fn find_cycles(table: &SymbolTable) -> Vec<Cycle> {
+    let symbols: Vec<_> = table.symbols().collect();
+    let choices = table.choices();
+    let mut names: Vec<String> = symbols.iter().map(|s| s.name.clone()).collect();
+    names.extend(choices.iter().map(|c| match &c.name {
+        Some(name) => name.clone(),
+        None => "<choice>".to_string(),
+    }));
+    let index: HashMap<&str, usize> = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.name.as_str(), i))
+        .collect();
+
+    let mut edges: Vec<Vec<Edge>> = Vec::with_capacity(names.len());
+    for symbol in &symbols {
+        let mut adjacency = vec![];
+        let mut add = |name: &str, relation, span| {
+            if let Some(to) = index.get(name) {
+                adjacency.push(Edge {
+                    to: *to,
+                    relation,
+                    span,
+                })
+            }
+        };
+        for (reverse, relation) in symbol
+            .selected_by
+            .iter()
+            .map(|r| (r, Relation::SelectedBy))
+            .chain(symbol.implied_by.iter().map(|r| (r, Relation::ImpliedBy)))
+        {
+            add(&reverse.symbol, relation, &reverse.span);
+            for name in reverse.r#if.iter().flat_map(|e| e.symbols()) {
+                add(name, relation, &reverse.span);
+            }
+        }
+        for definition in &symbol.definitions {
+            for name in definition.dependencies.iter().flat_map(|e| e.symbols()) {
+                add(name, Relation::DependsOn, &definition.span);
+            }
+        }
+        if let (Some(choice), Some(definition)) = (symbol.choice, symbol.definitions.first()) {
+            adjacency.push(Edge {
+                to: symbols.len() + choice,
+                relation: Relation::PartOfChoice,
+                span: &definition.span,
+            });
+        }
+        edges.push(adjacency);
+    }
+    for choice in choices {
+        let mut adjacency = vec![];
+        for definition in &choice.definitions {
+            for name in definition.dependencies.iter().flat_map(|e| e.symbols()) {
+                if let Some(to) = index.get(name) {
+                    adjacency.push(Edge {
+                        to: *to,
+                        relation: Relation::DependsOn,
+                        span: &definition.span,
+                    });
+                }
+            }
+        }
+        if let Some(definition) = choice.definitions.first() {
+            for member in &choice.members {
+                if let Some(to) = index.get(member.as_str()) {
+                    adjacency.push(Edge {
+                        to: *to,
+                        relation: Relation::ContainsSymbol,
+                        span: &definition.span,
+                    });
+                }
+            }
+        }
+        edges.push(adjacency);
+    }
+
+    // iterative depth-first search, the stack holds the node and the index of its next edge to explore
+    let mut cycles = vec![];
+    let mut states = vec![State::Unvisited; names.len()];
+    for start in 0..names.len() {
+        if states[start] != State::Unvisited {
+            continue;
+        }
+        let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+        states[start] = State::OnStack;
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            let Some(edge) = edges[node].get(*next) else {
+                states[node] = State::Done;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            match states[edge.to] {
+                State::Unvisited => {
+                    states[edge.to] = State::OnStack;
+                    stack.push((edge.to, 0));
+                }
+                State::OnStack => {
+                    let position = stack.iter().position(|(n, _)| *n == edge.to).unwrap();
+                    // the edge taken from a node is the one before its next edge to explore
+                    let taken = stack[position..]
+                        .iter()
+                        .map(|(n, next)| &edges[*n][*next - 1])
+                        .collect::<Vec<_>>();
+                    if taken.iter().all(|e| e.relation.is_membership()) {
+                        continue;
+                    }
+                    let steps = stack[position..]
+                        .iter()
+                        .zip(taken)
+                        .map(|((from, _), edge)| CycleStep {
+                            from: names[*from].clone(),
+                            relation: edge.relation,
+                            to: names[edge.to].clone(),
+                            span: edge.span.clone(),
+                        })
+                        .collect();
+                    cycles.push(Cycle { steps });
+                }
+                State::Done => (),
+            }
+        }
+    }
+    cycles
+}
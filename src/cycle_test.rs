@@ -0,0 +1,130 @@
+use crate::{
+    cycle::{find_cycles, Cycle, Relation},
+    lib_test::parse,
+    symbol_table::SymbolTable,
+};
+
+fn cycles(input: &str) -> Vec<Cycle> {
+    let kconfig = parse(input);
+    find_cycles(&SymbolTable::new(&kconfig))
+}
+
+#[test]
+fn test_cycle_select_and_depends_on() {
+    // example of Documentation/kbuild/kconfig-language.rst
+    let cycles = cycles(
+        r#"config CORE
+	bool
+
+config CORE_BELL_A
+	bool
+	depends on CORE
+
+config CORE_BELL_A_ADVANCED
+	bool
+	depends on CORE_BELL_A
+	select CORE
+"#,
+    );
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(
+        cycles[0].path(),
+        vec!["CORE", "CORE_BELL_A_ADVANCED", "CORE_BELL_A", "CORE"]
+    );
+    assert_eq!(
+        cycles[0].to_string(),
+        r#":11:error: recursive dependency detected!
+:11:	symbol CORE is selected by CORE_BELL_A_ADVANCED
+:8:	symbol CORE_BELL_A_ADVANCED depends on CORE_BELL_A
+:4:	symbol CORE_BELL_A depends on CORE
+For a resolution refer to Documentation/kbuild/kconfig-language.rst
+subsection "Kconfig recursive dependency limitations""#
+    );
+    assert_eq!(
+        cycles[0].to_diagnostic().message,
+        "recursive dependency detected: CORE -> CORE_BELL_A_ADVANCED -> CORE_BELL_A -> CORE"
+    );
+}
+
+#[test]
+fn test_cycle_through_if_blocks_and_imply() {
+    let cycles = cycles(
+        r#"config A
+    bool "a"
+    depends on A
+
+if B
+config C
+    bool "c"
+    imply B
+endif
+
+config B
+    bool "b"
+"#,
+    );
+    let relations: Vec<Vec<Relation>> = cycles
+        .iter()
+        .map(|c| c.steps.iter().map(|s| s.relation).collect())
+        .collect();
+    assert_eq!(
+        relations,
+        vec![
+            vec![Relation::DependsOn],
+            vec![Relation::DependsOn, Relation::ImpliedBy]
+        ]
+    );
+    assert_eq!(cycles[1].path(), vec!["C", "B", "C"]);
+    assert_eq!(cycles[1].steps[1].span.line, 8);
+}
+
+#[test]
+fn test_cycle_through_choice() {
+    let input = r#"choice
+    prompt "mode"
+config FAST
+    bool "fast"
+    depends on !EXPERT
+config SLOW
+    bool "slow"
+endchoice
+
+config EXPERT
+    bool "expert"
+"#;
+    assert_eq!(cycles(input), vec![]);
+
+    let cycles = cycles(&format!("{}    depends on SLOW\n", input));
+    assert_eq!(cycles.len(), 1);
+    let steps: Vec<String> = cycles[0].steps.iter().map(|s| s.to_string()).collect();
+    assert_eq!(
+        steps,
+        vec![
+            "symbol FAST depends on EXPERT",
+            "symbol EXPERT depends on SLOW",
+            "symbol SLOW is part of choice <choice>",
+            "choice <choice> contains symbol FAST",
+        ]
+    );
+}
+
+#[test]
+fn test_no_cycle() {
+    assert_eq!(
+        cycles(
+            r#"config A
+    bool
+    depends on B
+    select C
+
+config B
+    bool
+    select C
+
+config C
+    bool
+"#
+        ),
+        vec![]
+    );
+}
@@ -0,0 +1,210 @@
+//! Diagnostics reported when parsing with error recovery.
+//!
+//! [parse_kconfig](crate::parse_kconfig) stops at the first error. [parse_kconfig_with_diagnostics] records the error instead,
+//! skips the input until the next entry keyword (`config`, `menu`, `endif`...) and carries on.
+//! Sourced files are parsed the same way.
+//!
+//! ```
+//! use nom_kconfig::{diagnostic::{parse_kconfig_with_diagnostics, Severity}, KconfigInput};
+//!
+//! let input = "config A\n    bool\n    selekt B\n\nconfig C\n    select &&\n\nconfig D\n    bool\n";
+//! let result = parse_kconfig_with_diagnostics(KconfigInput::new_extra(input, Default::default()));
+//! assert_eq!(result.kconfig.entries.len(), 3);
+//! assert_eq!(result.diagnostics.len(), 2);
+//! assert_eq!(result.diagnostics[0].severity, Severity::Error);
+//! assert_eq!(result.diagnostics[0].message, "unknown keyword `selekt`");
+//! assert_eq!(result.diagnostics[1].message, "expected a symbol after `select`");
+//! assert_eq!(result.diagnostics[1].span.line, 6);
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use nom::{combinator::eof, sequence::delimited, Parser};
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    entry::parse_entries_recovering, span::Span, util::ws, util::ws_comment, Kconfig, KconfigInput,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A problem found in a Kconfig file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// Locations of the `source` statements that led to the file, the outermost first.
+    pub include_chain: Vec<Span>,
+}
+
+impl Diagnostic {
+    pub fn error<S: Into<String>>(message: S, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            include_chain: vec![],
+        }
+    }
+
+    pub fn warning<S: Into<String>>(message: S, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(message, span)
+        }
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        };
+        write!(f, "{}: {}\n  --> {}", severity, self.message, self.span)?;
+        for span in self.include_chain.iter().rev() {
+            write!(f, "\n  = included from {}", span)?;
+        }
+        Ok(())
+    }
+}
+
+/// Shared list of diagnostics, see [KconfigFile::diagnostics](crate::KconfigFile::diagnostics).
+pub type Diagnostics = Rc<RefCell<Vec<Diagnostic>>>;
+
+/// The partial [Kconfig] along with the problems found while parsing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseResult {
+    pub kconfig: Kconfig,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseResult {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Parses a kconfig input, recovering from errors.
+pub fn parse_kconfig_with_diagnostics(mut input: KconfigInput) -> ParseResult {
+    let diagnostics = Diagnostics::default();
+    input.extra.diagnostics = Some(diagnostics.clone());
+    let file = input.extra.file.display().to_string();
+    let entries =
+        match delimited(ws_comment, parse_entries_recovering(None), ws(eof)).parse(input.clone()) {
+            Ok((_, entries)) => entries,
+            Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                report(
+                    &e.input,
+                    Diagnostic::error("invalid syntax", line_span(&e.input)),
+                );
+                vec![]
+            }
+            Err(nom::Err::Incomplete(_)) => {
+                report(
+                    &input,
+                    Diagnostic::error("incomplete input", line_span(&input)),
+                );
+                vec![]
+            }
+        };
+    let diagnostics = diagnostics.take();
+    ParseResult {
+        kconfig: Kconfig { file, entries },
+        diagnostics,
+    }
+}
+
+/// Records a diagnostic if the input is parsed with error recovery.
+pub(crate) fn report(input: &KconfigInput, mut diagnostic: Diagnostic) {
+    if let Some(diagnostics) = &input.extra.diagnostics {
+        diagnostic.include_chain = input.extra.include_chain.clone();
+        diagnostics.borrow_mut().push(diagnostic);
+    }
+}
+
+/// The span of the line starting at the input.
+pub(crate) fn line_span(input: &KconfigInput) -> Span {
+    let mut span = Span::from_input(input);
+    let start = span.offset - input.location_offset();
+    span.length = input.fragment()[start..]
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+        .len();
+    span
+}
+
+/// Explains why the entry starting at `input` failed to parse at `error`.
+pub(crate) fn describe_error(input: &KconfigInput, error: &KconfigInput) -> (String, Span) {
+    let fragment = input.fragment();
+    let position = error
+        .location_offset()
+        .saturating_sub(input.location_offset())
+        .min(fragment.len());
+    let line_start = fragment[..position].rfind('\n').map_or(0, |i| i + 1);
+    let line = fragment[line_start..].lines().next().unwrap_or_default();
+    let mut words = line.split_whitespace();
+    let keyword = words
+        .next()
+        .unwrap_or_default()
+        .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .next()
+        .unwrap_or_default();
+    let expected = match keyword {
+        "select" | "imply" => Some("a symbol"),
+        "config" | "menuconfig" | "configdefault" => Some("a symbol name"),
+        "default" | "def_bool" | "def_tristate" | "requires" | "if" | "visible" => {
+            Some("an expression")
+        }
+        "depends" => match words.next() {
+            Some("on") => {
+                return (
+                    "expected an expression after `depends on`".to_string(),
+                    span_of(input, line_start),
+                )
+            }
+            _ => Some("`on`"),
+        },
+        "range" => Some("two symbols or numbers"),
+        "prompt" | "menu" | "comment" | "mainmenu" => Some("a quoted string"),
+        "bool" | "tristate" | "string" | "hex" | "int" => Some("a quoted prompt"),
+        "source" | "osource" | "rsource" | "orsource" => Some("a file path"),
+        "option" => Some("`defconfig_list`, `modules`, `allnoconfig_y` or `env=`"),
+        "help" | "---help---" => Some("a new line"),
+        _ => None,
+    };
+    let message = match (expected, keyword) {
+        (Some(expected), keyword) => format!("expected {} after `{}`", expected, keyword),
+        (None, "") => match line.trim_start().chars().next() {
+            Some(c) => format!("unexpected `{}`", c),
+            None => "unexpected end of file".to_string(),
+        },
+        (None, "endmenu" | "endif" | "endchoice") => format!("unexpected `{}`", keyword),
+        (None, keyword) => format!("unknown keyword `{}`", keyword),
+    };
+    (message, span_of(input, line_start))
+}
+
+fn span_of(input: &KconfigInput, offset: usize) -> Span {
+    use nom::Input;
+    line_span(&input.take_from(offset))
+}
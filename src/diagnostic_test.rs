@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use crate::{
+    diagnostic::{parse_kconfig_with_diagnostics, ParseResult, Severity},
+    vfs::MemoryFs,
+    Entry, KconfigFile, KconfigInput,
+};
+
+fn parse(input: &str) -> ParseResult {
+    parse_kconfig_with_diagnostics(KconfigInput::new_extra(input, Default::default()))
+}
+
+fn messages(result: &ParseResult) -> Vec<(u32, &str)> {
+    result
+        .diagnostics
+        .iter()
+        .map(|d| (d.span.line, d.message.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_diagnostics_valid_input() {
+    let result = parse("config A\n    bool\nmenu \"M\"\nendmenu\n");
+    assert!(!result.has_errors());
+    assert!(result.diagnostics.is_empty());
+    assert_eq!(result.kconfig.entries.len(), 2);
+}
+
+#[test]
+fn test_diagnostics_unknown_attribute() {
+    let result = parse("config A\n    bool\n    selekt B\n    default y\n\nconfig C\n    bool\n");
+    assert!(result.has_errors());
+    assert_eq!(messages(&result), vec![(3, "unknown keyword `selekt`")]);
+    assert_eq!(result.diagnostics[0].severity, Severity::Error);
+    assert_eq!(result.diagnostics[0].span.column, 5);
+    assert_eq!(result.diagnostics[0].span.length, 8);
+    assert_eq!(result.kconfig.entries.len(), 2);
+}
+
+#[test]
+fn test_diagnostics_recovery_skips_help() {
+    let input = r#"config A
+    bool "a"
+    selekt B
+    help
+      menu entries below
+
+      source code will be bigger
+config C
+    bool
+"#;
+    let result = parse(input);
+    assert_eq!(messages(&result), vec![(3, "unknown keyword `selekt`")]);
+    assert_eq!(result.kconfig.entries.len(), 2);
+    assert!(matches!(&result.kconfig.entries[1], Entry::Config(c) if c.symbol == "C"));
+}
+
+#[test]
+#[cfg(feature = "named-choice")]
+fn test_diagnostics_named_choice() {
+    let result = parse("choice NAME\n    prompt \"p\"\nconfig A\n    bool\nendchoice\n");
+    assert!(result.diagnostics.is_empty());
+    assert!(
+        matches!(&result.kconfig.entries[0], Entry::Choice(c) if c.name.as_deref() == Some("NAME"))
+    );
+}
+
+#[test]
+fn test_diagnostics_recovery_inside_blocks() {
+    let input = r#"menu "Drivers"
+config A
+    bool
+    select &&
+if B
+config C
+    bool
+    range 1 )
+endif
+endmenu
+config D
+    bool
+"#;
+    let result = parse(input);
+    assert_eq!(
+        messages(&result),
+        vec![
+            (4, "expected a symbol after `select`"),
+            (8, "expected two symbols or numbers after `range`")
+        ]
+    );
+    let Entry::Menu(menu) = &result.kconfig.entries[0] else {
+        unreachable!()
+    };
+    assert_eq!(menu.entries.len(), 2);
+    assert!(matches!(&menu.entries[1], Entry::If(i) if i.entries.len() == 1));
+    assert!(matches!(&result.kconfig.entries[1], Entry::Config(c) if c.symbol == "D"));
+}
+
+#[test]
+fn test_diagnostics_unclosed_blocks() {
+    let result = parse("menu \"M\"\nif A\nconfig B\n    bool\nendmenu\nendif\n");
+    assert_eq!(
+        messages(&result),
+        vec![
+            (5, "expected `endif` before `endmenu`"),
+            (6, "unexpected `endif`")
+        ]
+    );
+    let result = parse("choice\n    prompt \"p\"\nconfig A\n    bool\n");
+    assert_eq!(
+        messages(&result),
+        vec![(5, "expected `endchoice` before the end of the file")]
+    );
+    assert_eq!(result.kconfig.entries.len(), 1);
+}
+
+#[test]
+fn test_diagnostics_sourced_file() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Kconfig.simple", "config SIMPLE\n\tbool\n");
+    fs.insert(
+        "/kernel/Kconfig.invalid",
+        "config INVALID\n\tbool\n\tselekt B\n",
+    );
+    let file =
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig.main")).with_vfs(fs);
+    let input =
+        "source \"Kconfig.simple\"\nsource \"Kconfig.invalid\"\nsource \"does-not-exist\"\n";
+    let result = parse_kconfig_with_diagnostics(KconfigInput::new_extra(input, file));
+    assert_eq!(result.kconfig.entries.len(), 3);
+    assert_eq!(result.diagnostics.len(), 2);
+
+    let invalid = &result.diagnostics[0];
+    assert_eq!(invalid.message, "unknown keyword `selekt`");
+    assert_eq!(
+        (invalid.span.file.as_str(), invalid.span.line),
+        ("Kconfig.invalid", 3)
+    );
+    assert_eq!(invalid.include_chain.len(), 1);
+    assert_eq!(invalid.include_chain[0].file, "Kconfig.main");
+    assert_eq!(invalid.include_chain[0].line, 2);
+    assert_eq!(
+        invalid.to_string(),
+        "error: unknown keyword `selekt`\n  --> Kconfig.invalid:3:2\n  = included from Kconfig.main:2:1"
+    );
+
+    let missing = &result.diagnostics[1];
+    assert!(missing.message.contains("does-not-exist"));
+    assert_eq!(missing.span.line, 3);
+    assert!(missing.include_chain.is_empty());
+}
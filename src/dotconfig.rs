@@ -0,0 +1,465 @@
+//! Reading and writing configuration files produced by Kconfig: `.config`, defconfig and `auto.conf`.
+//!
+//! All these files share the same line based format:
+//! ```text
+//! CONFIG_MODULES=y
+//! CONFIG_HZ=250
+//! CONFIG_PHYSICAL_START=0x1000000
+//! CONFIG_LOCALVERSION="-custom"
+//! # CONFIG_DEBUG is not set
+//! ```
+//! A [DotConfig] keeps every line of the file, comments and blank lines included, so that writing it back gives the exact same bytes.
+//!
+//! ```
+//! use nom_kconfig::{attribute::SymbolValue, dotconfig::DotConfig, tristate::Tristate};
+//!
+//! let input = "# Comment\nCONFIG_FOO=y\n# CONFIG_BAR is not set\n";
+//! let mut config = DotConfig::parse(input);
+//! assert_eq!(config.get("FOO"), Some(SymbolValue::Tristate(Tristate::Yes)));
+//! assert_eq!(config.get("BAR"), Some(SymbolValue::Tristate(Tristate::No)));
+//! assert_eq!(config.to_string(), input);
+//!
+//! config.set("BAR", SymbolValue::Int(42));
+//! assert_eq!(config.to_string(), "# Comment\nCONFIG_FOO=y\nCONFIG_BAR=42\n");
+//! ```
+
+use std::{io, path::Path};
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{SymbolProvider, SymbolValue},
+    symbol_table::SymbolType,
+    tristate::Tristate,
+    SymbolTable,
+};
+
+/// The prefix used by the Linux kernel. Other projects like Zephyr or ESP-IDF may use a different one.
+pub const DEFAULT_PREFIX: &str = "CONFIG_";
+
+/// Position of a line in the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct LineSpan {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Offset in bytes from the beginning of the file.
+    pub offset: usize,
+    /// Length in bytes of the line, line ending excluded.
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum LineKind {
+    /// `CONFIG_FOO=value`, the symbol is stored without its prefix.
+    Assignment {
+        symbol: String,
+        value: SymbolValue,
+    },
+    /// `# CONFIG_FOO is not set`
+    NotSet {
+        symbol: String,
+    },
+    /// Any other line starting with `#`.
+    Comment,
+    Blank,
+    /// A line that could not be understood, it is kept as it is.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Line {
+    pub kind: LineKind,
+    /// The text of the line, line ending excluded.
+    pub raw: String,
+    /// `"\n"`, `"\r\n"` or an empty string for the last line of a file without a trailing newline.
+    pub ending: String,
+    pub span: LineSpan,
+}
+
+impl Line {
+    /// The symbol assigned by this line, without the prefix.
+    pub fn symbol(&self) -> Option<&str> {
+        match &self.kind {
+            LineKind::Assignment { symbol, .. } | LineKind::NotSet { symbol } => Some(symbol),
+            _ => None,
+        }
+    }
+
+    /// The value assigned by this line, `# CONFIG_FOO is not set` being `n`.
+    pub fn value(&self) -> Option<SymbolValue> {
+        match &self.kind {
+            LineKind::Assignment { value, .. } => Some(value.clone()),
+            LineKind::NotSet { .. } => Some(SymbolValue::Tristate(Tristate::No)),
+            _ => None,
+        }
+    }
+}
+
+/// A parsed configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct DotConfig {
+    pub prefix: String,
+    pub lines: Vec<Line>,
+}
+
+impl Default for DotConfig {
+    fn default() -> Self {
+        Self {
+            prefix: DEFAULT_PREFIX.to_string(),
+            lines: vec![],
+        }
+    }
+}
+
+/// A problem found when checking a configuration against a [SymbolTable].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct ConfigIssue {
+    pub symbol: String,
+    pub span: LineSpan,
+    pub kind: ConfigIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum ConfigIssueKind {
+    /// The symbol is not defined in the Kconfig files.
+    UnknownSymbol,
+    /// The symbol is defined but has no type.
+    Untyped,
+    /// The value is not valid for the type of the symbol.
+    InvalidValue {
+        expected: SymbolType,
+        value: SymbolValue,
+    },
+    /// The symbol has already been assigned on a previous line.
+    Duplicate { previous: LineSpan },
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.span.line)?;
+        match &self.kind {
+            ConfigIssueKind::UnknownSymbol => write!(f, "unknown symbol {}", self.symbol),
+            ConfigIssueKind::Untyped => write!(f, "symbol {} has no type", self.symbol),
+            ConfigIssueKind::InvalidValue { expected, value } => write!(
+                f,
+                "'{}' invalid for {}, expected a {} value",
+                value, self.symbol, expected
+            ),
+            ConfigIssueKind::Duplicate { previous } => write!(
+                f,
+                "override: {} already set at line {}",
+                self.symbol, previous.line
+            ),
+        }
+    }
+}
+
+impl DotConfig {
+    /// Parses a configuration file using the `CONFIG_` prefix.
+    pub fn parse(input: &str) -> Self {
+        Self::parse_with_prefix(input, DEFAULT_PREFIX)
+    }
+
+    /// Parses a configuration file using a custom prefix. Parsing never fails, lines that cannot be understood are kept as [LineKind::Unknown].
+    pub fn parse_with_prefix(input: &str, prefix: &str) -> Self {
+        let mut lines = vec![];
+        let mut offset = 0;
+        for (index, chunk) in input.split_inclusive('\n').enumerate() {
+            let (raw, ending) = match chunk.strip_suffix("\r\n") {
+                Some(raw) => (raw, "\r\n"),
+                None => match chunk.strip_suffix('\n') {
+                    Some(raw) => (raw, "\n"),
+                    None => (chunk, ""),
+                },
+            };
+            lines.push(Line {
+                kind: parse_line(raw, prefix),
+                raw: raw.to_string(),
+                ending: ending.to_string(),
+                span: LineSpan {
+                    line: index + 1,
+                    offset,
+                    length: raw.len(),
+                },
+            });
+            offset += chunk.len();
+        }
+        Self {
+            prefix: prefix.to_string(),
+            lines,
+        }
+    }
+
+    /// Reads and parses a configuration file.
+    pub fn from_file(path: impl AsRef<Path>, prefix: &str) -> io::Result<Self> {
+        Ok(Self::parse_with_prefix(
+            &std::fs::read_to_string(path)?,
+            prefix,
+        ))
+    }
+
+    /// Writes the configuration file, unmodified lines are written as they were read.
+    pub fn write_to(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for line in &self.lines {
+            writer.write_all(line.raw.as_bytes())?;
+            writer.write_all(line.ending.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the configuration to a file.
+    pub fn write_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_to(io::BufWriter::new(std::fs::File::create(path)?))
+    }
+
+    /// Returns the value of a symbol. When a symbol is assigned several times, the last assignment wins like in the C implementation.
+    pub fn get(&self, symbol: &str) -> Option<SymbolValue> {
+        let symbol = symbol.strip_prefix(&self.prefix).unwrap_or(symbol);
+        self.lines
+            .iter()
+            .rev()
+            .find(|l| l.symbol() == Some(symbol))
+            .and_then(|l| l.value())
+    }
+
+    /// Iterates over the symbols and their values, in the order of the file.
+    pub fn values(&self) -> impl Iterator<Item = (&str, SymbolValue)> {
+        self.lines
+            .iter()
+            .filter_map(|l| Some((l.symbol()?, l.value()?)))
+    }
+
+    /// Sets the value of a symbol. The last line assigning the symbol is replaced, otherwise a new line is appended.
+    /// Setting a tristate symbol to `n` writes a `# CONFIG_FOO is not set` line.
+    pub fn set(&mut self, symbol: &str, value: SymbolValue) {
+        let symbol = symbol
+            .strip_prefix(&self.prefix)
+            .unwrap_or(symbol)
+            .to_string();
+        let (kind, raw) = match value {
+            SymbolValue::Tristate(Tristate::No) => (
+                LineKind::NotSet {
+                    symbol: symbol.clone(),
+                },
+                format!("# {}{} is not set", self.prefix, symbol),
+            ),
+            value => (
+                LineKind::Assignment {
+                    symbol: symbol.clone(),
+                    value: value.clone(),
+                },
+                format!("{}{}={}", self.prefix, symbol, format_value(&value)),
+            ),
+        };
+        match self
+            .lines
+            .iter_mut()
+            .rev()
+            .find(|l| l.symbol() == Some(symbol.as_str()))
+        {
+            Some(line) => {
+                line.kind = kind;
+                line.raw = raw;
+                line.span.length = line.raw.len();
+            }
+            None => {
+                if let Some(last) = self.lines.last_mut() {
+                    if last.ending.is_empty() {
+                        last.ending = "\n".to_string();
+                    }
+                }
+                let span = match self.lines.last() {
+                    Some(last) => LineSpan {
+                        line: last.span.line + 1,
+                        offset: last.span.offset + last.raw.len() + last.ending.len(),
+                        length: raw.len(),
+                    },
+                    None => LineSpan {
+                        line: 1,
+                        offset: 0,
+                        length: raw.len(),
+                    },
+                };
+                self.lines.push(Line {
+                    kind,
+                    raw,
+                    ending: "\n".to_string(),
+                    span,
+                });
+            }
+        }
+    }
+
+    /// Checks the values against the symbols defined in the Kconfig files.
+    pub fn check(&self, table: &SymbolTable) -> Vec<ConfigIssue> {
+        let mut issues = vec![];
+        let mut seen: std::collections::HashMap<&str, LineSpan> = Default::default();
+        for line in &self.lines {
+            let Some(symbol) = line.symbol() else {
+                continue;
+            };
+            let issue = |kind| ConfigIssue {
+                symbol: symbol.to_string(),
+                span: line.span,
+                kind,
+            };
+            if let Some(previous) = seen.insert(symbol, line.span) {
+                issues.push(issue(ConfigIssueKind::Duplicate { previous }));
+            }
+            let Some(record) = table.get(symbol) else {
+                issues.push(issue(ConfigIssueKind::UnknownSymbol));
+                continue;
+            };
+            let Some(expected) = record.r#type() else {
+                issues.push(issue(ConfigIssueKind::Untyped));
+                continue;
+            };
+            if let LineKind::Assignment { value, .. } = &line.kind {
+                if !is_valid(expected, value) {
+                    issues.push(issue(ConfigIssueKind::InvalidValue {
+                        expected,
+                        value: value.clone(),
+                    }));
+                }
+            }
+        }
+        issues
+    }
+}
+
+impl SymbolProvider for DotConfig {
+    fn value(&self, symbol: &str) -> Option<SymbolValue> {
+        self.get(symbol)
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for DotConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            write!(f, "{}{}", line.raw, line.ending)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_line(raw: &str, prefix: &str) -> LineKind {
+    let line = raw.trim();
+    if line.is_empty() {
+        return LineKind::Blank;
+    }
+    if let Some(comment) = line.strip_prefix('#') {
+        return match comment
+            .trim_start()
+            .strip_prefix(prefix)
+            .and_then(|c| c.strip_suffix(" is not set"))
+        {
+            Some(symbol) if is_symbol(symbol) => LineKind::NotSet {
+                symbol: symbol.to_string(),
+            },
+            _ => LineKind::Comment,
+        };
+    }
+    let Some((symbol, value)) = line.strip_prefix(prefix).and_then(|l| l.split_once('=')) else {
+        return LineKind::Unknown;
+    };
+    if !is_symbol(symbol) {
+        return LineKind::Unknown;
+    }
+    match parse_value(value) {
+        Some(value) => LineKind::Assignment {
+            symbol: symbol.to_string(),
+            value,
+        },
+        None => LineKind::Unknown,
+    }
+}
+
+fn is_symbol(symbol: &str) -> bool {
+    !symbol.is_empty()
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_value(value: &str) -> Option<SymbolValue> {
+    match value {
+        "y" => return Some(SymbolValue::Tristate(Tristate::Yes)),
+        "m" => return Some(SymbolValue::Tristate(Tristate::Module)),
+        "n" => return Some(SymbolValue::Tristate(Tristate::No)),
+        _ => {}
+    }
+    if let Some(quoted) = value.strip_prefix('"') {
+        return unescape(quoted.strip_suffix('"')?).map(SymbolValue::String);
+    }
+    if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some(SymbolValue::Hex(value.to_string()));
+        }
+    }
+    match value.parse::<i64>() {
+        Ok(i) if i.to_string() == value => Some(SymbolValue::Int(i)),
+        // unquoted strings are accepted by the C implementation
+        _ => Some(SymbolValue::String(value.to_string())),
+    }
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.push(chars.next()?),
+            '"' => return None,
+            c => result.push(c),
+        }
+    }
+    Some(result)
+}
+
+/// Formats a value the way it is written in a configuration file, strings are quoted and escaped.
+pub fn format_value(value: &SymbolValue) -> String {
+    match value {
+        SymbolValue::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        value => value.raw(),
+    }
+}
+
+fn is_valid(expected: SymbolType, value: &SymbolValue) -> bool {
+    match (expected, value) {
+        (SymbolType::Bool, SymbolValue::Tristate(t)) => *t != Tristate::Module,
+        (SymbolType::Tristate, SymbolValue::Tristate(_)) => true,
+        (SymbolType::Int, SymbolValue::Int(_)) => true,
+        (SymbolType::Hex, SymbolValue::Hex(_)) => true,
+        // the 0x prefix is optional for hex values
+        (SymbolType::Hex, SymbolValue::Int(i)) => *i >= 0,
+        (SymbolType::Hex, SymbolValue::String(s)) => {
+            !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        (SymbolType::String, _) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    attribute::SymbolValue,
+    dotconfig::{ConfigIssueKind, DotConfig, LineKind, LineSpan},
+    kconfig::parse_kconfig,
+    symbol_table::SymbolType,
+    tristate::Tristate,
+    KconfigInput, SymbolTable,
+};
+
+#[test]
+fn test_dotconfig_parse() {
+    let input = r#"#
+# Automatically generated file; DO NOT EDIT.
+#
+CONFIG_MODULES=y
+CONFIG_EXT4_FS=m
+# CONFIG_DEBUG is not set
+
+CONFIG_HZ=250
+CONFIG_PHYSICAL_START=0x1000000
+CONFIG_LOCALVERSION="-custom \"quoted\""
+garbage
+"#;
+    let config = DotConfig::parse(input);
+    assert_eq!(config.lines.len(), 11);
+    assert_eq!(config.lines[0].kind, LineKind::Comment);
+    assert_eq!(config.lines[6].kind, LineKind::Blank);
+    assert_eq!(config.lines[10].kind, LineKind::Unknown);
+    assert_eq!(
+        config.lines[3].span,
+        LineSpan {
+            line: 4,
+            offset: 49,
+            length: 16
+        }
+    );
+    assert_eq!(
+        config.get("MODULES"),
+        Some(SymbolValue::Tristate(Tristate::Yes))
+    );
+    assert_eq!(
+        config.get("CONFIG_EXT4_FS"),
+        Some(SymbolValue::Tristate(Tristate::Module))
+    );
+    assert_eq!(
+        config.get("DEBUG"),
+        Some(SymbolValue::Tristate(Tristate::No))
+    );
+    assert_eq!(config.get("HZ"), Some(SymbolValue::Int(250)));
+    assert_eq!(
+        config.get("PHYSICAL_START"),
+        Some(SymbolValue::Hex("0x1000000".to_string()))
+    );
+    assert_eq!(
+        config.get("LOCALVERSION"),
+        Some(SymbolValue::String("-custom \"quoted\"".to_string()))
+    );
+    assert_eq!(config.get("UNKNOWN"), None);
+    assert_eq!(config.to_string(), input);
+}
+
+#[test]
+fn test_dotconfig_write_is_byte_faithful() {
+    let input = "CONFIG_A=y\r\n#   odd   comment\n  CONFIG_B=1  \nCONFIG_C=\"x\"";
+    let config = DotConfig::parse(input);
+    let mut output = vec![];
+    config.write_to(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), input);
+}
+
+#[test]
+fn test_dotconfig_custom_prefix() {
+    let config = DotConfig::parse_with_prefix("CONFIG_A=y\nCT_B=y\n# CT_C is not set\n", "CT_");
+    assert_eq!(config.lines[0].kind, LineKind::Unknown);
+    assert_eq!(config.get("B"), Some(SymbolValue::Tristate(Tristate::Yes)));
+    assert_eq!(
+        config.get("CT_C"),
+        Some(SymbolValue::Tristate(Tristate::No))
+    );
+}
+
+#[test]
+fn test_dotconfig_set() {
+    let mut config = DotConfig::parse("CONFIG_A=y\n# CONFIG_B is not set\nCONFIG_C=1");
+    config.set("A", SymbolValue::Tristate(Tristate::No));
+    config.set("CONFIG_B", SymbolValue::String("a\"b".to_string()));
+    config.set("D", SymbolValue::Hex("0x10".to_string()));
+    assert_eq!(
+        config.to_string(),
+        "# CONFIG_A is not set\nCONFIG_B=\"a\\\"b\"\nCONFIG_C=1\nCONFIG_D=0x10\n"
+    );
+    assert_eq!(config.lines[3].span.line, 4);
+    assert_eq!(
+        DotConfig::parse(&config.to_string()).get("B"),
+        Some(SymbolValue::String("a\"b".to_string()))
+    );
+}
+
+#[test]
+fn test_dotconfig_check() {
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(
+        r#"
+config BOOL
+    bool "bool"
+config TRI
+    tristate "tri"
+config NUM
+    int "num"
+config ADDR
+    hex "addr"
+"#,
+        Default::default(),
+    ))
+    .unwrap();
+    let table = SymbolTable::new(&kconfig);
+    let config = DotConfig::parse(
+        "CONFIG_BOOL=m\nCONFIG_TRI=m\nCONFIG_NUM=abc\nCONFIG_ADDR=1000\nCONFIG_OTHER=y\nCONFIG_TRI=y\n",
+    );
+    let issues = config.check(&table);
+    let kinds: Vec<_> = issues
+        .iter()
+        .map(|i| (i.symbol.as_str(), i.kind.clone()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (
+                "BOOL",
+                ConfigIssueKind::InvalidValue {
+                    expected: SymbolType::Bool,
+                    value: SymbolValue::Tristate(Tristate::Module)
+                }
+            ),
+            (
+                "NUM",
+                ConfigIssueKind::InvalidValue {
+                    expected: SymbolType::Int,
+                    value: SymbolValue::String("abc".to_string())
+                }
+            ),
+            ("OTHER", ConfigIssueKind::UnknownSymbol),
+            (
+                "TRI",
+                ConfigIssueKind::Duplicate {
+                    previous: config.lines[1].span
+                }
+            ),
+        ]
+    );
+    assert_eq!(issues[3].span.line, 6);
+}
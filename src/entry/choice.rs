@@ -3,15 +3,16 @@ use nom::{
     bytes::complete::tag,
     combinator::map,
     multi::many0,
-    sequence::{delimited, pair},
+    sequence::{pair, preceded},
     IResult, Parser,
 };
 
 #[cfg(feature = "named-choice")]
 use nom::{
-    character::complete::{alphanumeric1, one_of},
-    combinator::recognize,
+    character::complete::{alphanumeric1, one_of, space0},
+    combinator::{not, recognize},
     multi::many1,
+    sequence::terminated,
 };
 #[cfg(feature = "deserialize")]
 use serde::Deserialize;
@@ -22,19 +23,19 @@ use serde::Serialize;
 use crate::attribute::string::parse_string;
 use crate::{
     attribute::{optional::parse_optional, parse_attribute, r#type::parse_type, Attribute},
+    span::{ignore_span, spanned, Span},
     util::ws,
     Entry, KconfigInput,
 };
 
-use super::parse_entry;
+use super::parse_block_entries;
 
 /// This defines a choice group and accepts any of the above attributes as options. A choice can only be of type bool or tristate. If no type is specified for a choice, its type will be determined by the type of the first choice element in the group or remain unknown if none of the choice elements have a type specified, as well.
 ///
 /// While a boolean choice only allows a single config entry to be selected, a tristate choice also allows any number of config entries to be set to 'm'. This can be used if multiple drivers for a single hardware exists and only a single driver can be compiled/loaded into the kernel, but all drivers can be compiled as modules.
 ///
 /// A choice accepts another option "optional", which allows to set the choice to 'n' and no entry needs to be selected. If no [symbol](crate::symbol::Symbol) is associated with a choice, then you can not have multiple definitions of that choice. If a [symbol](crate::symbol::Symbol) is associated to the choice, then you may define the same choice (i.e. with the same entries) in another place.
-#[derive(Debug, Clone, Default, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Choice {
@@ -43,23 +44,44 @@ pub struct Choice {
     pub name: Option<String>,
     pub options: Vec<Attribute>,
     pub entries: Vec<Entry>,
+    pub span: Span,
 }
 
+ignore_span!(Choice {
+    #[cfg(feature = "named-choice")]
+    name,
+    options,
+    entries,
+});
+
 fn parse_choice_attributes(input: KconfigInput) -> IResult<KconfigInput, Vec<Attribute>> {
     ws(many0(alt((
         parse_attribute,
         parse_type,
-        map(ws(parse_optional), |_| Attribute::Optional),
+        map(ws(spanned(parse_optional)), |(_, span)| {
+            Attribute::Optional(span)
+        }),
     ))))
     .parse(input)
 }
 
+/// A choice without name, the keyword must not be followed by a name on the same line.
+///
+/// With [diagnostics](crate::diagnostic), the entries of the block are parsed recovering from errors, so
+/// `choice NAME` would be accepted as an unnamed choice with an unknown keyword `NAME`, instead of
+/// falling back to [parse_named_choice].
+fn parse_choice_keyword(input: KconfigInput) -> IResult<KconfigInput, KconfigInput> {
+    #[cfg(feature = "named-choice")]
+    return terminated(tag("choice"), not((space0, alphanumeric1))).parse(input);
+    #[cfg(not(feature = "named-choice"))]
+    return tag("choice").parse(input);
+}
+
 fn parse_choice_simple(input: KconfigInput) -> IResult<KconfigInput, Choice> {
     map(
-        delimited(
-            tag("choice"),
-            pair(parse_choice_attributes, many0(ws(parse_entry))),
-            ws(tag("endchoice")),
+        preceded(
+            parse_choice_keyword,
+            pair(parse_choice_attributes, parse_block_entries("endchoice")),
         ),
         |(options, entries)| {
             #[cfg(feature = "named-choice")]
@@ -67,39 +89,47 @@ fn parse_choice_simple(input: KconfigInput) -> IResult<KconfigInput, Choice> {
                 options,
                 entries,
                 name: None,
+                span: Span::default(),
             };
             #[cfg(not(feature = "named-choice"))]
-            return Choice { options, entries };
+            return Choice {
+                options,
+                entries,
+                span: Span::default(),
+            };
         },
     )
     .parse(input)
 }
 
 pub fn parse_choice(input: KconfigInput) -> IResult<KconfigInput, Choice> {
-    alt((
-        parse_choice_simple,
-        #[cfg(feature = "named-choice")]
-        parse_named_choice,
-    ))
+    map(
+        spanned(alt((
+            parse_choice_simple,
+            #[cfg(feature = "named-choice")]
+            parse_named_choice,
+        ))),
+        |(choice, span)| Choice { span, ..choice },
+    )
     .parse(input)
 }
 
 #[cfg(feature = "named-choice")]
 pub fn parse_named_choice(input: KconfigInput) -> IResult<KconfigInput, Choice> {
     map(
-        delimited(
+        preceded(
             tag("choice"),
             (
                 ws(parse_choice_name),
                 parse_choice_attributes,
-                many0(ws(parse_entry)),
+                parse_block_entries("endchoice"),
             ),
-            ws(tag("endchoice")),
         ),
         |(name, options, entries)| Choice {
             options,
             entries,
             name: Some(name),
+            span: Span::default(),
         },
     )
     .parse(input)
@@ -22,8 +22,9 @@ fn test_parse_choice_optional() {
         Ok((
             "",
             Choice {
-                options: vec!(Attribute::Optional),
-                entries: vec!()
+                options: vec!(Attribute::Optional(Default::default())),
+                entries: vec!(),
+                span: Default::default()
             }
         ))
     )
@@ -64,7 +65,8 @@ endchoice"#,
                 options: vec!(
                     Attribute::Type(ConfigType {
                         r#type: Type::Bool(Some("MTU3 Mode Selection".to_string())),
-                        r#if: None
+                        r#if: None,
+                        span: Default::default()
                     }),
                     Attribute::Default(DefaultAttribute {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
@@ -81,7 +83,8 @@ endchoice"#,
                                     ))),
                                 ))
                             )))
-                        ))))
+                        )))),
+                        span: Default::default()
                     })
                 ),
                 entries: vec!(
@@ -89,15 +92,19 @@ endchoice"#,
                         symbol: "USB_MTU3_HOST".to_string(),
                         attributes: vec!(Attribute::Type(ConfigType {
                             r#type: Type::Bool(Some("Host only mode".to_string())),
-                            r#if: None
-                        }))
+                            r#if: None,
+                            span: Default::default()
+                        })),
+                        span: Default::default()
                     }),
                     Entry::Comment(Comment {
                         prompt: "Gadget/Dual-role mode requires USB Gadget support to be enabled"
                             .to_string(),
-                        dependencies: vec!()
+                        dependencies: vec!(),
+                        span: Default::default()
                     })
-                )
+                ),
+                span: Default::default()
             }
         ))
     )
@@ -121,15 +128,19 @@ endchoice"#,
                 name: Some("DEFAULT_SCREEN_ROTATION".to_string()),
                 options: vec!(Attribute::Prompt(Prompt {
                     prompt: "Default screen orientation".to_string(),
-                    r#if: None
+                    r#if: None,
+                    span: Default::default()
                 }),),
                 entries: vec!(Entry::Config(Config {
                     symbol: "DEFAULT_SCREEN_ROTATION_NONE".to_string(),
                     attributes: vec!(Attribute::Type(ConfigType {
                         r#type: Type::Bool(Some("Non-rotated".to_string())),
-                        r#if: None
-                    }))
-                }))
+                        r#if: None,
+                        span: Default::default()
+                    })),
+                    span: Default::default()
+                })),
+                span: Default::default()
             }
         ))
     )
@@ -153,15 +164,19 @@ endchoice"#,
                 name: None,
                 options: vec!(Attribute::Prompt(Prompt {
                     prompt: "CBFS prefix to use".to_string(),
-                    r#if: None
+                    r#if: None,
+                    span: Default::default()
                 }),),
                 entries: vec!(Entry::Config(Config {
                     symbol: "CBFS_PREFIX_FALLBACK".to_string(),
                     attributes: vec!(Attribute::Type(ConfigType {
                         r#type: Type::Bool(Some("fallback".to_string())),
-                        r#if: None
-                    }))
-                }))
+                        r#if: None,
+                        span: Default::default()
+                    })),
+                    span: Default::default()
+                })),
+                span: Default::default()
             }
         ))
     )
@@ -181,6 +196,7 @@ fn test_named_choice_with_double_quotes() {
                 name: Some("MCUBOOT_UTIL_LOG_LEVEL_CHOICE".to_string()),
                 options: vec!(),
                 entries: vec!(),
+                span: Default::default()
             }
         ))
     )
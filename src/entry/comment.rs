@@ -9,30 +9,37 @@ use crate::{
         depends_on::{parse_depends_on, DependsOn},
         prompt::parse_prompt_value,
     },
+    span::{ignore_span, spanned, Span},
     util::ws,
     KconfigInput,
 };
 
 /// This defines a comment which is displayed to the user during the configuration process and is also echoed to the output files. The only possible options are dependencies.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Comment {
     pub prompt: String,
     pub dependencies: Vec<DependsOn>,
+    pub span: Span,
 }
 
+ignore_span!(Comment {
+    prompt,
+    dependencies,
+});
+
 pub fn parse_comment(input: KconfigInput) -> IResult<KconfigInput, Comment> {
     map(
-        (
+        spanned((
             ws(tag("comment")),
             ws(parse_prompt_value),
             many0(ws(parse_depends_on)),
-        ),
-        |(_, prompt, dependencies)| Comment {
+        )),
+        |((_, prompt, dependencies), span)| Comment {
             prompt: prompt.to_string(),
             dependencies,
+            span,
         },
     )
     .parse(input)
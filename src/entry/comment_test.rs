@@ -14,7 +14,8 @@ fn test_parse_comment() {
             "",
             Comment {
                 prompt: "Default contiguous memory area size:".to_string(),
-                dependencies: vec!()
+                dependencies: vec!(),
+                span: Default::default()
             }
         ))
     )
@@ -35,7 +36,9 @@ fn test_parse_comment_with_dependencies() {
                         Symbol::NonConstant("JVM".to_string())
                     )))),
                     r#if: None,
-                })
+                    span: Default::default()
+                }),
+                span: Default::default()
             }
         ))
     )
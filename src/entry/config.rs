@@ -14,20 +14,23 @@ use serde::Serialize;
 
 use crate::{
     attribute::{parse_attribute, r#type::parse_type, Attribute},
+    span::{ignore_span, spanned, Span},
     util::ws,
     KconfigInput,
 };
 
 /// This defines a config symbol.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Config {
     pub symbol: String,
     pub attributes: Vec<Attribute>,
+    pub span: Span,
 }
 
+ignore_span!(Config { symbol, attributes });
+
 #[macro_export]
 macro_rules! generic_config_parser {
     ($t:ident, $tag:expr, $fn:expr) => {{
@@ -36,13 +39,14 @@ macro_rules! generic_config_parser {
         use $crate::attribute::parse_attribute;
 
         map(
-            pair(
+            $crate::span::spanned(pair(
                 map(pair(ws(tag($tag)), ws(parse_config_symbol)), |(_, id)| id),
                 many0(ws(alt(($fn, parse_attribute)))),
-            ),
-            |(symbol, attributes)| $t {
+            )),
+            |((symbol, attributes), span)| $t {
                 symbol: symbol.to_string(),
                 attributes,
+                span,
             },
         )
     }};
@@ -58,13 +62,14 @@ pub(crate) fn parse_config_symbol(input: KconfigInput<'_>) -> IResult<KconfigInp
 
 pub fn parse_config(input: KconfigInput) -> IResult<KconfigInput, Config> {
     map(
-        pair(
+        spanned(pair(
             preceded(ws(tag("config")), ws(parse_config_symbol)),
             many0(ws(alt((parse_type, parse_attribute)))),
-        ),
-        |(symbol, attributes)| Config {
+        )),
+        |((symbol, attributes), span)| Config {
             symbol: symbol.to_string(),
             attributes,
+            span,
         },
     )
     .parse(input)
@@ -22,8 +22,10 @@ fn test_parse_config() {
                 symbol: "KVM".to_string(),
                 attributes: vec!(Attribute::Type(ConfigType {
                     r#type: Type::Hex(Some("wow".to_string())),
-                    r#if: None
-                }))
+                    r#if: None,
+                    span: Default::default()
+                })),
+                span: Default::default()
             }
         ))
     )
@@ -49,15 +51,18 @@ fn test_parse_config_no_type() {
                         ))),
                         r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                             Atom::Symbol(Symbol::NonConstant("64BIT".to_string()))
-                        ))))
+                        )))),
+                        span: Default::default()
                     }),
                     Attribute::Default(DefaultAttribute {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
                             Atom::Symbol(Symbol::Constant(ConstantSymbol::Integer(8)))
                         ))),
-                        r#if: None
+                        r#if: None,
+                        span: Default::default()
                     })
-                )
+                ),
+                span: Default::default()
             }
         ))
     )
@@ -75,8 +80,10 @@ fn test_parse_config_tristate() {
                 symbol: "RAPIDIO_ENUM_BASIC".to_string(),
                 attributes: vec!(Attribute::Type(ConfigType {
                     r#type: Type::Tristate(None),
-                    r#if: None
-                }))
+                    r#if: None,
+                    span: Default::default()
+                })),
+                span: Default::default()
             }
         ))
     )
@@ -104,6 +111,7 @@ fn test_parse_config_def_bool_multiline_expression() {
                             Atom::Symbol(Symbol::NonConstant("SH_CLK_CPG".to_string()))
                         ))),
                         r#if: None,
+                        span: Default::default()
                     }),
                     Attribute::Type(ConfigType {
                         r#type: Type::DefBool(Expression::Term(AndExpression::Term(Term::Atom(
@@ -129,9 +137,11 @@ fn test_parse_config_def_bool_multiline_expression() {
                             Term::Not(Atom::Symbol(Symbol::NonConstant(
                                 "CPU_SUBTYPE_SH7269".to_string()
                             ))),
-                        ))))
+                        )))),
+                        span: Default::default()
                     }),
-                )
+                ),
+                span: Default::default()
             }
         ))
     )
@@ -153,7 +163,8 @@ fn test_parse_config_bootparam_string() {
                 attributes: vec!(
                     Attribute::Type(ConfigType {
                         r#type: Type::String(Some("Kernel Boot Parameter".to_string())),
-                        r#if: None
+                        r#if: None,
+                        span: Default::default()
                     }),
                     Attribute::Default(DefaultAttribute {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
@@ -161,15 +172,18 @@ fn test_parse_config_bootparam_string() {
                                 "console=ttyS0,19200".to_string()
                             )))
                         ))),
-                        r#if: None
+                        r#if: None,
+                        span: Default::default()
                     }),
                     Attribute::DependsOn(DependsOn {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
                             Atom::Symbol(Symbol::NonConstant("BOOTPARAM".to_string()))
                         ))),
                         r#if: None,
+                        span: Default::default()
                     }),
-                )
+                ),
+                span: Default::default()
             }
         ))
     )
@@ -189,8 +203,10 @@ fn test_parse_config_name_with_hyphen() {
                 symbol: "ZEPHYR_TFLITE-MICRO_MODULE".to_string(),
                 attributes: vec!(Attribute::Type(ConfigType {
                     r#type: Type::Bool(None),
-                    r#if: None
-                }),)
+                    r#if: None,
+                    span: Default::default()
+                }),),
+                span: Default::default()
             }
         ))
     )
@@ -12,29 +12,33 @@ use serde::Serialize;
 use crate::{
     attribute::{parse_default, DefaultAttribute},
     entry::config::parse_config_symbol,
+    span::{ignore_span, spanned, Span},
     util::ws,
     KconfigInput,
 };
 
 /// This defines a config symbol.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct ConfigDefault {
     pub symbol: String,
     pub default: DefaultAttribute,
+    pub span: Span,
 }
 
+ignore_span!(ConfigDefault { symbol, default });
+
 pub fn parse_configdefault(input: KconfigInput) -> IResult<KconfigInput, ConfigDefault> {
     map(
-        pair(
+        spanned(pair(
             preceded(ws(tag("configdefault")), ws(parse_config_symbol)),
             parse_default,
-        ),
-        |(symbol, default_attribute)| ConfigDefault {
+        )),
+        |((symbol, default_attribute), span)| ConfigDefault {
             symbol: symbol.to_string(),
             default: default_attribute,
+            span,
         },
     )
     .parse(input)
@@ -22,8 +22,10 @@ fn test_parse_configdefault() {
                     )))),
                     r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                         Atom::Symbol(Symbol::NonConstant("BAR".to_string()))
-                    ))))
-                }
+                    )))),
+                    span: Default::default()
+                },
+                span: Default::default()
             }
         ))
     )
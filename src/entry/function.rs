@@ -12,30 +12,37 @@ use serde::Deserialize;
 #[cfg(feature = "serialize")]
 use serde::Serialize;
 
-use crate::{util::ws, KconfigInput};
+use crate::{
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 
-#[derive(Debug, PartialEq, Clone, Default)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Function {
     pub name: String,
     pub body: String,
+    pub span: Span,
 }
 
+ignore_span!(Function { name, body });
+
 pub fn parse_function(input: KconfigInput) -> IResult<KconfigInput, Function> {
     map(
-        (
+        spanned((
             recognize(ws(many1(alt((
                 alphanumeric1::<KconfigInput, _>,
                 recognize(one_of("_$()")),
             ))))),
             ws(tag("=")),
             ws(terminated(not_line_ending, line_ending)),
-        ),
-        |(l, _, o)| Function {
+        )),
+        |((l, _, o), span)| Function {
             name: l.trim().to_string(),
             body: o.to_string(),
+            span,
         },
     )
     .parse(input)
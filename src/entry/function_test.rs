@@ -12,7 +12,8 @@ fn test_parse_function() {
             "",
             Function {
                 name: "greeting".to_string(),
-                body: "$(1), my name is $(2).".to_string()
+                body: "$(1), my name is $(2).".to_string(),
+                span: Default::default()
             }
         ))
     )
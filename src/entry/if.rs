@@ -1,10 +1,4 @@
-use nom::{
-    bytes::complete::tag,
-    combinator::{cut, map},
-    multi::many0,
-    sequence::{pair, terminated},
-    IResult, Parser,
-};
+use nom::{combinator::map, sequence::pair, IResult, Parser};
 #[cfg(feature = "deserialize")]
 use serde::Deserialize;
 #[cfg(feature = "serialize")]
@@ -12,21 +6,24 @@ use serde::Serialize;
 
 use crate::{
     attribute::expression::{parse_if_expression, Expression},
+    span::{ignore_span, spanned, Span},
     util::ws,
     KconfigInput,
 };
 
-use super::{parse_entry, Entry};
+use super::{parse_block_entries, Entry};
 
 /// This defines an if block. The dependency expression [expr]((crate::attribute::expression)) is appended to all enclosed menu entries.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct If {
     pub condition: Expression,
     pub entries: Vec<Entry>,
+    pub span: Span,
 }
+
+ignore_span!(If { condition, entries });
 /// it parses a if block.
 ///
 /// # Example
@@ -46,18 +43,20 @@ pub struct If {
 ///             condition: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
 ///                 Symbol::NonConstant("NET_VENDOR_AMD".to_string())
 ///             )))),
-///             entries: vec!(Entry::Comment(Comment { prompt: "Support of PCI".to_string(), dependencies: vec!() }))
+///             entries: vec!(Entry::Comment(Comment { prompt: "Support of PCI".to_string(), dependencies: vec!(), span: Default::default() })),
+///             span: Default::default(),
 ///         }
 ///     ))
 /// )
 /// ```
 pub fn parse_if(input: KconfigInput) -> IResult<KconfigInput, If> {
     map(
-        pair(
-            ws(parse_if_expression),
-            terminated(cut(many0(parse_entry)), ws(tag("endif"))),
-        ),
-        |(condition, entries)| If { condition, entries },
+        spanned(pair(ws(parse_if_expression), parse_block_entries("endif"))),
+        |((condition, entries), span)| If {
+            condition,
+            entries,
+            span,
+        },
     )
     .parse(input)
 }
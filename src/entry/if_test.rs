@@ -26,8 +26,12 @@ fn test_parse_if_entry() {
                     kconfigs: vec![Kconfig {
                         file: "$(VAR)/Kconfig".to_string(),
                         ..Default::default()
-                    }]
-                }))
+                    }],
+                    span: Default::default(),
+                    path: "$(VAR)/Kconfig".to_string(),
+                    unresolved: None,
+                })),
+                span: Default::default()
             }
         ))
     )
@@ -56,14 +60,18 @@ fn test_parse_if_entry_with_config() {
                     attributes: vec!(
                         Attribute::Type(ConfigType {
                             r#type: Type::Bool(None),
-                            r#if: None
+                            r#if: None,
+                            span: Default::default()
                         }),
                         Attribute::Select(Select {
                             symbol: "KVM_MMIO".to_string(),
-                            r#if: None
+                            r#if: None,
+                            span: Default::default()
                         })
-                    )
-                }))
+                    ),
+                    span: Default::default()
+                })),
+                span: Default::default()
             }
         ))
     )
@@ -4,23 +4,31 @@ use serde::Deserialize;
 #[cfg(feature = "serialize")]
 use serde::Serialize;
 
-use crate::{attribute::parse_prompt_value, util::ws, KconfigInput};
+use crate::{
+    attribute::parse_prompt_value,
+    span::{ignore_span, spanned, Span},
+    util::ws,
+    KconfigInput,
+};
 
 pub fn parse_main_menu(input: KconfigInput) -> IResult<KconfigInput, MainMenu> {
     map(
-        pair(ws(tag("mainmenu")), ws(parse_prompt_value)),
-        |(_, prompt)| MainMenu {
+        spanned(pair(ws(tag("mainmenu")), ws(parse_prompt_value))),
+        |((_, prompt), span)| MainMenu {
             prompt: prompt.to_string(),
+            span,
         },
     )
     .parse(input)
 }
 
 /// This sets the config program's title bar if the config program chooses to use it. It should be placed at the top of the configuration, before any other statement.
-#[derive(Debug, Default, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct MainMenu {
     pub prompt: String,
+    pub span: Span,
 }
+
+ignore_span!(MainMenu { prompt });
@@ -13,6 +13,7 @@ fn test_parse_main_menu() {
             "",
             MainMenu {
                 prompt: "BPF subsystem".to_string(),
+                span: Default::default()
             }
         ))
     )
@@ -3,7 +3,7 @@ use nom::{
     bytes::complete::tag,
     combinator::{cut, map},
     multi::many0,
-    sequence::{pair, preceded, terminated},
+    sequence::{pair, preceded},
     IResult, Parser,
 };
 #[cfg(feature = "deserialize")]
@@ -16,15 +16,15 @@ use crate::{
         depends_on::DependsOn, parse_depends_on, parse_prompt_value, parse_visible,
         visible::Visible, Attribute,
     },
+    span::{ignore_span, spanned, Span},
     util::ws,
     KconfigInput,
 };
 
-use super::{parse_entry, Entry};
+use super::{parse_block_entries, Entry};
 
 /// This defines a menu block, see ["Menu structure"](https://www.kernel.org/doc/html/latest/kbuild/kconfig-language.html#menu-structure) for more information. The only possible options are dependencies and "visible" attributes.
-#[derive(Debug, Clone, Default, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Menu {
@@ -36,8 +36,16 @@ pub struct Menu {
     pub visible: Option<Visible>,
     pub depends_on: Vec<DependsOn>,
     pub entries: Vec<Entry>,
+    pub span: Span,
 }
 
+ignore_span!(Menu {
+    prompt,
+    visible,
+    depends_on,
+    entries,
+});
+
 fn parse_menu_attributes(input: KconfigInput) -> IResult<KconfigInput, Vec<Attribute>> {
     many0(alt((
         map(ws(parse_depends_on), Attribute::DependsOn),
@@ -47,6 +55,14 @@ fn parse_menu_attributes(input: KconfigInput) -> IResult<KconfigInput, Vec<Attri
 }
 
 pub fn parse_menu(input: KconfigInput) -> IResult<KconfigInput, Menu> {
+    map(spanned(parse_menu_inner), |(menu, span)| Menu {
+        span,
+        ..menu
+    })
+    .parse(input)
+}
+
+fn parse_menu_inner(input: KconfigInput) -> IResult<KconfigInput, Menu> {
     let (input, mut menu) = map(
         preceded(
             ws(tag("menu")),
@@ -69,8 +85,7 @@ pub fn parse_menu(input: KconfigInput) -> IResult<KconfigInput, Menu> {
     )
     .parse(input)?;
 
-    let (input, entries) =
-        cut(terminated(many0(ws(parse_entry)), ws(tag("endmenu")))).parse(input)?;
+    let (input, entries) = cut(parse_block_entries("endmenu")).parse(input)?;
     menu.entries = entries;
     Ok((input, menu))
 }
@@ -1,6 +1,6 @@
 use crate::{
     assert_parsing_eq, assert_parsing_fail,
-    attribute::{depends_on::DependsOn, AndExpression, Atom, Expression, Term},
+    attribute::{depends_on::DependsOn, visible::Visible, AndExpression, Atom, Expression, Term},
     entry::{parse_menu, Menu},
     symbol::Symbol,
 };
@@ -32,9 +32,12 @@ fn test_parse_menu_visible() {
             "",
             Menu {
                 prompt: "BPF subsystem".to_string(),
-                visible: Some(Some(Expression::Term(AndExpression::Term(Term::Atom(
-                    Atom::Symbol(Symbol::NonConstant("EXPERT".to_string()))
-                ))))),
+                visible: Some(Visible {
+                    r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
+                        Atom::Symbol(Symbol::NonConstant("EXPERT".to_string()))
+                    )))),
+                    span: Default::default()
+                }),
                 ..Default::default()
             }
         ))
@@ -62,7 +65,8 @@ fn test_parse_menu_depends_on() {
                     expression: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                         Symbol::NonConstant("MODULES".to_string())
                     )))),
-                    r#if: None
+                    r#if: None,
+                    span: Default::default()
                 }),
                 ..Default::default()
             }
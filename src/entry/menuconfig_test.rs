@@ -24,7 +24,9 @@ fn test_parse_menuconfig() {
                         Atom::Symbol(Symbol::Constant(ConstantSymbol::Boolean(true)))
                     )))),
                     r#if: None,
-                }))
+                    span: Default::default()
+                })),
+                span: Default::default()
             }
         ))
     )
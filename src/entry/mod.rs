@@ -1,7 +1,15 @@
 //! Module defining the different Kconfig entries.
 //! Most entries define a config option; all other entries help to organize them. [https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#menu-entries](https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#menu-entries)
 
-use nom::{branch::alt, combinator::map, multi::many0, sequence::delimited, IResult, Parser};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::space0,
+    combinator::{cut, map},
+    multi::many0,
+    sequence::{delimited, terminated},
+    IResult, Input, Parser,
+};
 #[cfg(feature = "deserialize")]
 use serde::Deserialize;
 #[cfg(feature = "serialize")]
@@ -12,6 +20,9 @@ use crate::entry::configdefault::{parse_configdefault, ConfigDefault};
 
 use crate::{
     attribute::function::{parse_function_call, FunctionCall},
+    diagnostic::{describe_error, line_span, report, Diagnostic},
+    preprocess::Help,
+    span::Span,
     util::{ws, ws_comment},
     KconfigInput,
 };
@@ -25,7 +36,7 @@ pub use self::{
     menu::{parse_menu, Menu},
     menuconfig::{parse_menu_config, MenuConfig},
     r#if::{parse_if, If},
-    source::{parse_source, Source},
+    source::{parse_source, Source, SourceKind, SourceMode, SourceReference},
     variable::{parse_variable_assignment, Value, VariableAssignment, VariableIdentifier},
 };
 
@@ -74,6 +85,30 @@ pub enum Entry {
     OrSource(OrSource),
 }
 
+impl Entry {
+    /// The location of the entry.
+    pub fn span(&self) -> &Span {
+        match self {
+            Entry::Config(config) | Entry::MenuConfig(config) => &config.span,
+            Entry::Choice(choice) => &choice.span,
+            Entry::Menu(menu) => &menu.span,
+            Entry::Comment(comment) => &comment.span,
+            Entry::Source(source) => &source.span,
+            Entry::VariableAssignment(assignment) => &assignment.span,
+            Entry::FunctionCall(call) => &call.span,
+            Entry::Function(function) => &function.span,
+            Entry::If(r#if) => &r#if.span,
+            Entry::MainMenu(main_menu) => &main_menu.span,
+            #[cfg(feature = "kconfiglib")]
+            Entry::ConfigDefault(config_default) => &config_default.span,
+            #[cfg(feature = "kconfiglib")]
+            Entry::OSource(source) | Entry::RSource(source) | Entry::OrSource(source) => {
+                &source.span
+            }
+        }
+    }
+}
+
 pub fn parse_entry(input: KconfigInput) -> IResult<KconfigInput, Entry> {
     alt((
         #[cfg(feature = "kconfiglib")]
@@ -100,6 +135,134 @@ pub fn parse_entry(input: KconfigInput) -> IResult<KconfigInput, Entry> {
     .parse(input)
 }
 
+/// Keywords where the parsing resumes after an error.
+const RECOVERY_KEYWORDS: &[&str] = &[
+    "config",
+    "menuconfig",
+    "configdefault",
+    "choice",
+    "endchoice",
+    "menu",
+    "endmenu",
+    "if",
+    "endif",
+    "comment",
+    "mainmenu",
+    "source",
+    "osource",
+    "rsource",
+    "orsource",
+];
+
+fn first_word<'a>(input: &KconfigInput<'a>) -> &'a str {
+    let fragment: &'a str = input.fragment();
+    fragment
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Skips the current line and the following ones until a line starting with a keyword of [RECOVERY_KEYWORDS].
+/// Lines of help texts are skipped whatever their first word.
+fn skip_to_next_entry(input: KconfigInput) -> KconfigInput {
+    let mut input = input;
+    let mut help = Help::Outside;
+    loop {
+        let next_line = match input.fragment().find('\n') {
+            Some(index) => index + 1,
+            None => input.fragment().len(),
+        };
+        if help == Help::Outside
+            && matches!(input.fragment()[..next_line].trim(), "help" | "---help---")
+        {
+            help = Help::Start;
+        }
+        input = input.take_from(next_line);
+        let line = input
+            .fragment()
+            .split_inclusive('\n')
+            .next()
+            .unwrap_or_default();
+        help = help.next(line);
+        let (rest, _) = space0::<_, nom::error::Error<_>>(input.clone()).unwrap();
+        if rest.fragment().is_empty()
+            || (help == Help::Outside && RECOVERY_KEYWORDS.contains(&first_word(&rest)))
+        {
+            return input;
+        }
+    }
+}
+
+/// Parses entries until the keyword `end` (consumed), or the end of the file if there is none.
+/// When the input is parsed with [diagnostics](crate::diagnostic), errors are recorded and the parser skips to the next entry instead of failing.
+pub(crate) fn parse_entries_recovering<'a>(
+    end: Option<&'static str>,
+) -> impl Parser<KconfigInput<'a>, Output = Vec<Entry>, Error = nom::error::Error<KconfigInput<'a>>>
+{
+    move |input: KconfigInput<'a>| -> IResult<KconfigInput<'a>, Vec<Entry>> {
+        let mut entries = vec![];
+        let mut input = input;
+        loop {
+            let (rest, _) = ws_comment(input.clone())?;
+            input = rest;
+            let keyword = first_word(&input);
+            if input.fragment().is_empty() {
+                if let Some(end) = end {
+                    report(
+                        &input,
+                        Diagnostic::error(
+                            format!("expected `{}` before the end of the file", end),
+                            line_span(&input),
+                        ),
+                    );
+                }
+                return Ok((input, entries));
+            }
+            if Some(keyword) == end {
+                return Ok((input.take_from(keyword.len()), entries));
+            }
+            if end.is_some() && ["endmenu", "endif", "endchoice"].contains(&keyword) {
+                // The block is not closed, the keyword may close a parent block.
+                report(
+                    &input,
+                    Diagnostic::error(
+                        format!(
+                            "expected `{}` before `{}`",
+                            end.unwrap_or_default(),
+                            keyword
+                        ),
+                        line_span(&input),
+                    ),
+                );
+                return Ok((input, entries));
+            }
+            match parse_entry(input.clone()) {
+                Ok((rest, entry)) => {
+                    entries.push(entry);
+                    input = rest;
+                }
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                    let (message, span) = describe_error(&input, &e.input);
+                    report(&input, Diagnostic::error(message, span));
+                    input = skip_to_next_entry(input);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Parses the entries of a `menu`, `if` or `choice` block, until its closing keyword.
+pub(crate) fn parse_block_entries<'a>(
+    end: &'static str,
+) -> impl Parser<KconfigInput<'a>, Output = Vec<Entry>, Error = nom::error::Error<KconfigInput<'a>>>
+{
+    move |input: KconfigInput<'a>| match input.extra.diagnostics {
+        Some(_) => parse_entries_recovering(Some(end)).parse(input),
+        None => terminated(cut(many0(ws(parse_entry))), ws(tag(end))).parse(input),
+    }
+}
+
 pub fn parse_entries(input: KconfigInput) -> IResult<KconfigInput, Vec<Entry>> {
     delimited(ws_comment, many0(parse_entry), ws_comment).parse(input)
 }
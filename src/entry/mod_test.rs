@@ -27,12 +27,15 @@ fn test_parse_entries() {
                     symbol: "KVM".to_string(),
                     attributes: vec!(Attribute::Type(ConfigType {
                         r#type: Type::Bool(None),
-                        r#if: None
-                    }))
+                        r#if: None,
+                        span: Default::default()
+                    })),
+                    span: Default::default()
                 }),
                 Entry::Comment(Comment {
                     prompt: "some configs".to_string(),
-                    dependencies: vec!()
+                    dependencies: vec!(),
+                    span: Default::default()
                 }),
             )
         ))
@@ -53,7 +56,8 @@ fn test_if_success_variable() {
                 right: Value::Literal(
                     r#"$(shell,{ $(1); } >/dev/null 2>&1 && echo "$(2)" || echo "$(3)")"#
                         .to_string()
-                )
+                ),
+                span: Default::default()
             })
         ))
     )
@@ -79,26 +83,32 @@ fn test_double_indented_entries() {
             "",
             vec!(
                 Entry::MainMenu(crate::entry::MainMenu {
-                    prompt: "MAIN".to_string()
+                    prompt: "MAIN".to_string(),
+                    span: Default::default(),
                 }),
                 Entry::Config(Config {
                     symbol: "A".to_string(),
                     attributes: vec!(
                         Attribute::Type(ConfigType {
                             r#type: Type::Bool(None),
-                            r#if: None
+                            r#if: None,
+                            span: Default::default(),
                         }),
                         Attribute::Help(
-                            "- Lorem ipsum dolor sit amet, consetetur sadipscing elitr.\n    - Lorem ipsum dolor sit amet, consetetur sadipscing elitr.".to_string()
+                            "- Lorem ipsum dolor sit amet, consetetur sadipscing elitr.\n    - Lorem ipsum dolor sit amet, consetetur sadipscing elitr.".to_string(),
+                            Default::default()
                         )
-                    )
+                    ),
+                    span: Default::default(),
                 }),
                 Entry::Config(Config {
                     symbol: "B".to_string(),
                     attributes: vec![Attribute::Type(ConfigType {
                         r#type: Type::Bool(None),
-                        r#if: None
-                    })]
+                        r#if: None,
+                        span: Default::default(),
+                    })],
+                    span: Default::default(),
                 })
             )
         ))
@@ -124,13 +134,15 @@ config COMPACT_UNEVICTABLE_DEFAULT
                 attributes: vec!(
                     Attribute::Type(ConfigType {
                         r#type: Type::Int(None),
-                        r#if: None
+                        r#if: None,
+                        span: Default::default()
                     }),
                     Attribute::DependsOn(DependsOn {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
                             Atom::Symbol(Symbol::NonConstant("COMPACTION".to_string()))
                         ))),
                         r#if: None,
+                        span: Default::default()
                     }),
                     Attribute::Default(DefaultAttribute {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
@@ -138,15 +150,18 @@ config COMPACT_UNEVICTABLE_DEFAULT
                         ))),
                         r#if: Some(Expression::Term(AndExpression::Term(Term::Atom(
                             Atom::Symbol(Symbol::NonConstant("PREEMPT_RT".to_string()))
-                        ))))
+                        )))),
+                        span: Default::default()
                     }),
                     Attribute::Default(DefaultAttribute {
                         expression: Expression::Term(AndExpression::Term(Term::Atom(
                             Atom::Symbol(Symbol::Constant(ConstantSymbol::Integer(1)))
                         ))),
-                        r#if: None
+                        r#if: None,
+                        span: Default::default()
                     })
-                )
+                ),
+                span: Default::default()
             }),)
         ))
     )
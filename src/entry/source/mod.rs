@@ -2,6 +2,7 @@
 mod orsource;
 #[cfg(feature = "kconfiglib")]
 mod osource;
+mod reference;
 #[cfg(feature = "kconfiglib")]
 mod rsource;
 #[allow(clippy::module_inception)]
@@ -9,10 +10,12 @@ mod source;
 
 use nom::{
     branch::alt,
+    bytes::complete::{tag, take_till1},
     character::complete::{alphanumeric1, one_of},
     combinator::{cut, map, recognize},
     error::{Error, ErrorKind, ParseError},
     multi::many1,
+    sequence::delimited,
     IResult, Parser,
 };
 
@@ -21,11 +24,18 @@ pub use self::{
     orsource::parse_orsource, orsource::OrSource, osource::parse_osource, osource::OSource,
     rsource::parse_rsource, rsource::RSource,
 };
+pub(crate) use reference::include_source;
+pub use reference::{SourceKind, SourceMode, SourceReference};
 pub use source::{parse_source, Source};
 #[cfg(feature = "debug")]
 use tracing::{debug, error};
 
-use crate::{kconfig::private_parse_kconfig, KconfigInput};
+use crate::{
+    diagnostic::{report, Diagnostic},
+    kconfig::private_parse_kconfig,
+    preprocess::Variable,
+    KconfigInput,
+};
 use crate::{util::ws, Kconfig, KconfigFile};
 
 #[cfg(feature = "glob-wildcard")]
@@ -55,13 +65,51 @@ pub(crate) fn parse_filepath(input: KconfigInput<'_>) -> IResult<KconfigInput<'_
     .parse(input)
 }
 
+/// The path of a source statement, quoted or not. Quoted, it may contain spaces and commas, `$(shell, ...)` for instance.
+pub(crate) fn parse_source_path(input: KconfigInput<'_>) -> IResult<KconfigInput<'_>, &str> {
+    alt((
+        map(
+            delimited(tag("\""), take_till1(|c| c == '"' || c == '\n'), tag("\"")),
+            |d: KconfigInput| d.fragment().to_owned(),
+        ),
+        parse_filepath,
+    ))
+    .parse(input)
+}
+
 #[allow(clippy::type_complexity)]
 fn parse_source_kconfig(
     input: KconfigInput,
     source_kconfig_file: KconfigFile,
-) -> Result<(HashMap<String, String>, Kconfig), nom::Err<Error<KconfigInput>>> {
-    let source_content = source_kconfig_file
-        .read_to_string()
+) -> Result<(HashMap<String, Variable>, Kconfig), nom::Err<Error<KconfigInput>>> {
+    let source_content = source_kconfig_file.read_to_string();
+    if let (Err(error), Some(span)) = (&source_content, source_kconfig_file.include_chain.last()) {
+        let message = format!(
+            "cannot read `{}`: {}",
+            source_kconfig_file.file.display(),
+            error
+        );
+        #[cfg(feature = "kconfiglib")]
+        report(&input, Diagnostic::warning(message, span.clone()));
+        #[cfg(not(feature = "kconfiglib"))]
+        {
+            report(&input, Diagnostic::error(message, span.clone()));
+            if input.extra.diagnostics.is_some() {
+                return Ok((
+                    input.extra.vars(),
+                    Kconfig {
+                        file: source_kconfig_file.file.display().to_string(),
+                        entries: vec![],
+                    },
+                ));
+            }
+        }
+    }
+    // `$(error-if)` and other preprocessing errors
+    #[cfg(feature = "kconfiglib")]
+    let invalid_content =
+        matches!(&source_content, Err(e) if e.kind() == std::io::ErrorKind::InvalidData);
+    let source_content = source_content
         .map_err(|_| nom::Err::Error(Error::from_error_kind(input.clone(), ErrorKind::Fail)));
 
     #[cfg(feature = "kconfiglib")]
@@ -71,7 +119,7 @@ fn parse_source_kconfig(
         // In that case, we can return an empty Kconfig instead of failing to parse the source file.
         //
         // This is not the best solution !
-        if source_content.is_err() {
+        if source_content.is_err() && !invalid_content {
             #[cfg(feature = "debug")]
             error!(
                 "I tried to parse the source file '{}' defined in '{}'. This is likely because the filename is dynamically generated with macros/variables that are not supported yet. Returning an empty Kconfig for this source file.",
@@ -151,21 +199,19 @@ fn expand_source_files<'a>(
     };
 
     let full_path_pattern = prefix_path.join(file);
-    let paths: Vec<PathBuf> = glob(&full_path_pattern.display().to_string())
-        .map_err(|_| nom::Err::Error(Error::from_error_kind(input.clone(), ErrorKind::Fail)))?
-        .collect::<Result<Vec<_>, _>>()
+    let vfs = &input.extra.vfs;
+    let paths: Vec<PathBuf> = vfs
+        .glob(&full_path_pattern)
         .map_err(|_| nom::Err::Error(Error::from_error_kind(input.clone(), ErrorKind::Fail)))?;
 
     if paths.is_empty() {
         return Ok(vec![prefix_path.join(file)]);
     }
+    let root_dir = vfs.canonicalize(&input.extra.root_dir);
     for source_path in paths {
-        let source_path = source_path.canonicalize().unwrap();
+        let source_path = vfs.canonicalize(&source_path);
         let source_path_without_root = source_path
-            // TODO need to canonicalize because of macOS weird filepath for temp directories
-            // /var/folder
-            // and /private/var/folder
-            .strip_prefix(input.extra.root_dir.canonicalize().unwrap())
+            .strip_prefix(&root_dir)
             .map_err(|_| nom::Err::Error(Error::from_error_kind(input.clone(), ErrorKind::Fail)))?;
         expanded_files.push(source_path_without_root.to_path_buf());
     }
@@ -1,14 +1,18 @@
 /// An rsource statement is available for including files specified with a relative path.
 /// The path is relative to the directory of the Kconfig file that contains the rsource statement.
 /// <https://docs.zephyrproject.org/latest/build/kconfig/extensions.html>
-use nom::{branch::alt, bytes::complete::tag, sequence::delimited, IResult, Parser};
+use nom::{bytes::complete::tag, sequence::preceded, IResult, Parser};
 
 use crate::{
     entry::{
-        source::{expand_source_files, parse_filepath, parse_source_kconfig, JoinPathMode},
+        source::{
+            expand_source_files, include_source, parse_source_kconfig, parse_source_path,
+            JoinPathMode, SourceKind,
+        },
         Source,
     },
     kconfig::Kconfig,
+    span::{spanned, Span},
     util::{ws, wsi},
     KconfigInput,
 };
@@ -16,18 +20,30 @@ use crate::{
 pub type OrSource = Source;
 
 pub fn parse_orsource(input: KconfigInput) -> IResult<KconfigInput, OrSource> {
-    let (input, _) = ws(tag("orsource")).parse(input)?;
-    let (mut input, file) = wsi(alt((
-        delimited(tag("\""), parse_filepath, tag("\"")),
-        parse_filepath,
-    )))
-    .parse(input)?;
+    let (input, (file, span)) =
+        spanned(preceded(ws(tag("orsource")), wsi(parse_source_path))).parse(input)?;
+    include_source(input, SourceKind::OrSource, file, span)
+}
+
+/// Reads and parses the files included by an `orsource` statement, `file` is `path` once expanded.
+pub(crate) fn expand_orsource<'a>(
+    mut input: KconfigInput<'a>,
+    path: &str,
+    file: &str,
+    span: Span,
+) -> IResult<KconfigInput<'a>, OrSource> {
     let expanded_files = expand_source_files(input.clone(), file, JoinPathMode::Relative)?;
     let mut sources = vec![];
 
     for expanded_file in expanded_files {
-        let source_kconfig_file = input.extra.new_source_file(expanded_file);
-        if !source_kconfig_file.full_path().exists() {
+        let source_kconfig_file = input
+            .extra
+            .new_source_file(expanded_file)
+            .included_from(span.clone());
+        if !source_kconfig_file
+            .vfs
+            .exists(&source_kconfig_file.full_path())
+        {
             sources.push(Kconfig {
                 file: file.to_string(),
                 ..Default::default()
@@ -39,5 +55,13 @@ pub fn parse_orsource(input: KconfigInput) -> IResult<KconfigInput, OrSource> {
         sources.push(source);
     }
 
-    Ok((input, OrSource { kconfigs: sources }))
+    Ok((
+        input,
+        OrSource {
+            kconfigs: sources,
+            span,
+            path: path.to_string(),
+            unresolved: None,
+        },
+    ))
 }
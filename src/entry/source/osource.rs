@@ -1,14 +1,18 @@
 /// For cases where it's okay for a pattern to match no files (or for a plain filename to not exist), a separate osource (optional source) statement is available.
 /// osource is a no-op if no file matches.
 /// <https://docs.zephyrproject.org/latest/build/kconfig/extensions.html>
-use nom::{branch::alt, bytes::complete::tag, sequence::delimited, IResult, Parser};
+use nom::{bytes::complete::tag, sequence::preceded, IResult, Parser};
 
 use crate::{
     entry::{
-        source::{expand_source_files, parse_filepath, parse_source_kconfig, JoinPathMode},
+        source::{
+            expand_source_files, include_source, parse_source_kconfig, parse_source_path,
+            JoinPathMode, SourceKind,
+        },
         Source,
     },
     kconfig::Kconfig,
+    span::{spanned, Span},
     util::{ws, wsi},
     KconfigInput,
 };
@@ -16,19 +20,31 @@ use crate::{
 pub type OSource = Source;
 
 pub fn parse_osource(input: KconfigInput) -> IResult<KconfigInput, OSource> {
-    let (input, _) = ws(tag("osource")).parse(input)?;
-    let (mut input, file) = wsi(alt((
-        delimited(tag("\""), parse_filepath, tag("\"")),
-        parse_filepath,
-    )))
-    .parse(input)?;
+    let (input, (file, span)) =
+        spanned(preceded(ws(tag("osource")), wsi(parse_source_path))).parse(input)?;
+    include_source(input, SourceKind::OSource, file, span)
+}
+
+/// Reads and parses the files included by an `osource` statement, `file` is `path` once expanded.
+pub(crate) fn expand_osource<'a>(
+    mut input: KconfigInput<'a>,
+    path: &str,
+    file: &str,
+    span: Span,
+) -> IResult<KconfigInput<'a>, OSource> {
     let expanded_files = expand_source_files(input.clone(), file, JoinPathMode::Root)?;
     let mut sources = vec![];
 
     for expanded_file in expanded_files {
-        let source_kconfig_file = input.extra.new_source_file(expanded_file);
+        let source_kconfig_file = input
+            .extra
+            .new_source_file(expanded_file)
+            .included_from(span.clone());
 
-        if !source_kconfig_file.full_path().exists() {
+        if !source_kconfig_file
+            .vfs
+            .exists(&source_kconfig_file.full_path())
+        {
             sources.push(Kconfig {
                 file: file.to_string(),
                 ..Default::default()
@@ -40,7 +56,15 @@ pub fn parse_osource(input: KconfigInput) -> IResult<KconfigInput, OSource> {
         sources.push(source);
     }
 
-    Ok((input, OSource { kconfigs: sources }))
+    Ok((
+        input,
+        OSource {
+            kconfigs: sources,
+            span,
+            path: path.to_string(),
+            unresolved: None,
+        },
+    ))
 }
 
 #[cfg(test)]
@@ -70,9 +94,14 @@ fn test_parse_osource() {
                         attributes: vec![Attribute::Type(ConfigType {
                             r#type: Type::Tristate(None),
                             r#if: None,
+                            span: Default::default(),
                         })],
+                        span: Default::default(),
                     })],
                 }],
+                span: Default::default(),
+                path: "Kconfig.simple".to_string(),
+                unresolved: None,
             },
         )),
     )
@@ -89,6 +118,9 @@ fn test_parse_osource_does_not_exist() {
                     file: "this-file-does-not-exist".to_string(),
                     ..Default::default()
                 }],
+                span: Default::default(),
+                path: "this-file-does-not-exist".to_string(),
+                unresolved: None,
             },
         )),
     )
@@ -99,14 +131,13 @@ fn assert_parsing_osource_eq(
     input: &str,
     expected: Result<(&str, OSource), nom::Err<nom::error::Error<KconfigInput>>>,
 ) {
-    use crate::KconfigFile;
+    use crate::{vfs::MemoryFs, KconfigFile};
 
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Kconfig.simple", "config KVM\n\ttristate");
     let res = parse_osource(KconfigInput::new_extra(
         input,
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"),
-            ..Default::default()
-        },
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig")).with_vfs(fs),
     ))
     .map(|r| (r.0.fragment().to_owned(), r.1));
     assert_eq!(res, expected)
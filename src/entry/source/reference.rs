@@ -0,0 +1,157 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    rc::Rc,
+};
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+#[cfg(feature = "kconfiglib")]
+use super::{orsource::expand_orsource, osource::expand_osource, rsource::expand_rsource};
+use nom::IResult;
+
+use super::{source::expand_source, Source};
+use crate::{error::Error, preprocess::Variable, span::Span, Kconfig, KconfigFile, KconfigInput};
+
+/// How the files included by `source` statements are handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum SourceMode {
+    /// Included files are read and parsed along with the file that includes them.
+    #[default]
+    Eager,
+    /// Included files are left untouched, [Source::unresolved] records what to read. Use [Source::expand] to parse them.
+    Lazy,
+}
+
+/// The keyword of a source statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum SourceKind {
+    Source,
+    #[cfg(feature = "kconfiglib")]
+    OSource,
+    #[cfg(feature = "kconfiglib")]
+    RSource,
+    #[cfg(feature = "kconfiglib")]
+    OrSource,
+}
+
+impl SourceKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            SourceKind::Source => "source",
+            #[cfg(feature = "kconfiglib")]
+            SourceKind::OSource => "osource",
+            #[cfg(feature = "kconfiglib")]
+            SourceKind::RSource => "rsource",
+            #[cfg(feature = "kconfiglib")]
+            SourceKind::OrSource => "orsource",
+        }
+    }
+}
+
+/// A source statement that has not been expanded yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct SourceReference {
+    pub kind: SourceKind,
+    /// The path as written in the Kconfig file, `arch/$(SRCARCH)/Kconfig` for instance.
+    pub path: String,
+    /// [SourceReference::path] once the variables are substituted, `arch/x86/Kconfig`. It may contain wildcards.
+    pub expanded_path: String,
+    /// The Kconfig file containing the statement, relative to the root directory.
+    pub file: PathBuf,
+    /// The variables defined when the statement was met, the included files are parsed with them.
+    pub variables: BTreeMap<String, Variable>,
+}
+
+impl Source {
+    /// Parses the included files.
+    /// `kconfig_file` provides the root directory, the [Vfs](crate::vfs::Vfs) and the parsing options.
+    /// The included files are expanded eagerly, whatever [KconfigFile::source_mode] is.
+    /// Like parsing the statement eagerly, the variables assigned in the included files are added to `kconfig_file`.
+    pub fn expand(&self, kconfig_file: &mut KconfigFile) -> Result<Vec<Kconfig>, Error> {
+        let Some(reference) = &self.unresolved else {
+            return Ok(self.kconfigs.clone());
+        };
+        let mut file = kconfig_file.clone();
+        file.file = reference.file.clone();
+        file.local_vars = Rc::new(reference.variables.clone().into_iter().collect());
+        file.source_mode = SourceMode::Eager;
+        let input = KconfigInput::new_extra(&reference.expanded_path, file);
+        let (input, source) = expand(
+            input,
+            reference.kind,
+            &reference.path,
+            &reference.expanded_path,
+            self.span.clone(),
+        )?;
+        let assigned: HashMap<String, Variable> = input
+            .extra
+            .local_vars
+            .iter()
+            .filter(|(name, variable)| reference.variables.get(*name) != Some(*variable))
+            .map(|(name, variable)| (name.clone(), variable.clone()))
+            .collect();
+        kconfig_file.add_local_vars(assigned);
+        Ok(source.kconfigs)
+    }
+}
+
+/// Parses the files included by a source statement, or records them when the input is parsed with [SourceMode::Lazy].
+/// `path` is the path as written, its variables are substituted here.
+pub(crate) fn include_source<'a>(
+    input: KconfigInput<'a>,
+    kind: SourceKind,
+    path: &str,
+    span: Span,
+) -> IResult<KconfigInput<'a>, Source> {
+    let expanded_path = input.extra.preprocessor().expand(path);
+    match input.extra.source_mode {
+        SourceMode::Eager => expand(input, kind, path, &expanded_path, span),
+        SourceMode::Lazy => {
+            let reference = SourceReference {
+                kind,
+                path: path.to_string(),
+                expanded_path,
+                file: input.extra.file.clone(),
+                variables: input.extra.vars().into_iter().collect(),
+            };
+            Ok((
+                input,
+                Source {
+                    kconfigs: vec![],
+                    span,
+                    path: path.to_string(),
+                    unresolved: Some(reference),
+                },
+            ))
+        }
+    }
+}
+
+fn expand<'a>(
+    input: KconfigInput<'a>,
+    kind: SourceKind,
+    path: &str,
+    expanded_path: &str,
+    span: Span,
+) -> IResult<KconfigInput<'a>, Source> {
+    match kind {
+        SourceKind::Source => expand_source(input, path, expanded_path, span),
+        #[cfg(feature = "kconfiglib")]
+        SourceKind::OSource => expand_osource(input, path, expanded_path, span),
+        #[cfg(feature = "kconfiglib")]
+        SourceKind::RSource => expand_rsource(input, path, expanded_path, span),
+        #[cfg(feature = "kconfiglib")]
+        SourceKind::OrSource => expand_orsource(input, path, expanded_path, span),
+    }
+}
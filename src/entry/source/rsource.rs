@@ -2,10 +2,13 @@ use crate::entry::source::JoinPathMode;
 use crate::{entry::Source, util::ws, KconfigInput};
 use nom::{bytes::complete::tag, IResult, Parser};
 
-use nom::{branch::alt, sequence::delimited};
+use nom::sequence::preceded;
 
 use crate::{
-    entry::source::{expand_source_files, parse_filepath, parse_source_kconfig},
+    entry::source::{
+        expand_source_files, include_source, parse_source_kconfig, parse_source_path, SourceKind,
+    },
+    span::{spanned, Span},
     util::wsi,
 };
 
@@ -13,24 +16,40 @@ pub type RSource = Source;
 
 #[allow(dead_code)]
 pub fn parse_rsource(input: KconfigInput) -> IResult<KconfigInput, RSource> {
-    let (input, _) = ws(tag("rsource")).parse(input)?;
-    let (mut input, file) = wsi(alt((
-        delimited(tag("\""), parse_filepath, tag("\"")),
-        parse_filepath,
-    )))
-    .parse(input)?;
+    let (input, (file, span)) =
+        spanned(preceded(ws(tag("rsource")), wsi(parse_source_path))).parse(input)?;
+    include_source(input, SourceKind::RSource, file, span)
+}
 
+/// Reads and parses the files included by an `rsource` statement, `file` is `path` once expanded.
+pub(crate) fn expand_rsource<'a>(
+    mut input: KconfigInput<'a>,
+    path: &str,
+    file: &str,
+    span: Span,
+) -> IResult<KconfigInput<'a>, RSource> {
     let expanded_files = expand_source_files(input.clone(), file, JoinPathMode::Relative)?;
     let mut sources = vec![];
 
     for expanded_file in expanded_files {
-        let source_kconfig_file = input.extra.new_source_file(expanded_file);
+        let source_kconfig_file = input
+            .extra
+            .new_source_file(expanded_file)
+            .included_from(span.clone());
         let (variables, source) = parse_source_kconfig(input.clone(), source_kconfig_file)?;
         input.extra.add_local_vars(variables);
         sources.push(source);
     }
 
-    Ok((input, RSource { kconfigs: sources }))
+    Ok((
+        input,
+        RSource {
+            kconfigs: sources,
+            span,
+            path: path.to_string(),
+            unresolved: None,
+        },
+    ))
 }
 
 #[cfg(test)]
@@ -39,14 +58,12 @@ use std::path::PathBuf;
 #[test]
 #[ignore]
 fn test_parse_rsource() {
-    use crate::KconfigFile;
+    use crate::{vfs::MemoryFs, KconfigFile};
 
     let res = parse_rsource(KconfigInput::new_extra(
         r#"rsource "boards/*.defconfig""#,
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"),
-            ..Default::default()
-        },
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig"))
+            .with_vfs(MemoryFs::new()),
     ));
     assert!(res.is_err())
 }
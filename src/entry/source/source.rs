@@ -1,4 +1,4 @@
-use nom::{branch::alt, bytes::complete::tag, sequence::delimited, IResult, Parser};
+use nom::{bytes::complete::tag, sequence::preceded, IResult, Parser};
 #[cfg(feature = "deserialize")]
 use serde::Deserialize;
 #[cfg(feature = "serialize")]
@@ -7,55 +7,90 @@ use serde::Serialize;
 #[cfg(feature = "glob-wildcard")]
 use crate::entry::source::{expand_source_files, JoinPathMode};
 use crate::{
-    entry::source::{parse_filepath, parse_source_kconfig},
+    entry::source::{
+        include_source, parse_source_kconfig, parse_source_path, SourceKind, SourceReference,
+    },
     kconfig::Kconfig,
+    span::{ignore_span, spanned, Span},
     util::{ws, wsi},
     KconfigInput,
 };
 
 /// Entry that reads the specified configuration file. This file is always parsed.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct Source {
     pub kconfigs: Vec<Kconfig>,
+    pub span: Span,
+    /// The path as written in the Kconfig file, before its variables are substituted or its wildcards matched.
+    pub path: String,
+    /// The included path, set instead of [Source::kconfigs] when parsed with [SourceMode::Lazy](crate::entry::SourceMode::Lazy).
+    pub unresolved: Option<SourceReference>,
 }
 
+ignore_span!(Source {
+    kconfigs,
+    path,
+    unresolved,
+});
+
 pub fn parse_source(input: KconfigInput) -> IResult<KconfigInput, Source> {
-    let (input, _) = ws(tag("source")).parse(input)?;
-    let (mut input, file) = wsi(alt((
-        delimited(tag("\""), parse_filepath, tag("\"")),
-        parse_filepath,
-    )))
-    .parse(input)?;
+    let (input, (file, span)) =
+        spanned(preceded(ws(tag("source")), wsi(parse_source_path))).parse(input)?;
+    include_source(input, SourceKind::Source, file, span)
+}
 
+/// Reads and parses the files included by a `source` statement, `file` is `path` once expanded.
+pub(crate) fn expand_source<'a>(
+    mut input: KconfigInput<'a>,
+    path: &str,
+    file: &str,
+    span: Span,
+) -> IResult<KconfigInput<'a>, Source> {
     #[cfg(feature = "glob-wildcard")]
     {
         let expanded_files = expand_source_files(input.clone(), file, JoinPathMode::Root)?;
         let mut sources = vec![];
 
         for expanded_file in expanded_files {
-            let source_kconfig_file = input.extra.new_source_file(expanded_file);
+            let source_kconfig_file = input
+                .extra
+                .new_source_file(expanded_file)
+                .included_from(span.clone());
             let (variables, source) = parse_source_kconfig(input.clone(), source_kconfig_file)?;
             input.extra.add_local_vars(variables);
             sources.push(source);
         }
 
-        Ok((input, Source { kconfigs: sources }))
+        Ok((
+            input,
+            Source {
+                kconfigs: sources,
+                span,
+                path: path.to_string(),
+                unresolved: None,
+            },
+        ))
     }
 
     #[cfg(not(feature = "glob-wildcard"))]
     {
         use std::path::PathBuf;
 
-        let source_kconfig_file = input.extra.new_source_file(PathBuf::from(file));
+        let source_kconfig_file = input
+            .extra
+            .new_source_file(PathBuf::from(file))
+            .included_from(span.clone());
         let (variables, source) = parse_source_kconfig(input.clone(), source_kconfig_file)?;
         input.extra.add_local_vars(variables);
         return Ok((
             input,
             Source {
                 kconfigs: vec![source],
+                span,
+                path: path.to_string(),
+                unresolved: None,
             },
         ));
     }
@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
 use crate::{
-    entry::{parse_source, Source},
-    Kconfig, KconfigFile, KconfigInput,
+    entry::{parse_source, Source, SourceKind, SourceMode},
+    kconfig::parse_kconfig,
+    preprocess::Variable,
+    vfs::MemoryFs,
+    Entry, Kconfig, KconfigFile, KconfigInput,
 };
 
 #[test]
@@ -16,6 +19,9 @@ fn test_parse_source() {
                     file: "empty".to_string(),
                     ..Default::default()
                 }],
+                span: Default::default(),
+                path: "empty".to_string(),
+                unresolved: None,
             },
         )),
     )
@@ -33,6 +39,9 @@ fn test_parse_source_no_quote() {
                     file: "empty".to_string(),
                     ..Default::default()
                 }],
+                span: Default::default(),
+                path: "empty".to_string(),
+                unresolved: None,
             },
         )),
     )
@@ -43,22 +52,18 @@ fn test_parse_source_no_quote() {
 fn test_parse_source_fail_file_not_exist() {
     let res = parse_source(KconfigInput::new_extra(
         "source a/random/file",
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
-            ..Default::default()
-        },
+        kconfig_file(MemoryFs::new()),
     ));
     assert!(res.is_err())
 }
 
 #[test]
 fn test_parse_source_fail_to_parse() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Cargo.toml", "[package]\nname = \"nom-kconfig\"\n");
     let res = parse_source(KconfigInput::new_extra(
         "source \"Cargo.toml\"",
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
-            ..Default::default()
-        },
+        kconfig_file(fs),
     ));
     assert!(res.is_err())
 }
@@ -68,10 +73,7 @@ fn test_parse_source_fail_to_parse() {
 fn test_parse_source_glob_not_supported_without_feature() {
     let res = parse_source(KconfigInput::new_extra(
         "source glob-fixtures/source-child-*.Kconfig",
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"),
-            ..Default::default()
-        },
+        kconfig_file(glob_fixtures()),
     ));
     assert!(res.is_err())
 }
@@ -81,25 +83,112 @@ fn test_parse_source_glob_not_supported_without_feature() {
 fn test_parse_source_glob_no_match_fails_with_feature() {
     let res = parse_source(KconfigInput::new_extra(
         "source glob-fixtures/does-not-exist-*.Kconfig",
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"),
-            ..Default::default()
-        },
+        kconfig_file(glob_fixtures()),
     ));
     assert!(res.is_ok())
 }
 
+#[test]
+fn test_parse_source_lazy() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Kconfig", "source \"arch/$(SRCARCH)/Kconfig\"\n");
+    fs.insert(
+        "/kernel/arch/x86/Kconfig",
+        "BITS := 64\nconfig X86\n    bool\n",
+    );
+    let mut kconfig_file = KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig"))
+        .with_vfs(fs)
+        .with_source_mode(SourceMode::Lazy);
+    kconfig_file.add_local_var("SRCARCH", "x86");
+
+    let input = kconfig_file.read_to_string().unwrap();
+    let (_, kconfig) =
+        parse_kconfig(KconfigInput::new_extra(&input, kconfig_file.clone())).unwrap();
+    let Entry::Source(source) = &kconfig.entries[0] else {
+        unreachable!()
+    };
+    assert!(source.kconfigs.is_empty());
+    let reference = source.unresolved.as_ref().unwrap();
+    assert_eq!(reference.kind, SourceKind::Source);
+    assert_eq!(reference.path, "arch/$(SRCARCH)/Kconfig");
+    assert_eq!(reference.expanded_path, "arch/x86/Kconfig");
+    assert_eq!(reference.file, PathBuf::from("Kconfig"));
+    assert_eq!(reference.variables["SRCARCH"], Variable::recursive("x86"));
+
+    let kconfigs = source.expand(&mut kconfig_file).unwrap();
+    assert_eq!(kconfigs.len(), 1);
+    assert_eq!(kconfigs[0].file, "arch/x86/Kconfig");
+    assert_eq!(kconfigs[0].entries.len(), 2);
+    assert_eq!(kconfig_file.vars()["BITS"], Variable::simple("64"));
+    assert_eq!(kconfig_file.vars()["SRCARCH"], Variable::recursive("x86"));
+}
+
+#[cfg(all(feature = "kconfiglib", feature = "display"))]
+#[test]
+fn test_parse_rsource_lazy() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/boards/a.Kconfig", "config A\n    bool\n");
+    fs.insert("/kernel/boards/b.Kconfig", "config B\n    bool\n");
+    let mut kconfig_file =
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("boards/Kconfig"))
+            .with_vfs(fs)
+            .with_source_mode(SourceMode::Lazy);
+    let input = "rsource \"*.Kconfig\"\n";
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, kconfig_file.clone())).unwrap();
+    assert_eq!(kconfig.to_string(), input);
+    let Entry::RSource(source) = &kconfig.entries[0] else {
+        unreachable!()
+    };
+    let files: Vec<String> = source
+        .expand(&mut kconfig_file)
+        .unwrap()
+        .into_iter()
+        .map(|k| k.file)
+        .collect();
+    assert_eq!(files, vec!["boards/a.Kconfig", "boards/b.Kconfig"]);
+}
+
+#[test]
+fn test_expand_resolved_source() {
+    let source = Source {
+        kconfigs: vec![Kconfig {
+            file: "empty".to_string(),
+            ..Default::default()
+        }],
+        span: Default::default(),
+        path: "empty".to_string(),
+        unresolved: None,
+    };
+    assert_eq!(
+        source.expand(&mut KconfigFile::default()).unwrap(),
+        source.kconfigs
+    );
+}
+
+fn kconfig_file(fs: MemoryFs) -> KconfigFile {
+    KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig")).with_vfs(fs)
+}
+
+fn glob_fixtures() -> MemoryFs {
+    let mut fs = MemoryFs::new();
+    fs.insert(
+        "/kernel/glob-fixtures/source-child-a.Kconfig",
+        "config GLOB_CHILD_A\n    bool \"glob child a\"\n",
+    );
+    fs.insert(
+        "/kernel/glob-fixtures/source-child-b.Kconfig",
+        "config GLOB_CHILD_B\n    bool \"glob child b\"\n",
+    );
+    fs
+}
+
 pub fn assert_parsing_source_eq(
     input: &str,
     expected: Result<(&str, Source), nom::Err<nom::error::Error<KconfigInput>>>,
 ) {
-    let res = parse_source(KconfigInput::new_extra(
-        input,
-        KconfigFile {
-            root_dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"),
-            ..Default::default()
-        },
-    ))
-    .map(|r| (r.0.fragment().to_owned(), r.1));
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/empty", "");
+    let res = parse_source(KconfigInput::new_extra(input, kconfig_file(fs)))
+        .map(|r| (r.0.fragment().to_owned(), r.1));
     assert_eq!(res, expected)
 }
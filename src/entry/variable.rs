@@ -1,4 +1,4 @@
-use nom::combinator::all_consuming;
+use nom::combinator::{all_consuming, consumed};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -18,21 +18,28 @@ use crate::{
         function::{parse_expression_token_variable_parameter, ExpressionToken},
         FunctionCall,
     },
+    span::{ignore_span, spanned, Span},
     string::parse_string,
     util::{parse_until_eol, ws},
     KconfigInput,
 };
 
-#[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "hash", derive(Hash))]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 pub struct VariableAssignment {
     pub identifier: VariableIdentifier,
     pub operator: String,
     pub right: Value,
+    pub span: Span,
 }
 
+ignore_span!(VariableAssignment {
+    identifier,
+    operator,
+    right,
+});
+
 impl VariableIdentifier {
     fn raw(&self) -> String {
         match self {
@@ -65,16 +72,6 @@ pub enum Value {
     FunctionCall(FunctionCall),
 }
 
-impl Value {
-    fn raw(&self) -> String {
-        match self {
-            Value::Literal(s) => s.clone(),
-            Value::ExpandedVariable(s) => s.clone(),
-            Value::FunctionCall(function_call) => function_call.to_string(),
-        }
-    }
-}
-
 pub fn parse_value(input: KconfigInput) -> IResult<KconfigInput, Value> {
     alt((
         map(map_parser(parse_until_eol, parse_string), |s| {
@@ -105,25 +102,33 @@ pub fn parse_variable_identifier(input: KconfigInput) -> IResult<KconfigInput, V
 }
 
 pub fn parse_variable_assignment(input: KconfigInput) -> IResult<KconfigInput, VariableAssignment> {
-    let (mut remaining, assignment) = map(
-        (
+    let (mut remaining, (assignment, raw_value)) = map(
+        spanned((
             ws(parse_variable_identifier),
             ws(parse_assign),
-            ws(parse_value),
-        ),
-        |(l, o, r)| VariableAssignment {
-            identifier: l,
-            operator: o.to_string(),
-            right: r,
+            ws(consumed(parse_value)),
+        )),
+        |((l, o, (raw_value, r)), span)| {
+            (
+                VariableAssignment {
+                    identifier: l,
+                    operator: o.to_string(),
+                    right: r,
+                    span,
+                },
+                raw_value,
+            )
         },
     )
     .parse(input)?;
 
     // If the parsing is successful, we add the variable assignment to the local variables of the KconfigFile.
     // variables can be used by the preprocessor.
-    remaining
-        .extra
-        .add_local_var(assignment.identifier.raw(), assignment.right.raw());
+    remaining.extra.assign_local_var(
+        &assignment.identifier.raw(),
+        &assignment.operator,
+        raw_value.fragment(),
+    );
     Ok((remaining, assignment))
 }
 
@@ -16,7 +16,8 @@ fn test_parse_variable() {
             VariableAssignment {
                 identifier: VariableIdentifier::Identifier("hello".to_string()),
                 operator: "=".to_string(),
-                right: Value::Literal("world".to_string())
+                right: Value::Literal("world".to_string()),
+                span: Default::default()
             }
         ))
     )
@@ -36,7 +37,8 @@ fn test_parse_variable_variables_lefthand_side() {
                     ExpressionToken::Variable("Y".to_string())
                 )),
                 operator: ":=".to_string(),
-                right: Value::Literal("5".to_string())
+                right: Value::Literal("5".to_string()),
+                span: Default::default()
             }
         ))
     )
@@ -54,7 +56,8 @@ fn test_parse_variable_comma() {
             VariableAssignment {
                 identifier: VariableIdentifier::Identifier("comma".to_string()),
                 operator: ":=".to_string(),
-                right: Value::Literal(",".to_string())
+                right: Value::Literal(",".to_string()),
+                span: Default::default()
             }
         ))
     )
@@ -71,7 +74,8 @@ fn test_parse_variable_space() {
             VariableAssignment {
                 identifier: VariableIdentifier::Identifier("space".to_string()),
                 operator: ":=".to_string(),
-                right: Value::Literal("$(empty) $(empty)".to_string())
+                right: Value::Literal("$(empty) $(empty)".to_string()),
+                span: Default::default()
             }
         ))
     )
@@ -91,7 +95,8 @@ fn test_parse_variable_if_success() {
                 right: Value::Literal(
                     r#"$(shell,{ $(1); } >/dev/null 2>&1 && echo "$(2)" || echo "$(3)")"#
                         .to_string()
-                )
+                ),
+                span: Default::default()
             }
         ))
     )
@@ -108,7 +113,8 @@ fn test_pouet() {
             VariableAssignment {
                 identifier: VariableIdentifier::Identifier("module".to_string()),
                 operator: "=".to_string(),
-                right: Value::Literal("MCUBOOT_UTIL".to_string())
+                right: Value::Literal("MCUBOOT_UTIL".to_string()),
+                span: Default::default()
             }
         ))
     )
@@ -130,7 +136,9 @@ fn test_variable_with_external_function_call() {
                     parameters: vec![Parameter {
                         tokens: vec![ExpressionToken::Variable("BOARD".to_string())],
                     }],
-                })
+                    span: Default::default()
+                }),
+                span: Default::default()
             }
         ))
     )
@@ -0,0 +1,122 @@
+//! Exports a resolved configuration for build systems that do not read `.config` files: CMake, JSON and TOML.
+//!
+//! Only the symbols written to `.config` are exported. `bool` and `tristate` symbols set to `n` are exported too,
+//! as an empty string in CMake so that `if(CONFIG_FOO)` is false, and as `false` or `"n"` in JSON and TOML.
+//! Hex values are exported as strings: addresses like `0xc000000000000000` do not fit in a TOML integer,
+//! nor in the integers JSON parsers read without losing precision.
+//!
+//! | Kconfig    | CMake                        | JSON and TOML       |
+//! |------------|------------------------------|---------------------|
+//! | `bool`     | `set(CONFIG_FOO "y")`        | `true`              |
+//! | `tristate` | `set(CONFIG_FOO "m")`        | `"y"`, `"m"`, `"n"` |
+//! | `int`      | `set(CONFIG_FOO "250")`      | integer             |
+//! | `hex`      | `set(CONFIG_FOO "0x1000")`   | `"0x1000"`          |
+//! | `string`   | `set(CONFIG_FOO "value")`    | string              |
+//!
+//! CMake variables carry the prefix, JSON and TOML keys are the names of the symbols.
+//!
+//! ```
+//! use nom_kconfig::{export::ConfigExport, parse_kconfig, resolve::resolve, KconfigInput};
+//!
+//! let input = "config FOO\n    def_bool y\nconfig BAR\n    int\n    default 42\n";
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let configuration = resolve(&kconfig, None);
+//! let export = ConfigExport::new(&configuration);
+//! assert!(export.to_cmake().ends_with("set(CONFIG_FOO \"y\")\nset(CONFIG_BAR \"42\")\n"));
+//! assert_eq!(export.to_json(), "{\n  \"FOO\": true,\n  \"BAR\": 42\n}\n");
+//! assert!(export.to_toml().ends_with("FOO = true\nBAR = 42\n"));
+//! ```
+
+use crate::{
+    attribute::SymbolValue,
+    dotconfig::DEFAULT_PREFIX,
+    resolve::{Configuration, ResolvedSymbol},
+    symbol_table::SymbolType,
+    tristate::Tristate,
+    util::escape_json,
+};
+
+/// Exporters of a resolved [Configuration].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigExport<'a> {
+    configuration: &'a Configuration,
+    prefix: String,
+}
+
+impl<'a> ConfigExport<'a> {
+    pub fn new(configuration: &'a Configuration) -> Self {
+        Self {
+            configuration,
+            prefix: DEFAULT_PREFIX.to_string(),
+        }
+    }
+
+    /// Changes the prefix of the CMake variables, `CONFIG_` by default.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    fn symbols(&self) -> impl Iterator<Item = &ResolvedSymbol> {
+        self.configuration.symbols.iter().filter(|s| s.written)
+    }
+
+    /// A CMake script setting a variable per symbol, like the `sdkconfig.cmake` of ESP-IDF.
+    pub fn to_cmake(&self) -> String {
+        let mut output = "#\n# Automatically generated file; DO NOT EDIT.\n#\n".to_string();
+        for symbol in self.symbols() {
+            let value = match (symbol.r#type, &symbol.value) {
+                (SymbolType::Bool | SymbolType::Tristate, value)
+                    if value.tristate() == Tristate::No =>
+                {
+                    String::new()
+                }
+                (_, value) => value.raw(),
+            };
+            output.push_str(&format!(
+                "set({}{} \"{}\")\n",
+                self.prefix,
+                symbol.name,
+                escape_cmake(&value)
+            ));
+        }
+        output
+    }
+
+    /// A JSON object mapping the symbols to their values.
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .symbols()
+            .map(|symbol| format!("  {}: {}", escape_json(&symbol.name), typed_value(symbol)))
+            .collect();
+        match entries.is_empty() {
+            true => "{}\n".to_string(),
+            false => format!("{{\n{}\n}}\n", entries.join(",\n")),
+        }
+    }
+
+    /// A TOML document mapping the symbols to their values.
+    pub fn to_toml(&self) -> String {
+        let mut output = "# Automatically generated file; DO NOT EDIT.\n".to_string();
+        for symbol in self.symbols() {
+            output.push_str(&format!("{} = {}\n", symbol.name, typed_value(symbol)));
+        }
+        output
+    }
+}
+
+/// The value as a JSON or TOML scalar. Hex values, and values that do not match the type of the symbol, are kept as strings.
+fn typed_value(symbol: &ResolvedSymbol) -> String {
+    match (symbol.r#type, &symbol.value) {
+        (SymbolType::Bool, value) => (value.tristate() != Tristate::No).to_string(),
+        (SymbolType::Int, SymbolValue::Int(i)) => i.to_string(),
+        (_, value) => escape_json(&value.raw()),
+    }
+}
+
+fn escape_cmake(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+}
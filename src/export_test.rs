@@ -0,0 +1,83 @@
+use crate::{
+    export::ConfigExport,
+    lib_test::{resolve_input, CONFIGURATION},
+};
+
+#[test]
+fn test_export_cmake() {
+    let (_, configuration) = resolve_input(
+        CONFIGURATION,
+        "CONFIG_MODULES=y\nCONFIG_USB=m\nCONFIG_LOCALVERSION=\"${HOME}\"\n",
+    );
+    assert_eq!(
+        ConfigExport::new(&configuration)
+            .with_prefix("CFG_")
+            .to_cmake(),
+        r#"#
+# Automatically generated file; DO NOT EDIT.
+#
+set(CFG_MODULES "y")
+set(CFG_USB "m")
+set(CFG_PCI "")
+set(CFG_DEBUG "")
+set(CFG_HZ "250")
+set(CFG_PHYS "0x1000000")
+set(CFG_LOCALVERSION "\${HOME}")
+"#
+    );
+}
+
+#[test]
+fn test_export_json() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\nCONFIG_USB=m\n");
+    assert_eq!(
+        ConfigExport::new(&configuration).to_json(),
+        r#"{
+  "MODULES": true,
+  "USB": "m",
+  "PCI": false,
+  "DEBUG": false,
+  "HZ": 250,
+  "PHYS": "0x1000000",
+  "LOCALVERSION": "-custom"
+}
+"#
+    );
+    assert_eq!(
+        ConfigExport::new(&resolve_input("", "").1).to_json(),
+        "{}\n"
+    );
+}
+
+#[test]
+fn test_export_toml() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\n");
+    assert_eq!(
+        ConfigExport::new(&configuration).to_toml(),
+        r#"# Automatically generated file; DO NOT EDIT.
+MODULES = true
+USB = "n"
+PCI = false
+DEBUG = false
+HZ = 250
+PHYS = "0x1000000"
+LOCALVERSION = "-custom"
+"#
+    );
+}
+
+#[test]
+fn test_export_hex_above_i64() {
+    let (_, configuration) = resolve_input(
+        "config PAGE_OFFSET\n    hex\n    default 0xc000000000000000\n",
+        "",
+    );
+    let export = ConfigExport::new(&configuration);
+    assert_eq!(
+        export.to_json(),
+        "{\n  \"PAGE_OFFSET\": \"0xc000000000000000\"\n}\n"
+    );
+    assert!(export
+        .to_toml()
+        .ends_with("\nPAGE_OFFSET = \"0xc000000000000000\"\n"));
+}
@@ -0,0 +1,436 @@
+//! Canonical formatting of Kconfig files.
+//!
+//! The formatter prints a parsed tree back as Kconfig, the output parses back to the same AST.
+//! [format_kconfig] loses the comments and the blank lines. [format_cst] formats a tree parsed in [lossless mode](crate::cst)
+//! and keeps them, only the layout of the entries and attributes is canonical.
+//!
+//! ```
+//! use nom_kconfig::{format::{format_kconfig, FormatOptions, Indent}, kconfig::parse_kconfig, KconfigInput};
+//!
+//! let input = r#"menu "Drivers"
+//! config FOO
+//!   bool "foo"
+//!   help
+//!    Enable foo.
+//! endmenu"#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let options = FormatOptions {
+//!     indent: Indent::Spaces(4),
+//!     ..Default::default()
+//! };
+//! assert_eq!(
+//!     format_kconfig(&kconfig, &options),
+//!     "menu \"Drivers\"\n\nconfig FOO\n    bool \"foo\"\n    help\n      Enable foo.\n\nendmenu\n"
+//! );
+//! ```
+
+use std::{
+    borrow::Cow,
+    fmt::{Display, Write},
+};
+
+use crate::{
+    attribute::{function::ExpressionToken, Attribute},
+    cst::{CstEntry, CstKconfig},
+    entry::{
+        Choice, Comment, Config, If, Menu, Source, Value, VariableAssignment, VariableIdentifier,
+    },
+    Entry, Kconfig,
+};
+
+/// The unit of indentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Tab,
+    Spaces(usize),
+}
+
+impl Display for Indent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Indent::Tab => write!(f, "\t"),
+            Indent::Spaces(n) => write!(f, "{:n$}", ""),
+        }
+    }
+}
+
+/// Options of the formatter, the default follows the style of the Linux kernel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Indentation of the attributes of an entry.
+    pub indent: Indent,
+    /// Number of spaces added after the attribute indentation for help text lines.
+    pub help_indent: usize,
+    /// Indents the entries nested in `menu`, `if` and `choice` blocks.
+    pub indent_blocks: bool,
+    /// When set, paragraphs of help text are wrapped at this width.
+    /// Lines that are more indented than the paragraph (lists, code...) are left untouched.
+    /// Wrapping changes the help text, the output no longer re-parses to the same AST.
+    pub help_width: Option<usize>,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: Indent::Tab,
+            help_indent: 2,
+            indent_blocks: false,
+            help_width: None,
+        }
+    }
+}
+
+/// Formats a Kconfig file.
+/// Sourced files are not inlined, only the `source` statements are printed.
+pub fn format_kconfig(kconfig: &Kconfig, options: &FormatOptions) -> String {
+    let nodes: Vec<Node> = kconfig.entries.iter().map(Node::new).collect();
+    let mut formatter = Formatter::new(options);
+    formatter.entries(&nodes, 0);
+    formatter.output
+}
+
+/// Formats a Kconfig file parsed in [lossless mode](crate::cst), keeping its comments and blank lines.
+/// Comments are indented like the node they precede, a comment at the end of a line stays there.
+pub fn format_cst(cst: &CstKconfig, options: &FormatOptions) -> String {
+    let nodes: Vec<Node> = cst.entries.iter().map(Node::from_cst).collect();
+    let mut formatter = Formatter::new(options);
+    formatter.entries(&nodes, 0);
+    formatter.trivia(cst.trailing_trivia.trim_end(), 0, false);
+    formatter.output
+}
+
+/// Formats a single entry.
+pub fn format_entry(entry: &Entry, options: &FormatOptions) -> String {
+    let mut formatter = Formatter::new(options);
+    formatter.entry(&Node::new(entry), 0);
+    formatter.output
+}
+
+/// Formats the text of a `help` attribute, every line being prefixed with `indent`.
+pub fn format_help(text: &str, indent: &str, width: Option<usize>) -> String {
+    let text = match width {
+        Some(width) => reflow(text, width.saturating_sub(indent.chars().count())),
+        None => text.to_string(),
+    };
+    let mut output = String::from("help\n");
+    for line in text.lines() {
+        match line.trim_end().is_empty() {
+            true => output.push('\n'),
+            false => {
+                output.push_str(indent);
+                output.push_str(line.trim_end());
+                output.push('\n');
+            }
+        }
+    }
+    output
+}
+
+fn reflow(text: &str, width: usize) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let flush = |paragraph: &mut Vec<&str>, lines: &mut Vec<String>| {
+        let mut current = String::new();
+        for word in paragraph.drain(..).flat_map(str::split_whitespace) {
+            if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+    };
+    for line in text.lines() {
+        if line.trim().is_empty() || line.starts_with([' ', '\t']) {
+            flush(&mut paragraph, &mut lines);
+            lines.push(line.to_string());
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut paragraph, &mut lines);
+    lines.join("\n")
+}
+
+/// An entry with the trivia preceding its attributes, its children and its footer.
+/// The trivia is empty when the entry does not come from a [CstKconfig].
+struct Node<'a> {
+    trivia: &'a str,
+    entry: &'a Entry,
+    attributes: Vec<(&'a str, Cow<'a, Attribute>)>,
+    children: Vec<Node<'a>>,
+    footer_trivia: &'a str,
+}
+
+impl<'a> Node<'a> {
+    fn new(entry: &'a Entry) -> Self {
+        let (attributes, children): (Vec<Cow<Attribute>>, &[Entry]) = match entry {
+            Entry::Config(config) | Entry::MenuConfig(config) => {
+                (config.attributes.iter().map(Cow::Borrowed).collect(), &[])
+            }
+            Entry::Choice(choice) => (
+                choice.options.iter().map(Cow::Borrowed).collect(),
+                &choice.entries,
+            ),
+            Entry::Menu(menu) => (
+                menu.visible
+                    .iter()
+                    .cloned()
+                    .map(Attribute::Visible)
+                    .chain(menu.depends_on.iter().cloned().map(Attribute::DependsOn))
+                    .map(Cow::Owned)
+                    .collect(),
+                &menu.entries,
+            ),
+            Entry::Comment(comment) => (
+                comment
+                    .dependencies
+                    .iter()
+                    .cloned()
+                    .map(|d| Cow::Owned(Attribute::DependsOn(d)))
+                    .collect(),
+                &[],
+            ),
+            Entry::If(r#if) => (vec![], &r#if.entries),
+            _ => (vec![], &[]),
+        };
+        Self {
+            trivia: "",
+            entry,
+            attributes: attributes.into_iter().map(|a| ("", a)).collect(),
+            children: children.iter().map(Node::new).collect(),
+            footer_trivia: "",
+        }
+    }
+
+    fn from_cst(cst: &'a CstEntry) -> Self {
+        let mut attributes: Vec<(&str, Cow<Attribute>)> = cst
+            .attributes
+            .iter()
+            .map(|a| (a.leading_trivia.as_str(), Cow::Borrowed(&a.node)))
+            .collect();
+        // `visible` is printed before the dependencies of a menu
+        attributes.sort_by_key(|(_, a)| !matches!(a.as_ref(), Attribute::Visible(_)));
+        Self {
+            trivia: &cst.leading_trivia,
+            entry: &cst.entry,
+            attributes,
+            children: cst.children.iter().map(Node::from_cst).collect(),
+            footer_trivia: cst
+                .footer
+                .as_ref()
+                .map_or("", |f| f.leading_trivia.as_str()),
+        }
+    }
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+    output: String,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(options: &'a FormatOptions) -> Self {
+        Self {
+            options,
+            output: String::new(),
+        }
+    }
+
+    fn indent(&self, depth: usize) -> String {
+        self.options.indent.to_string().repeat(depth)
+    }
+
+    fn line(&mut self, depth: usize, line: impl Display) {
+        let _ = writeln!(self.output, "{}{}", self.indent(depth), line);
+    }
+
+    /// Prints the comments and blank lines of the trivia preceding a node, `blank_line` separates the node from the previous one.
+    fn trivia(&mut self, trivia: &str, depth: usize, blank_line: bool) {
+        let mut lines = trivia.split('\n');
+        // the end of the line of the previous node
+        let end_of_line = lines.next().unwrap_or_default().trim();
+        if !end_of_line.is_empty() {
+            match self.output.trim_end_matches('\n').len() {
+                0 => self.line(depth, end_of_line),
+                end => self.output.insert_str(end, &format!(" {end_of_line}")),
+            }
+        }
+        if blank_line {
+            self.output.push('\n');
+        }
+        // the last line is the indentation of the node, or a comment at the end of the file
+        let lines: Vec<&str> = lines.map(str::trim).collect();
+        for (index, line) in lines.iter().enumerate() {
+            match line.is_empty() {
+                false => self.line(depth, line),
+                true if index + 1 < lines.len()
+                    && !self.output.is_empty()
+                    && !self.output.ends_with("\n\n") =>
+                {
+                    self.output.push('\n')
+                }
+                true => {}
+            }
+        }
+    }
+
+    fn entries(&mut self, nodes: &[Node], depth: usize) {
+        let mut previous: Option<&Entry> = None;
+        for node in nodes {
+            let blank_line = previous.is_some_and(|p| !is_compact(p) || !is_compact(node.entry));
+            self.trivia(node.trivia, depth, blank_line);
+            self.entry(node, depth);
+            previous = Some(node.entry);
+        }
+    }
+
+    fn block(&mut self, node: &Node, depth: usize, end: &str) {
+        let nested = match self.options.indent_blocks {
+            true => depth + 1,
+            false => depth,
+        };
+        if !node.children.is_empty() {
+            self.output.push('\n');
+            self.entries(&node.children, nested);
+        }
+        self.trivia(node.footer_trivia, nested, true);
+        self.line(depth, end);
+    }
+
+    fn entry(&mut self, node: &Node, depth: usize) {
+        match node.entry {
+            Entry::Config(config) => self.config("config", config, node, depth),
+            Entry::MenuConfig(config) => self.config("menuconfig", config, node, depth),
+            Entry::Choice(choice) => self.choice(choice, node, depth),
+            Entry::Menu(menu) => self.menu(menu, node, depth),
+            Entry::Comment(comment) => self.comment(comment, node, depth),
+            Entry::Source(source) => self.source("source", source, depth),
+            Entry::VariableAssignment(assignment) => self.variable(assignment, depth),
+            Entry::FunctionCall(call) => self.line(depth, call),
+            Entry::Function(function) => {
+                self.line(depth, format!("{} = {}", function.name, function.body))
+            }
+            Entry::If(r#if) => self.r#if(r#if, node, depth),
+            Entry::MainMenu(main_menu) => {
+                self.line(depth, format!("mainmenu \"{}\"", main_menu.prompt))
+            }
+            #[cfg(feature = "kconfiglib")]
+            Entry::ConfigDefault(config_default) => {
+                self.line(depth, format!("configdefault {}", config_default.symbol));
+                self.line(depth + 1, format!("default {}", config_default.default));
+            }
+            #[cfg(feature = "kconfiglib")]
+            Entry::OSource(source) => self.source("osource", source, depth),
+            #[cfg(feature = "kconfiglib")]
+            Entry::RSource(source) => self.source("rsource", source, depth),
+            #[cfg(feature = "kconfiglib")]
+            Entry::OrSource(source) => self.source("orsource", source, depth),
+        }
+    }
+
+    fn attributes(&mut self, attributes: &[(&str, Cow<Attribute>)], depth: usize) {
+        for (trivia, attribute) in attributes {
+            self.trivia(trivia, depth, false);
+            match attribute.as_ref() {
+                Attribute::Help(text, _) => {
+                    let indent = format!(
+                        "{}{:width$}",
+                        self.indent(depth),
+                        "",
+                        width = self.options.help_indent
+                    );
+                    let help = format_help(text, &indent, self.options.help_width);
+                    self.output.push_str(&self.indent(depth));
+                    self.output.push_str(&help);
+                }
+                attribute => self.line(depth, attribute),
+            }
+        }
+    }
+
+    fn config(&mut self, keyword: &str, config: &Config, node: &Node, depth: usize) {
+        self.line(depth, format!("{} {}", keyword, config.symbol));
+        self.attributes(&node.attributes, depth + 1);
+    }
+
+    fn choice(&mut self, choice: &Choice, node: &Node, depth: usize) {
+        #[cfg(feature = "named-choice")]
+        match &choice.name {
+            Some(name) => self.line(depth, format!("choice {name}")),
+            None => self.line(depth, "choice"),
+        }
+        #[cfg(not(feature = "named-choice"))]
+        self.line(depth, "choice");
+        self.attributes(&node.attributes, depth + 1);
+        self.block(node, depth, "endchoice");
+    }
+
+    fn menu(&mut self, menu: &Menu, node: &Node, depth: usize) {
+        self.line(depth, format!("menu \"{}\"", menu.prompt));
+        self.attributes(&node.attributes, depth + 1);
+        self.block(node, depth, "endmenu");
+    }
+
+    fn comment(&mut self, comment: &Comment, node: &Node, depth: usize) {
+        self.line(depth, format!("comment \"{}\"", comment.prompt));
+        self.attributes(&node.attributes, depth + 1);
+    }
+
+    fn r#if(&mut self, r#if: &If, node: &Node, depth: usize) {
+        self.line(depth, format!("if {}", r#if.condition));
+        self.block(node, depth, "endif");
+    }
+
+    fn source(&mut self, keyword: &str, source: &Source, depth: usize) {
+        self.line(depth, format!("{keyword} \"{}\"", source.path));
+    }
+
+    fn variable(&mut self, assignment: &VariableAssignment, depth: usize) {
+        let identifier = match &assignment.identifier {
+            VariableIdentifier::Identifier(identifier) => identifier.clone(),
+            VariableIdentifier::VariableRef(tokens) => tokens
+                .iter()
+                .map(|token| match token {
+                    ExpressionToken::Variable(variable) => format!("$({variable})"),
+                    token => token.to_string(),
+                })
+                .collect::<String>(),
+        };
+        let value = match &assignment.right {
+            Value::Literal(literal) => literal.clone(),
+            Value::ExpandedVariable(variable) => variable.clone(),
+            Value::FunctionCall(call) => call.to_string(),
+        };
+        self.line(
+            depth,
+            format!("{} {} {}", identifier, assignment.operator, value).trim_end(),
+        );
+    }
+}
+
+/// One-line statements that are not separated by a blank line.
+fn is_compact(entry: &Entry) -> bool {
+    match entry {
+        Entry::Source(_) | Entry::VariableAssignment(_) | Entry::FunctionCall(_) => true,
+        #[cfg(feature = "kconfiglib")]
+        Entry::OSource(_) | Entry::RSource(_) | Entry::OrSource(_) => true,
+        _ => false,
+    }
+}
+
+impl Display for Kconfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_kconfig(self, &FormatOptions::default()))
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_entry(self, &FormatOptions::default()))
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    cst::parse_kconfig_lossless,
+    format::{format_cst, format_entry, format_kconfig, FormatOptions, Indent},
+    kconfig::parse_kconfig,
+    lib_test::{linux_files, parse},
+    KconfigInput,
+};
+
+fn assert_round_trip(input: &str, options: &FormatOptions) -> String {
+    let kconfig = parse(input);
+    let formatted = format_kconfig(&kconfig, options);
+    assert_eq!(parse(&formatted), kconfig, "formatted:\n{formatted}");
+    assert_eq!(format_kconfig(&parse(&formatted), options), formatted);
+    formatted
+}
+
+const INPUT: &str = r#"mainmenu "Linux/$(ARCH) $(KERNELVERSION) Kernel Configuration"
+CC := gcc
+ARCH = x86
+menu   "General setup"
+visible if !EMBEDDED
+  depends on   A||B
+config LOCALVERSION
+    string "Local version" if EXPERT
+    default "-dev"
+    help
+     Append an extra string.
+
+       indented line
+     back.
+menuconfig NET
+    bool "Networking"   # comment
+    select   NET_CORE if PCI
+    imply NET_FOO
+    range 1 5
+    depends on !(A && B) || C = "y"
+    option modules
+if NET
+comment "Drivers"
+  depends on PCI
+choice
+    prompt "Mode"
+    optional
+    default MODE_A
+config MODE_A
+    bool "A"
+config MODE_B
+    bool "B"
+endchoice
+endif
+endmenu
+source "Kconfig.simple"
+"#;
+
+#[test]
+fn test_format_round_trip() {
+    let formatted = assert_round_trip(INPUT, &FormatOptions::default());
+    assert_eq!(
+        formatted,
+        r#"mainmenu "Linux/$(ARCH) $(KERNELVERSION) Kernel Configuration"
+
+CC := gcc
+ARCH = x86
+
+menu "General setup"
+	visible if !EMBEDDED
+	depends on A || B
+
+config LOCALVERSION
+	string "Local version" if EXPERT
+	default "-dev"
+	help
+	  Append an extra string.
+
+	    indented line
+	  back.
+
+menuconfig NET
+	bool "Networking"
+	select NET_CORE if PCI
+	imply NET_FOO
+	range 1 5
+	depends on !(A && B) || C = "y"
+	option modules
+
+if NET
+
+comment "Drivers"
+	depends on PCI
+
+choice
+	prompt "Mode"
+	optional
+	default MODE_A
+
+config MODE_A
+	bool "A"
+
+config MODE_B
+	bool "B"
+
+endchoice
+
+endif
+
+endmenu
+
+source "Kconfig.simple"
+"#
+    );
+}
+
+#[test]
+fn test_format_indent_blocks() {
+    let options = FormatOptions {
+        indent: Indent::Spaces(2),
+        indent_blocks: true,
+        ..Default::default()
+    };
+    let formatted = assert_round_trip(
+        "if A\nmenu \"M\"\nconfig B\nbool\nhelp\n hello\nendmenu\nendif",
+        &options,
+    );
+    assert_eq!(
+        formatted,
+        "if A\n\n  menu \"M\"\n\n    config B\n      bool\n      help\n        hello\n\n  endmenu\n\nendif\n"
+    );
+}
+
+#[test]
+fn test_format_help_width() {
+    let options = FormatOptions {
+        help_width: Some(30),
+        ..Default::default()
+    };
+    let kconfig = parse(
+        "config A\n\tbool\n\thelp\n\t  Some words that are long enough to\n\t  be wrapped.\n\n\t    keep  me\n",
+    );
+    assert_eq!(
+        format_kconfig(&kconfig, &options),
+        "config A\n\tbool\n\thelp\n\t  Some words that are long\n\t  enough to be wrapped.\n\n\t    keep  me\n"
+    );
+}
+
+#[test]
+fn test_format_entry_display() {
+    let kconfig = parse("config A\n bool \"a\"\n");
+    assert_eq!(
+        format_entry(&kconfig.entries[0], &FormatOptions::default()),
+        kconfig.entries[0].to_string()
+    );
+    assert_eq!(kconfig.to_string(), "config A\n\tbool \"a\"\n");
+}
+
+#[test]
+fn test_format_linux_files() {
+    let options = FormatOptions::default();
+    for (kconfig_file, input) in linux_files() {
+        let file = kconfig_file.file.display().to_string();
+        let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&input, kconfig_file.clone()))
+            .unwrap_or_else(|e| panic!("{}: {:?}", file, e));
+        let formatted = format_kconfig(&kconfig, &options);
+        let (_, reparsed) =
+            parse_kconfig(KconfigInput::new_extra(&formatted, kconfig_file.clone()))
+                .unwrap_or_else(|e| panic!("{}: {:?}\n{}", file, e, formatted));
+        assert_eq!(reparsed, kconfig, "{}", file);
+
+        let (_, cst) =
+            parse_kconfig_lossless(KconfigInput::new_extra(&input, kconfig_file.clone()))
+                .unwrap_or_else(|e| panic!("{}: {:?}", file, e));
+        let formatted = format_cst(&cst, &options);
+        let (_, reparsed) = parse_kconfig(KconfigInput::new_extra(&formatted, kconfig_file))
+            .unwrap_or_else(|e| panic!("{}: {:?}\n{}", file, e, formatted));
+        assert_eq!(reparsed, kconfig, "{}", file);
+        assert_eq!(
+            formatted.matches('#').count(),
+            input.matches('#').count(),
+            "{}",
+            file
+        );
+    }
+}
+
+#[test]
+fn test_format_macros() {
+    let input = "config CC_VERSION_TEXT\n\tstring\n\tdefault \"$(CC_VERSION_TEXT)\"\n\nconfig CC_IS_GCC\n\tdef_bool $(success, test \"$(cc-name)\" = GCC)\n";
+    let formatted = assert_round_trip(input, &FormatOptions::default());
+    assert_eq!(formatted, input);
+}
+
+#[cfg(feature = "kconfiglib")]
+#[test]
+fn test_format_sources_as_written() {
+    use std::path::PathBuf;
+
+    use crate::{vfs::MemoryFs, Entry, KconfigFile};
+
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/boards/a.Kconfig", "config A\n\tbool\n");
+    fs.insert("/kernel/boards/b.Kconfig", "config B\n\tbool\n");
+    fs.insert("/kernel/arch/x86/Kconfig", "config X86\n\tbool\n");
+    let input = "BOARDS := boards\nsource \"$(BOARDS)/*.Kconfig\"\nosource \"missing/Kconfig\"\nrsource \"arch/x86/Kconfig\"\n";
+    let kconfig_file =
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig")).with_vfs(fs);
+    let content = kconfig_file.preprocess_content(input.to_string());
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file)).unwrap();
+    assert!(matches!(&kconfig.entries[1], Entry::Source(source) if source.kconfigs.len() == 2));
+    assert_eq!(format_kconfig(&kconfig, &FormatOptions::default()), input);
+}
+
+#[test]
+fn test_format_cst_keeps_comments() {
+    let input = r#"# SPDX-License-Identifier: GPL-2.0
+
+
+menu "Networking" # net
+  depends on   A
+    # the main switch
+
+config NET
+    bool "Networking"   # on by default
+    # only with A
+
+    depends on A
+    help
+      Say Y.
+# the end of the menu
+endmenu # net
+# eof
+
+"#;
+    let (_, cst) =
+        parse_kconfig_lossless(KconfigInput::new_extra(input, Default::default())).unwrap();
+    let formatted = format_cst(&cst, &FormatOptions::default());
+    assert_eq!(
+        formatted,
+        r#"# SPDX-License-Identifier: GPL-2.0
+
+menu "Networking" # net
+	depends on A
+
+# the main switch
+
+config NET
+	bool "Networking" # on by default
+	# only with A
+
+	depends on A
+	help
+	  Say Y.
+
+# the end of the menu
+endmenu # net
+# eof
+"#
+    );
+    assert_eq!(parse(&formatted), parse(input));
+    let (_, reparsed) =
+        parse_kconfig_lossless(KconfigInput::new_extra(&formatted, Default::default())).unwrap();
+    assert_eq!(format_cst(&reparsed, &FormatOptions::default()), formatted);
+}
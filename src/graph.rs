@@ -0,0 +1,516 @@
+//! The dependency graph of a [SymbolTable].
+//!
+//! Every symbol and every choice is a node. An edge goes from the symbol declaring an attribute to the symbol the attribute refers to:
+//! `config A` with `depends on B`, `select B`, `imply B`, `default B`, `range 0 B` or a choice containing `B` all give an edge `A -> B`.
+//! Edges are labelled with their [EdgeKind] and the `if` condition of the attribute.
+//! `depends on` edges include the dependencies inherited from menus and `if` blocks.
+//!
+//! ```
+//! use nom_kconfig::{graph::DependencyGraph, parse_kconfig, KconfigInput, SymbolTable};
+//!
+//! let input = r#"
+//! config FOO
+//!     bool "foo"
+//!     select BAR if BAZ
+//! config BAR
+//!     bool
+//! "#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let graph = DependencyGraph::new(&SymbolTable::new(&kconfig));
+//! assert!(graph.to_dot().contains(r#""FOO" -> "BAR" [label="select if BAZ"];"#));
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{range::RangeBound, Expression},
+    symbol_table::{ChoiceRecord, SymbolTable, SymbolType},
+    util::escape_json,
+    Kconfig, Symbol,
+};
+
+/// The attribute an edge comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "snake_case")
+)]
+pub enum EdgeKind {
+    DependsOn,
+    Select,
+    Imply,
+    Default,
+    Range,
+    /// From a choice to one of its symbols.
+    Choice,
+}
+
+impl EdgeKind {
+    /// The keyword of the attribute, used as a label.
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            EdgeKind::DependsOn => "depends on",
+            EdgeKind::Select => "select",
+            EdgeKind::Imply => "imply",
+            EdgeKind::Default => "default",
+            EdgeKind::Range => "range",
+            EdgeKind::Choice => "choice",
+        }
+    }
+}
+
+impl std::fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.keyword())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "lowercase")
+)]
+pub enum NodeKind {
+    Symbol,
+    Choice,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Node {
+    /// The name of the symbol or the choice. Anonymous choices are named `<choice N>`, `N` being their index in [SymbolTable::choices].
+    pub name: String,
+    pub kind: NodeKind,
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub r#type: Option<SymbolType>,
+    /// `false` for symbols that are referenced but never defined.
+    pub defined: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Edge {
+    /// Index of the source node in [DependencyGraph::nodes].
+    pub from: usize,
+    /// Index of the target node in [DependencyGraph::nodes].
+    pub to: usize,
+    pub kind: EdgeKind,
+    /// The `if` condition of the attribute.
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub condition: Option<Expression>,
+}
+
+impl Edge {
+    /// The label of the edge: the kind followed by the condition, `select if BAZ`.
+    pub fn label(&self) -> String {
+        match &self.condition {
+            Some(condition) => format!("{} if {}", self.kind, condition),
+            None => self.kind.to_string(),
+        }
+    }
+}
+
+/// Symbols and choices linked by their attributes.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct DependencyGraph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    index: HashMap<String, usize>,
+}
+
+impl DependencyGraph {
+    pub fn new(table: &SymbolTable) -> Self {
+        let mut graph = Self::default();
+        for record in table.symbols().chain(table.undefined()) {
+            let node = graph.node_mut(&record.name, NodeKind::Symbol);
+            graph.nodes[node].r#type = record.r#type();
+            graph.nodes[node].defined = !record.definitions.is_empty();
+        }
+        for (index, choice) in table.choices().iter().enumerate() {
+            let node = graph.node_mut(&choice_name(choice, index), NodeKind::Choice);
+            graph.nodes[node].r#type = choice.r#type();
+            graph.nodes[node].defined = true;
+        }
+
+        let mut seen = HashSet::new();
+        let mut add =
+            |graph: &mut Self, from: usize, to: &str, kind, condition: &Option<Expression>| {
+                let to = graph.node_mut(to, NodeKind::Symbol);
+                let label = condition.as_ref().map(|c| c.to_string());
+                if seen.insert((from, to, kind, label)) {
+                    graph.edges.push(Edge {
+                        from,
+                        to,
+                        kind,
+                        condition: condition.clone(),
+                    });
+                }
+            };
+
+        for record in table.symbols() {
+            let from = graph.index[&record.name];
+            for definition in &record.definitions {
+                for symbol in definition.dependencies.iter().flat_map(|d| d.symbols()) {
+                    add(&mut graph, from, symbol, EdgeKind::DependsOn, &None);
+                }
+            }
+            for select in &record.selects {
+                let value = &select.value;
+                add(
+                    &mut graph,
+                    from,
+                    &value.symbol,
+                    EdgeKind::Select,
+                    &value.r#if,
+                );
+            }
+            for imply in &record.implies {
+                if let Symbol::NonConstant(symbol) = &imply.value.symbol {
+                    add(&mut graph, from, symbol, EdgeKind::Imply, &imply.value.r#if);
+                }
+            }
+            for default in &record.defaults {
+                let value = &default.value;
+                for symbol in value.expression.symbols() {
+                    add(&mut graph, from, symbol, EdgeKind::Default, &value.r#if);
+                }
+            }
+            for range in &record.ranges {
+                let value = &range.value;
+                for bound in [&value.lower_bound, &value.upper_bound] {
+                    if let RangeBound::Symbol(symbol) = bound {
+                        add(&mut graph, from, symbol, EdgeKind::Range, &value.r#if);
+                    }
+                }
+            }
+        }
+        for (index, choice) in table.choices().iter().enumerate() {
+            let from = graph.index[&choice_name(choice, index)];
+            for definition in &choice.definitions {
+                for symbol in definition.dependencies.iter().flat_map(|d| d.symbols()) {
+                    add(&mut graph, from, symbol, EdgeKind::DependsOn, &None);
+                }
+            }
+            for default in &choice.defaults {
+                let value = &default.value;
+                for symbol in value.expression.symbols() {
+                    add(&mut graph, from, symbol, EdgeKind::Default, &value.r#if);
+                }
+            }
+            for member in &choice.members {
+                add(&mut graph, from, member, EdgeKind::Choice, &None);
+            }
+        }
+        graph
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Looks up a node. Like [SymbolTable::get], the `CONFIG_` prefix is optional.
+    pub fn node(&self, name: &str) -> Option<&Node> {
+        self.position(name).map(|i| &self.nodes[i])
+    }
+
+    /// Edges leaving a node.
+    pub fn edges_from<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a Edge> + 'a {
+        let node = self.position(name);
+        self.edges.iter().filter(move |e| Some(e.from) == node)
+    }
+
+    /// Edges entering a node.
+    pub fn edges_to<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a Edge> + 'a {
+        let node = self.position(name);
+        self.edges.iter().filter(move |e| Some(e.to) == node)
+    }
+
+    /// The subgraph of the nodes referring to `name`, directly or through at most `hops` edges.
+    /// Returns `None` if there is no such node.
+    pub fn ancestors(&self, name: &str, hops: usize) -> Option<Self> {
+        self.neighborhood(name, hops, |e| (e.to, e.from))
+    }
+
+    /// The subgraph of the nodes `name` refers to, directly or through at most `hops` edges.
+    /// Returns `None` if there is no such node.
+    pub fn descendants(&self, name: &str, hops: usize) -> Option<Self> {
+        self.neighborhood(name, hops, |e| (e.from, e.to))
+    }
+
+    /// Exports the graph in the Graphviz DOT format.
+    /// Choices are drawn as diamonds and undefined symbols with dashed borders.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph kconfig {\n");
+        for node in &self.nodes {
+            let mut attributes = vec![];
+            if node.kind == NodeKind::Choice {
+                attributes.push("shape=diamond");
+            }
+            if !node.defined {
+                attributes.push("style=dashed");
+            }
+            match attributes.is_empty() {
+                true => writeln!(dot, "    \"{}\";", escape_dot(&node.name)),
+                false => writeln!(
+                    dot,
+                    "    \"{}\" [{}];",
+                    escape_dot(&node.name),
+                    attributes.join(", ")
+                ),
+            }
+            .unwrap();
+        }
+        for edge in &self.edges {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape_dot(&self.nodes[edge.from].name),
+                escape_dot(&self.nodes[edge.to].name),
+                escape_dot(&edge.label())
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph in the GraphML format.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="kind" for="node" attr.name="kind" attr.type="string"/>
+  <key id="type" for="node" attr.name="type" attr.type="string"/>
+  <key id="defined" for="node" attr.name="defined" attr.type="boolean"/>
+  <key id="edge_kind" for="edge" attr.name="kind" attr.type="string"/>
+  <key id="condition" for="edge" attr.name="condition" attr.type="string"/>
+  <graph id="kconfig" edgedefault="directed">
+"#,
+        );
+        for node in &self.nodes {
+            write!(
+                xml,
+                "    <node id=\"{}\"><data key=\"kind\">{}</data>",
+                escape_xml(&node.name),
+                node_kind(node.kind)
+            )
+            .unwrap();
+            if let Some(r#type) = node.r#type {
+                write!(xml, "<data key=\"type\">{}</data>", r#type).unwrap();
+            }
+            writeln!(xml, "<data key=\"defined\">{}</data></node>", node.defined).unwrap();
+        }
+        for edge in &self.edges {
+            write!(
+                xml,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"edge_kind\">{}</data>",
+                escape_xml(&self.nodes[edge.from].name),
+                escape_xml(&self.nodes[edge.to].name),
+                edge.kind
+            )
+            .unwrap();
+            if let Some(condition) = &edge.condition {
+                write!(
+                    xml,
+                    "<data key=\"condition\">{}</data>",
+                    escape_xml(&condition.to_string())
+                )
+                .unwrap();
+            }
+            xml.push_str("</edge>\n");
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Exports the graph as JSON adjacency lists:
+    /// `{"nodes": [...], "adjacency": {"FOO": [{"target": "BAR", "kind": "select", "condition": "BAZ"}]}}`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\n  \"nodes\": [");
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "\n    {{\"name\": {}, \"kind\": \"{}\"",
+                escape_json(&node.name),
+                node_kind(node.kind)
+            )
+            .unwrap();
+            if let Some(r#type) = node.r#type {
+                write!(json, ", \"type\": \"{}\"", r#type).unwrap();
+            }
+            write!(json, ", \"defined\": {}}}", node.defined).unwrap();
+        }
+        json.push_str("\n  ],\n  \"adjacency\": {");
+        let mut adjacency: Vec<Vec<&Edge>> = vec![vec![]; self.nodes.len()];
+        for edge in &self.edges {
+            adjacency[edge.from].push(edge);
+        }
+        for (i, (node, edges)) in self.nodes.iter().zip(adjacency).enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(json, "\n    {}: [", escape_json(&node.name)).unwrap();
+            for (j, edge) in edges.iter().enumerate() {
+                if j > 0 {
+                    json.push_str(", ");
+                }
+                write!(
+                    json,
+                    "{{\"target\": {}, \"kind\": \"{}\"",
+                    escape_json(&self.nodes[edge.to].name),
+                    edge.kind
+                )
+                .unwrap();
+                if let Some(condition) = &edge.condition {
+                    write!(
+                        json,
+                        ", \"condition\": {}",
+                        escape_json(&condition.to_string())
+                    )
+                    .unwrap();
+                }
+                json.push('}');
+            }
+            json.push(']');
+        }
+        json.push_str("\n  }\n}\n");
+        json
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.index
+            .get(name)
+            .or_else(|| {
+                name.strip_prefix("CONFIG_")
+                    .and_then(|stripped| self.index.get(stripped))
+            })
+            .copied()
+    }
+
+    fn node_mut(&mut self, name: &str, kind: NodeKind) -> usize {
+        match self.index.get(name) {
+            Some(index) => *index,
+            None => {
+                self.nodes.push(Node {
+                    name: name.to_string(),
+                    kind,
+                    r#type: None,
+                    defined: false,
+                });
+                self.index.insert(name.to_string(), self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Breadth-first search following `direction`, which returns the (current, next) nodes of an edge.
+    fn neighborhood(
+        &self,
+        name: &str,
+        hops: usize,
+        direction: impl Fn(&Edge) -> (usize, usize),
+    ) -> Option<Self> {
+        let start = self.position(name)?;
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[&current];
+            if distance == hops {
+                continue;
+            }
+            for edge in &self.edges {
+                let (from, to) = direction(edge);
+                if from == current && !distances.contains_key(&to) {
+                    distances.insert(to, distance + 1);
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        let mut graph = Self::default();
+        let mut mapping = HashMap::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            if distances.contains_key(&i) {
+                mapping.insert(i, graph.nodes.len());
+                graph.index.insert(node.name.clone(), graph.nodes.len());
+                graph.nodes.push(node.clone());
+            }
+        }
+        graph.edges = self
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let (from, to) = direction(edge);
+                // only the edges leading away from the start node are kept
+                match (distances.get(&from), distances.get(&to)) {
+                    (Some(f), Some(t)) if f < t => Some(Edge {
+                        from: mapping[&edge.from],
+                        to: mapping[&edge.to],
+                        ..edge.clone()
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+        Some(graph)
+    }
+}
+
+impl From<&Kconfig> for DependencyGraph {
+    fn from(kconfig: &Kconfig) -> Self {
+        Self::new(&SymbolTable::new(kconfig))
+    }
+}
+
+fn choice_name(choice: &ChoiceRecord, index: usize) -> String {
+    match &choice.name {
+        Some(name) => name.clone(),
+        None => format!("<choice {}>", index),
+    }
+}
+
+fn node_kind(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Symbol => "symbol",
+        NodeKind::Choice => "choice",
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
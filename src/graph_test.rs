@@ -0,0 +1,155 @@
+use crate::{
+    graph::{DependencyGraph, EdgeKind, NodeKind},
+    lib_test::parse,
+};
+
+const INPUT: &str = r#"
+config NET
+    bool "net"
+
+if NET
+config FOO
+    tristate "foo"
+    depends on PCI
+    select BAR if BAZ
+    imply QUX
+    default NET && !QUX
+endif
+
+config BAR
+    int "bar"
+    range MIN 10 if FOO
+    default 4
+
+choice
+    prompt "mode"
+    default FAST
+config FAST
+    bool "fast"
+config SLOW
+    bool "slow"
+endchoice
+"#;
+
+fn edges(graph: &DependencyGraph) -> Vec<(String, String, EdgeKind, Option<String>)> {
+    graph
+        .edges()
+        .iter()
+        .map(|e| {
+            (
+                graph.nodes()[e.from].name.clone(),
+                graph.nodes()[e.to].name.clone(),
+                e.kind,
+                e.condition.as_ref().map(|c| c.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn edge(
+    from: &str,
+    to: &str,
+    kind: EdgeKind,
+    condition: Option<&str>,
+) -> (String, String, EdgeKind, Option<String>) {
+    (
+        from.to_string(),
+        to.to_string(),
+        kind,
+        condition.map(str::to_string),
+    )
+}
+
+#[test]
+fn test_dependency_graph_edges() {
+    let graph = DependencyGraph::from(&parse(INPUT));
+    assert_eq!(
+        edges(&graph),
+        vec![
+            edge("FOO", "NET", EdgeKind::DependsOn, None),
+            edge("FOO", "PCI", EdgeKind::DependsOn, None),
+            edge("FOO", "BAR", EdgeKind::Select, Some("BAZ")),
+            edge("FOO", "QUX", EdgeKind::Imply, None),
+            edge("FOO", "NET", EdgeKind::Default, None),
+            edge("FOO", "QUX", EdgeKind::Default, None),
+            edge("BAR", "MIN", EdgeKind::Range, Some("FOO")),
+            edge("<choice 0>", "FAST", EdgeKind::Default, None),
+            edge("<choice 0>", "FAST", EdgeKind::Choice, None),
+            edge("<choice 0>", "SLOW", EdgeKind::Choice, None),
+        ]
+    );
+    let choice = graph.node("<choice 0>").unwrap();
+    assert_eq!(choice.kind, NodeKind::Choice);
+    assert!(graph.node("CONFIG_NET").unwrap().defined);
+    assert!(!graph.node("PCI").unwrap().defined);
+    assert_eq!(graph.edges_to("QUX").count(), 2);
+}
+
+#[test]
+fn test_dependency_graph_filters() {
+    let graph = DependencyGraph::from(&parse(INPUT));
+    let descendants = graph.descendants("FOO", 1).unwrap();
+    let names: Vec<&str> = descendants
+        .nodes()
+        .iter()
+        .map(|n| n.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["NET", "FOO", "BAR", "QUX", "PCI"]);
+    assert_eq!(descendants.edges().len(), 6);
+    assert!(descendants.node("MIN").is_none());
+
+    let descendants = graph.descendants("FOO", 2).unwrap();
+    assert!(descendants.node("MIN").is_some());
+
+    let ancestors = graph.ancestors("NET", 1).unwrap();
+    assert_eq!(
+        edges(&ancestors),
+        vec![
+            edge("FOO", "NET", EdgeKind::DependsOn, None),
+            edge("FOO", "NET", EdgeKind::Default, None),
+        ]
+    );
+    let ancestors = graph.ancestors("MIN", 5).unwrap();
+    assert_eq!(ancestors.nodes().len(), 3);
+    assert!(graph.ancestors("UNKNOWN", 1).is_none());
+}
+
+#[test]
+fn test_dependency_graph_exports() {
+    let graph = DependencyGraph::from(&parse(INPUT))
+        .ancestors("BAR", 1)
+        .unwrap();
+    assert_eq!(
+        graph.to_dot(),
+        r#"digraph kconfig {
+    "FOO";
+    "BAR";
+    "FOO" -> "BAR" [label="select if BAZ"];
+}
+"#
+    );
+    assert_eq!(
+        graph.to_json(),
+        r#"{
+  "nodes": [
+    {"name": "FOO", "kind": "symbol", "type": "tristate", "defined": true},
+    {"name": "BAR", "kind": "symbol", "type": "int", "defined": true}
+  ],
+  "adjacency": {
+    "FOO": [{"target": "BAR", "kind": "select", "condition": "BAZ"}],
+    "BAR": []
+  }
+}
+"#
+    );
+    let graphml = DependencyGraph::from(&parse(INPUT)).to_graphml();
+    assert!(graphml.contains(
+        r#"<node id="&lt;choice 0&gt;"><data key="kind">choice</data><data key="defined">true</data></node>"#
+    ));
+    assert!(graphml.contains(
+        r#"<edge source="BAR" target="MIN"><data key="edge_kind">range</data><data key="condition">FOO</data></edge>"#
+    ));
+    assert!(graphml.contains(
+        r#"<node id="PCI"><data key="kind">symbol</data><data key="defined">false</data></node>"#
+    ));
+}
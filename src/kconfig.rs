@@ -12,7 +12,7 @@ use serde::Serialize;
 use tracing::debug;
 
 use crate::{
-    entry::{parse_entry, Entry},
+    entry::{parse_entries_recovering, parse_entry, Entry},
     error::Error,
     util::{ws, ws_comment},
     KconfigInput,
@@ -29,6 +29,23 @@ pub struct Kconfig {
     pub entries: Vec<Entry>,
 }
 
+impl Kconfig {
+    /// The file of this Kconfig followed by the files it sources, recursively, without duplicates.
+    pub fn files(&self) -> Vec<&str> {
+        let mut files = vec![];
+        collect_files(self, &mut files);
+        files
+    }
+
+    /// The prompt of the `mainmenu` entry, if any.
+    pub fn main_menu(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::MainMenu(main_menu) => Some(main_menu.prompt.as_str()),
+            _ => None,
+        })
+    }
+}
+
 /// Parses a kconfig input.
 /// # Example
 /// ```
@@ -47,16 +64,43 @@ pub fn parse_kconfig(input: KconfigInput) -> Result<(KconfigInput, Kconfig), Err
     }
 }
 
-pub(crate) fn private_parse_kconfig(input: KconfigInput) -> IResult<KconfigInput, Kconfig> {
+pub(crate) fn private_parse_kconfig<'a>(
+    input: KconfigInput<'a>,
+) -> IResult<KconfigInput<'a>, Kconfig> {
     #[cfg(feature = "debug")]
     debug!("parsing '{}'", input.extra.full_path().display());
     let file: std::path::PathBuf = input.extra.file.clone();
-    let (input, result) = map(delimited(ws_comment, many0(parse_entry), ws(eof)), |d| {
-        Kconfig {
-            file: file.display().to_string(),
-            entries: d,
-        }
+    let entries = |input: KconfigInput<'a>| match input.extra.diagnostics {
+        Some(_) => parse_entries_recovering(None).parse(input),
+        None => many0(parse_entry).parse(input),
+    };
+    let (input, result) = map(delimited(ws_comment, entries, ws(eof)), |d| Kconfig {
+        file: file.display().to_string(),
+        entries: d,
     })
     .parse(input)?;
     Ok((input, result))
 }
+
+fn collect_files<'a>(kconfig: &'a Kconfig, files: &mut Vec<&'a str>) {
+    if !files.contains(&kconfig.file.as_str()) {
+        files.push(&kconfig.file);
+    }
+    collect_entries_files(&kconfig.entries, files);
+}
+
+fn collect_entries_files<'a>(entries: &'a [Entry], files: &mut Vec<&'a str>) {
+    for entry in entries {
+        match entry {
+            Entry::Menu(menu) => collect_entries_files(&menu.entries, files),
+            Entry::If(r#if) => collect_entries_files(&r#if.entries, files),
+            Entry::Choice(choice) => collect_entries_files(&choice.entries, files),
+            Entry::Source(source) => source.kconfigs.iter().for_each(|k| collect_files(k, files)),
+            #[cfg(feature = "kconfiglib")]
+            Entry::OSource(source) | Entry::RSource(source) | Entry::OrSource(source) => {
+                source.kconfigs.iter().for_each(|k| collect_files(k, files))
+            }
+            _ => (),
+        }
+    }
+}
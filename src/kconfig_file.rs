@@ -27,9 +27,42 @@
 //! ```
 
 use std::collections::HashMap;
+use std::io;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::{fs, io};
+
+use crate::{
+    diagnostic::{Diagnostics, Severity},
+    entry::SourceMode,
+    preprocess::{Preprocessor, Variable},
+    shell::ShellPolicy,
+    span::Span,
+    toolchain::ToolchainProfile,
+    vfs::{Vfs, VfsHandle},
+};
+
+/// Parses a variable given on the command line as `KEY=VALUE`, `--var SRCARCH=x86` for instance.
+pub fn parse_variable(variable: &str) -> Result<(String, String), String> {
+    variable
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("'{}' is not a KEY=VALUE variable", variable))
+}
+
+/// The global variables given on the command line, see [KconfigFile::new_with_vars].
+/// `srcarch` sets both `SRCARCH` and `ARCH`, `variables` override it.
+pub fn command_line_variables(
+    srcarch: Option<&str>,
+    variables: &[(String, String)],
+) -> HashMap<String, String> {
+    let mut global_vars = HashMap::new();
+    if let Some(srcarch) = srcarch {
+        global_vars.insert("SRCARCH".to_string(), srcarch.to_string());
+        global_vars.insert("ARCH".to_string(), srcarch.to_string());
+    }
+    global_vars.extend(variables.iter().cloned());
+    global_vars
+}
 
 /// Represents a Kconfig file.
 /// It stores the kernel root directory because we need this information when a [`source`](https://www.kernel.org/doc/html/next/kbuild/kconfig-language.html#kconfig-syntax) keyword is met.
@@ -41,10 +74,22 @@ pub struct KconfigFile {
     pub file: PathBuf,
     /// Externally-specified variables to use when including child source files
     pub global_vars: Rc<HashMap<String, String>>,
-    pub local_vars: Rc<HashMap<String, String>>,
-    pub external_functions: Rc<HashMap<String, String>>,
+    /// Variables assigned in the Kconfig files parsed so far.
+    pub local_vars: Rc<HashMap<String, Variable>>,
     pub depth: usize,
     pub parent_file: Option<PathBuf>,
+    /// Locations of the `source` statements that led to this file, the outermost first.
+    pub include_chain: Vec<Span>,
+    /// When set, parsers record errors there and recover instead of failing, see [diagnostic](crate::diagnostic).
+    pub diagnostics: Option<Diagnostics>,
+    /// Where the sourced files are read from.
+    pub vfs: VfsHandle,
+    /// Whether sourced files are parsed right away or recorded for later, see [SourceMode].
+    pub source_mode: SourceMode,
+    /// How `$(shell,...)` is expanded, see [shell](crate::shell).
+    pub shell_policy: ShellPolicy,
+    /// Answers `$(cc-option)`, `$(cc-version)`... without running the toolchain, see [toolchain](crate::toolchain).
+    pub toolchain: Option<Rc<ToolchainProfile>>,
 }
 
 impl KconfigFile {
@@ -54,9 +99,14 @@ impl KconfigFile {
             file,
             global_vars: Rc::new(HashMap::new()),
             local_vars: Rc::new(HashMap::new()),
-            external_functions: Rc::new(HashMap::new()),
             depth: 0,
             parent_file: None,
+            include_chain: vec![],
+            diagnostics: None,
+            vfs: VfsHandle::default(),
+            source_mode: SourceMode::Eager,
+            shell_policy: ShellPolicy::Keep,
+            toolchain: None,
         }
     }
 
@@ -78,17 +128,39 @@ impl KconfigFile {
             local_vars: Rc::new(
                 local_vars
                     .iter()
-                    .map(|(s1, s2)| (s1.as_ref().to_string(), s2.as_ref().to_string()))
+                    .map(|(s1, s2)| (s1.as_ref().to_string(), Variable::recursive(s2.as_ref())))
                     .collect(),
             ),
-            external_functions: Rc::new(HashMap::new()),
             depth: 0,
             parent_file: None,
+            include_chain: vec![],
+            diagnostics: None,
+            vfs: VfsHandle::default(),
+            source_mode: SourceMode::Eager,
+            shell_policy: ShellPolicy::Keep,
+            toolchain: None,
         }
     }
 
-    pub fn with_external_functions(mut self, external_functions: &HashMap<String, String>) -> Self {
-        self.external_functions = Rc::new(external_functions.clone());
+    /// Reads the Kconfig files from `vfs` instead of the filesystem.
+    pub fn with_vfs<V: Vfs + 'static>(mut self, vfs: V) -> Self {
+        self.vfs = VfsHandle(Rc::new(vfs));
+        self
+    }
+
+    /// Records `source` statements instead of parsing the included files, see [SourceMode::Lazy].
+    pub fn with_source_mode(mut self, source_mode: SourceMode) -> Self {
+        self.source_mode = source_mode;
+        self
+    }
+
+    pub fn with_shell_policy(mut self, shell_policy: ShellPolicy) -> Self {
+        self.shell_policy = shell_policy;
+        self
+    }
+
+    pub fn with_toolchain(mut self, toolchain: ToolchainProfile) -> Self {
+        self.toolchain = Some(Rc::new(toolchain));
         self
     }
 
@@ -100,8 +172,19 @@ impl KconfigFile {
         copied
     }
 
-    pub fn vars(&self) -> HashMap<String, String> {
-        let mut variables = (*self.global_vars).clone();
+    /// Appends the location of the `source` statement to the include chain.
+    pub fn included_from(mut self, span: Span) -> Self {
+        self.include_chain.push(span);
+        self
+    }
+
+    /// The global variables, recursively-expanded, overridden by the local ones.
+    pub fn vars(&self) -> HashMap<String, Variable> {
+        let mut variables: HashMap<String, Variable> = self
+            .global_vars
+            .iter()
+            .map(|(name, value)| (name.clone(), Variable::recursive(value.as_str())))
+            .collect();
         variables.extend((*self.local_vars).clone());
         variables
     }
@@ -114,8 +197,36 @@ impl KconfigFile {
         self.root_dir.join(&self.file)
     }
 
+    /// Reads and preprocesses the file.
+    /// Errors raised by the preprocessor, `$(error-if)` for instance, are recorded in [KconfigFile::diagnostics] when set,
+    /// otherwise they are returned as [io::ErrorKind::InvalidData].
     pub fn read_to_string(&self) -> io::Result<String> {
-        fs::read_to_string(self.full_path()).map(|content| self.preprocess_content(content))
+        let content = self.vfs.read_to_string(&self.full_path())?;
+        let mut preprocessor = self.preprocessor();
+        let content = preprocessor.preprocess(&content);
+        match &self.diagnostics {
+            Some(diagnostics) => {
+                diagnostics
+                    .borrow_mut()
+                    .extend(preprocessor.diagnostics.into_iter().map(|mut diagnostic| {
+                        diagnostic.include_chain = self.include_chain.clone();
+                        diagnostic
+                    }))
+            }
+            None => {
+                if let Some(error) = preprocessor
+                    .diagnostics
+                    .iter()
+                    .find(|diagnostic| diagnostic.severity == Severity::Error)
+                {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}:{}: {}", error.span.file, error.span.line, error.message),
+                    ));
+                }
+            }
+        }
+        Ok(content)
     }
 
     pub fn set_global_vars<S: AsRef<str>>(&mut self, vars: &[(S, S)]) {
@@ -126,13 +237,13 @@ impl KconfigFile {
         );
     }
 
-    pub fn add_local_var<S: AsRef<str>>(&mut self, key: S, value: S) {
+    pub fn add_local_var<S: AsRef<str>, V: Into<Variable>>(&mut self, key: S, value: V) {
         let mut new_map = (*self.local_vars).clone();
-        new_map.insert(key.as_ref().to_string(), value.as_ref().to_string());
+        new_map.insert(key.as_ref().to_string(), value.into());
         self.local_vars = Rc::new(new_map);
     }
 
-    pub fn add_local_vars(&mut self, new_vars: HashMap<String, String>) {
+    pub fn add_local_vars(&mut self, new_vars: HashMap<String, Variable>) {
         if new_vars.is_empty() {
             return;
         }
@@ -141,17 +252,22 @@ impl KconfigFile {
         self.local_vars = Rc::new(new_map);
     }
 
-    pub fn preprocess_content(&self, content: String) -> String {
-        let variables = self.vars();
-        if variables.is_empty() {
-            return content;
-        }
-        let mut file_copy = content.clone();
-        for (var_name, var_value) in variables {
-            file_copy = file_copy.replace(&format!("$({var_name})"), &var_value);
-            file_copy = file_copy.replace(&format!("${{{var_name}}}"), &var_value);
-        }
+    /// Defines a variable, `operator` is one of `=`, `:=` or `+=`, see [preprocess](crate::preprocess).
+    pub fn assign_local_var(&mut self, name: &str, operator: &str, value: &str) {
+        let variable = self.preprocessor().assigned_value(name, operator, value);
+        self.add_local_var(name, variable);
+    }
 
-        file_copy
+    /// A [Preprocessor] knowing the variables defined so far.
+    pub fn preprocessor(&self) -> Preprocessor {
+        Preprocessor::new(self.vars())
+            .with_file(self.file.display().to_string())
+            .with_shell_policy(self.shell_policy.clone())
+            .with_toolchain(self.toolchain.clone())
+            .with_unexpanded_sources(true)
+    }
+
+    pub fn preprocess_content(&self, content: String) -> String {
+        self.preprocessor().preprocess(&content)
     }
 }
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use crate::kconfig_file::{command_line_variables, parse_variable};
+
+#[test]
+fn test_parse_variable() {
+    assert_eq!(
+        parse_variable("CC=gcc -m64"),
+        Ok(("CC".to_string(), "gcc -m64".to_string()))
+    );
+    assert_eq!(
+        parse_variable("CC"),
+        Err("'CC' is not a KEY=VALUE variable".to_string())
+    );
+}
+
+#[test]
+fn test_command_line_variables() {
+    let variables = vec![("ARCH".to_string(), "x86_64".to_string())];
+    assert_eq!(
+        command_line_variables(Some("x86"), &variables),
+        HashMap::from([
+            ("SRCARCH".to_string(), "x86".to_string()),
+            ("ARCH".to_string(), "x86_64".to_string()),
+        ])
+    );
+    assert!(command_line_variables(None, &[]).is_empty());
+}
@@ -37,8 +37,10 @@ fn test_parse_kconfig() {
                     symbol: "SND_INTEL_NHLT".to_string(),
                     attributes: vec!(Attribute::Type(ConfigType {
                         r#type: Type::Tristate(None),
-                        r#if: None
-                    }))
+                        r#if: None,
+                        span: Default::default()
+                    })),
+                    span: Default::default()
                 }))
             }
         ))
@@ -68,9 +70,12 @@ endchoice
                         symbol: "RAPIDIO_ENUM_BASIC".to_string(),
                         attributes: vec!(Attribute::Type(ConfigType {
                             r#type: Type::Tristate(None),
-                            r#if: None
-                        }))
-                    }))
+                            r#if: None,
+                            span: Default::default()
+                        })),
+                        span: Default::default()
+                    })),
+                    span: Default::default()
                 })),
             }
         ))
@@ -109,6 +114,7 @@ config 64BIT
                                     ))
                                 })
                             )))),
+                            span: Default::default()
                         }),
                         Attribute::Default(DefaultAttribute {
                             expression: Expression::Term(AndExpression::Term(Term::Atom(
@@ -122,9 +128,11 @@ config 64BIT
                                     ))
                                 })
                             ))),
-                            r#if: None
+                            r#if: None,
+                            span: Default::default()
                         })
                     ),
+                    span: Default::default()
                 }))
             }
         ))
@@ -166,7 +174,9 @@ config BOARD_$(BOARD_STRING)
                             parameters: vec![Parameter {
                                 tokens: vec![ExpressionToken::Variable("BOARD".to_string())],
                             }],
-                        })
+                            span: Default::default()
+                        }),
+                        span: Default::default()
                     }),
                     Entry::VariableAssignment(VariableAssignment {
                         identifier: VariableIdentifier::Identifier(
@@ -182,14 +192,18 @@ config BOARD_$(BOARD_STRING)
                                     ExpressionToken::Variable("BOARD_QUALIFIERS".to_string())
                                 ],
                             }],
-                        })
+                            span: Default::default()
+                        }),
+                        span: Default::default()
                     }),
                     Entry::Config(Config {
                         symbol: "BOARD_$(BOARD_STRING)".to_string(),
                         attributes: vec!(Attribute::Type(ConfigType {
                             r#type: Type::Bool(Some("y".to_string())),
-                            r#if: None
-                        }))
+                            r#if: None,
+                            span: Default::default()
+                        })),
+                        span: Default::default()
                     })
                 )
             }
@@ -27,34 +27,97 @@
 //! ```
 
 pub mod attribute;
+pub mod autoconf;
+pub mod cst;
+pub mod cycle;
+pub mod diagnostic;
+pub mod dotconfig;
 pub mod entry;
 pub mod error;
+pub mod export;
+#[cfg(feature = "display")]
+pub mod format;
+#[cfg(feature = "display")]
+pub mod graph;
 pub mod kconfig;
 pub mod kconfig_file;
+#[cfg(feature = "display")]
+pub mod lint;
+pub mod menu_tree;
+pub mod preprocess;
+pub mod resolve;
+pub mod rustcfg;
+pub mod shell;
+pub mod span;
 pub mod string;
 pub mod symbol;
+pub mod symbol_table;
+pub mod symbol_usage;
+pub mod toolchain;
 pub mod tristate;
 pub mod util;
+pub mod vfs;
 
 pub use self::attribute::Attribute;
 pub use self::entry::Entry;
 pub use self::kconfig::{parse_kconfig, Kconfig};
 pub use self::symbol::Symbol;
+pub use self::symbol_table::SymbolTable;
 pub use kconfig_file::KconfigFile;
 use nom_locate::LocatedSpan;
 
 /// [KconfigInput] is a struct gathering a [KconfigFile] and its associated content.
 pub type KconfigInput<'a> = LocatedSpan<&'a str, KconfigFile>;
 
+#[cfg(test)]
+pub mod autoconf_test;
+#[cfg(test)]
+pub mod cst_test;
+#[cfg(test)]
+pub mod cycle_test;
+#[cfg(test)]
+pub mod diagnostic_test;
+#[cfg(test)]
+pub mod dotconfig_test;
+#[cfg(test)]
+pub mod export_test;
+#[cfg(all(test, feature = "display"))]
+pub mod format_test;
+#[cfg(all(test, feature = "display"))]
+pub mod graph_test;
+#[cfg(test)]
+pub mod kconfig_file_test;
 #[cfg(test)]
 pub mod kconfig_test;
 #[cfg(test)]
 pub mod lib_test;
+#[cfg(all(test, feature = "display"))]
+pub mod lint_test;
+#[cfg(test)]
+pub mod menu_tree_test;
 mod number;
 #[cfg(test)]
+pub mod preprocess_test;
+#[cfg(test)]
+pub mod resolve_test;
+#[cfg(test)]
+pub mod rustcfg_test;
+#[cfg(test)]
+pub mod shell_test;
+#[cfg(test)]
+pub mod span_test;
+#[cfg(test)]
+pub mod symbol_table_test;
+#[cfg(test)]
 pub mod symbol_test;
 #[cfg(test)]
+pub mod symbol_usage_test;
+#[cfg(test)]
+pub mod toolchain_test;
+#[cfg(test)]
 pub mod util_test;
+#[cfg(test)]
+pub mod vfs_test;
 
 #[macro_export]
 macro_rules! assert_parsing_eq {
@@ -8,10 +8,15 @@ use crate::{
         r#type::{ConfigType, Type},
         DefaultAttribute, ExpressionToken, FunctionCall, Parameter,
     },
-    entry::{config::Config, r#if::If, MenuConfig, Value, VariableAssignment, VariableIdentifier},
+    dotconfig::DotConfig,
+    entry::{
+        config::Config, r#if::If, MenuConfig, SourceMode, Value, VariableAssignment,
+        VariableIdentifier,
+    },
     kconfig::parse_kconfig,
+    resolve::{resolve, Configuration},
     symbol::Symbol,
-    Attribute, Entry, Kconfig, KconfigFile,
+    Attribute, Entry, Kconfig, KconfigFile, KconfigInput,
 };
 
 #[macro_export]
@@ -39,6 +44,71 @@ macro_rules! assert_parsing_source_eq {
     }};
 }
 
+/// A symbol of each type, and one depending on `PCI`, for the tests of the writers of resolved configurations.
+pub const CONFIGURATION: &str = r#"mainmenu "Linux Kernel Configuration"
+
+config MODULES
+    bool "modules"
+    modules
+
+config USB
+    tristate "usb"
+
+config PCI
+    bool "pci"
+
+config DEBUG
+    bool "debug"
+
+config HZ
+    int "hz"
+    default 250
+
+config PHYS
+    hex "phys"
+    default 0x1000000
+
+config LOCALVERSION
+    string "local version"
+    default "-custom"
+
+config DEPENDENT
+    int "dependent"
+    default 1
+    depends on PCI
+"#;
+
+/// Parses `input`, `source` statements are relative to the current directory.
+pub(crate) fn parse(input: &str) -> Kconfig {
+    parse_kconfig(KconfigInput::new_extra(input, Default::default()))
+        .unwrap()
+        .1
+}
+
+/// Parses `input` and resolves it against the `.config` content `config`.
+pub fn resolve_input(input: &str, config: &str) -> (Kconfig, Configuration) {
+    let kconfig = parse(input);
+    let configuration = resolve(&kconfig, Some(&DotConfig::parse(config)));
+    (kconfig, configuration)
+}
+
+/// The Kconfig files of `benches/linux-6.4.10` and their content, `source` statements are left unresolved.
+pub fn linux_files() -> Vec<(KconfigFile, String)> {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/linux-6.4.10");
+    walkdir::WalkDir::new(&root)
+        .sort_by_file_name()
+        .into_iter()
+        .map(|entry| entry.unwrap().into_path())
+        .filter(|path| path.is_file())
+        .map(|path| {
+            let file = path.strip_prefix(&root).unwrap().to_path_buf();
+            let kconfig_file =
+                KconfigFile::new(root.clone(), file).with_source_mode(SourceMode::Lazy);
+            (kconfig_file, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect()
+}
+
 // 2.6.25/drivers/ide/Kconfig
 #[test]
 fn test_parse_type() {
@@ -60,14 +130,17 @@ endif"#;
                         symbol: "BLK_DEV_IDEDMA_SFF".to_string(),
                         attributes: vec!(Attribute::Type(ConfigType {
                             r#type: Type::Bool(None),
-                            r#if: None
-                        }))
+                            r#if: None,
+                            span: Default::default()
+                        })),
+                        span: Default::default()
                     }),
                     Entry::If(If {
                         condition: Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
                             Symbol::NonConstant("PCI".to_string())
                         )))),
-                        entries: vec!()
+                        entries: vec!(),
+                        span: Default::default()
                     })
                 )
             },
@@ -91,14 +164,17 @@ fn test_parse_config_without_attribute() {
                 entries: vec!(
                     Entry::Config(Config {
                         symbol: "PPC_86xx".to_string(),
-                        attributes: vec!()
+                        attributes: vec!(),
+                        span: Default::default()
                     }),
                     Entry::MenuConfig(MenuConfig {
                         symbol: "PPC_86xx".to_string(),
                         attributes: vec!(Attribute::Type(ConfigType {
                             r#type: Type::Bool(Some("86xx-based boards".to_string())),
-                            r#if: None
-                        }))
+                            r#if: None,
+                            span: Default::default()
+                        })),
+                        span: Default::default()
                     }),
                 )
             },
@@ -129,7 +205,8 @@ menuconfig GCC_PLUGINS
                         attributes: vec!(
                             Attribute::Type(ConfigType {
                                 r#type: Type::String(None),
-                                r#if: None
+                                r#if: None,
+                                span: Default::default()
                             }),
                             Attribute::Default(DefaultAttribute {
                                 expression: Expression::Term(AndExpression::Term(Term::Atom(
@@ -158,19 +235,24 @@ menuconfig GCC_PLUGINS
                                                     ExpressionToken::Variable("CC".to_string())
                                                 ])
                                             )
-                                        })
+                                        }),
+                                        span: Default::default()
                                     }))
                                 ))),
-                                r#if: None
+                                r#if: None,
+                                span: Default::default()
                             })
-                        )
+                        ),
+                        span: Default::default()
                     }),
                     Entry::MenuConfig(MenuConfig {
                         symbol: "GCC_PLUGINS".to_string(),
                         attributes: vec!(Attribute::Type(ConfigType {
                             r#type: Type::Bool(Some("GCC plugins".to_string())),
-                            r#if: None
-                        }))
+                            r#if: None,
+                            span: Default::default()
+                        })),
+                        span: Default::default()
                     }),
                 )
             },
@@ -208,13 +290,16 @@ config AS_WRUSS
                                             ExpressionToken::Variable("comma".to_string()),
                                             ExpressionToken::Literal("(%rbx)".to_string()),
                                         )
-                                    })
+                                    }),
+                                    span: Default::default()
                                 })))
                             ))),
-                            r#if: None
+                            r#if: None,
+                            span: Default::default()
                         },),
-                        Attribute::Help("Supported by binutils".to_string())
-                    )
+                        Attribute::Help("Supported by binutils".to_string(), Default::default())
+                    ),
+                    span: Default::default()
                 }),)
             },
         ))
@@ -252,14 +337,17 @@ endif
                         Entry::VariableAssignment(VariableAssignment {
                             identifier: VariableIdentifier::Identifier("module".to_string()),
                             operator: "=".to_string(),
-                            right: Value::Literal("MCUBOOT_UTIL".to_string())
+                            right: Value::Literal("MCUBOOT_UTIL".to_string()),
+                            span: Default::default()
                         }),
                         Entry::VariableAssignment(VariableAssignment {
                             identifier: VariableIdentifier::Identifier("module-str".to_string()),
                             operator: "=".to_string(),
-                            right: Value::Literal("MCUboot bootutil".to_string())
+                            right: Value::Literal("MCUboot bootutil".to_string()),
+                            span: Default::default()
                         }),
-                    )
+                    ),
+                    span: Default::default()
                 }))
             },
         ))
@@ -0,0 +1,502 @@
+//! Warnings about suspicious constructs of a Kconfig tree.
+//!
+//! Every warning belongs to a [Rule] identified by a name, like clippy lints.
+//! A rule can be allowed, kept as a warning or denied (reported as an error), for the whole tree or for a single file.
+//! Files can also change the level of rules themselves with a comment:
+//!
+//! ```text
+//! # kconfig-lint: allow(missing_help, select_with_dependencies)
+//! ```
+//!
+//! ```
+//! use nom_kconfig::{lint::{Level, Linter, Rule}, parse_kconfig, KconfigInput};
+//!
+//! let input = r#"
+//! config FOO
+//!     bool "foo"
+//!     range 0 10
+//! "#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let linter = Linter::default().with_level(Rule::MissingHelp, Level::Allow);
+//! let lints = linter.lint(&kconfig);
+//! assert_eq!(lints.len(), 1);
+//! assert_eq!(lints[0].rule, Rule::RangeOnBool);
+//! assert_eq!(lints[0].rule.id(), "range_on_bool");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{AndExpression, Atom, DefaultAttribute, Expression, Term},
+    diagnostic::{Diagnostic, Severity},
+    span::Span,
+    symbol::ConstantSymbol,
+    symbol_table::{SymbolRecord, SymbolTable, SymbolType},
+    Kconfig, KconfigFile, Symbol,
+};
+
+/// Prefix of the comments changing the level of rules in a file.
+pub const DIRECTIVE: &str = "kconfig-lint:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "snake_case")
+)]
+pub enum Rule {
+    /// `select` of a symbol that has a prompt or dependencies: the selected symbol may be enabled while its dependencies are not met.
+    SelectWithDependencies,
+    /// A symbol used in an expression, a `select` or an `imply` that is never defined.
+    UndefinedSymbol,
+    /// A constant default that does not match the type of the symbol, like `default "foo"` on a `bool`.
+    DefaultTypeMismatch,
+    /// `range` on a symbol that is not an `int` or a `hex`.
+    RangeOnBool,
+    /// A `default` that can never be used because an unconditional one precedes it.
+    DuplicateDefault,
+    /// A symbol without prompt nor default that is never selected or implied: it can never be set.
+    PromptlessWithoutDefault,
+    /// A symbol with a prompt but no help text.
+    MissingHelp,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 7] = [
+        Rule::SelectWithDependencies,
+        Rule::UndefinedSymbol,
+        Rule::DefaultTypeMismatch,
+        Rule::RangeOnBool,
+        Rule::DuplicateDefault,
+        Rule::PromptlessWithoutDefault,
+        Rule::MissingHelp,
+    ];
+
+    /// The name of the rule, used in directives and printed along with warnings.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::SelectWithDependencies => "select_with_dependencies",
+            Rule::UndefinedSymbol => "undefined_symbol",
+            Rule::DefaultTypeMismatch => "default_type_mismatch",
+            Rule::RangeOnBool => "range_on_bool",
+            Rule::DuplicateDefault => "duplicate_default",
+            Rule::PromptlessWithoutDefault => "promptless_without_default",
+            Rule::MissingHelp => "missing_help",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// What to do with the warnings of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "lowercase")
+)]
+pub enum Level {
+    Allow,
+    Warn,
+    /// Warnings are reported as errors.
+    Deny,
+}
+
+/// A warning of the linter.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Lint {
+    pub rule: Rule,
+    /// [Severity::Warning], or [Severity::Error] if the rule is denied.
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Lint {
+    /// The lint as a diagnostic, the message being prefixed by the rule: `[missing_help] ...`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            severity: self.severity,
+            message: format!("[{}] {}", self.rule, self.message),
+            span: self.span.clone(),
+            include_chain: vec![],
+        }
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        };
+        write!(
+            f,
+            "{}[{}]: {}\n  --> {}",
+            severity, self.rule, self.message, self.span
+        )
+    }
+}
+
+/// The rule set: a level per rule, overridden per file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Linter {
+    levels: HashMap<Rule, Level>,
+    files: HashMap<String, HashMap<Rule, Level>>,
+}
+
+impl Linter {
+    /// Sets the level of a rule for every file. Rules are warnings by default.
+    pub fn with_level(mut self, rule: Rule, level: Level) -> Self {
+        self.levels.insert(rule, level);
+        self
+    }
+
+    /// Sets the level of a rule for a file, as stored in [Kconfig::file].
+    pub fn with_file_level<S: Into<String>>(mut self, file: S, rule: Rule, level: Level) -> Self {
+        self.files
+            .entry(file.into())
+            .or_default()
+            .insert(rule, level);
+        self
+    }
+
+    /// The level of a rule in a file.
+    pub fn level(&self, rule: Rule, file: &str) -> Level {
+        self.files
+            .get(file)
+            .and_then(|levels| levels.get(&rule))
+            .or_else(|| self.levels.get(&rule))
+            .copied()
+            .unwrap_or(Level::Warn)
+    }
+
+    /// Reads the `# kconfig-lint: allow(rule, ...)`, `warn(...)` and `deny(...)` comments of a file.
+    /// `all` stands for every rule. Unknown rules are returned as errors.
+    pub fn read_directives(&mut self, file: &str, content: &str) -> Vec<Diagnostic> {
+        let mut errors = vec![];
+        for (number, line) in content.lines().enumerate() {
+            let Some(directive) = line
+                .trim_start()
+                .strip_prefix('#')
+                .map(str::trim_start)
+                .and_then(|comment| comment.strip_prefix(DIRECTIVE))
+            else {
+                continue;
+            };
+            let span = Span {
+                file: file.to_string(),
+                line: number as u32 + 1,
+                column: 1,
+                ..Default::default()
+            };
+            for group in directive.split(')').map(str::trim) {
+                if group.is_empty() {
+                    continue;
+                }
+                let Some((level, rules)) = group.split_once('(') else {
+                    errors.push(Diagnostic::error(
+                        format!("invalid lint directive `{}`", group),
+                        span.clone(),
+                    ));
+                    continue;
+                };
+                let level = match level.trim_start_matches(',').trim() {
+                    "allow" => Level::Allow,
+                    "warn" => Level::Warn,
+                    "deny" => Level::Deny,
+                    other => {
+                        errors.push(Diagnostic::error(
+                            format!("unknown lint level `{}`", other),
+                            span.clone(),
+                        ));
+                        continue;
+                    }
+                };
+                let levels = self.files.entry(file.to_string()).or_default();
+                for id in rules.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+                    match (id, Rule::from_id(id)) {
+                        ("all", _) => levels.extend(Rule::ALL.map(|rule| (rule, level))),
+                        (_, Some(rule)) => {
+                            levels.insert(rule, level);
+                        }
+                        (_, None) => errors.push(Diagnostic::error(
+                            format!("unknown lint rule `{}`", id),
+                            span.clone(),
+                        )),
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    /// Reads the directives of every file of the tree, through the [Vfs](crate::vfs::Vfs) of `kconfig_file`.
+    /// Files that cannot be read are skipped.
+    pub fn read_directives_from(
+        &mut self,
+        kconfig: &Kconfig,
+        kconfig_file: &KconfigFile,
+    ) -> Vec<Diagnostic> {
+        let files = kconfig.files();
+        let mut errors = vec![];
+        for file in files {
+            if let Ok(content) = kconfig_file
+                .vfs
+                .read_to_string(&kconfig_file.root_dir.join(file))
+            {
+                errors.extend(self.read_directives(file, &content));
+            }
+        }
+        errors
+    }
+
+    /// Runs every rule that is not allowed.
+    pub fn lint(&self, kconfig: &Kconfig) -> Vec<Lint> {
+        self.lint_table(&SymbolTable::new(kconfig))
+    }
+
+    pub fn lint_table(&self, table: &SymbolTable) -> Vec<Lint> {
+        let mut lints = vec![];
+        let mut report = |rule: Rule, message: String, span: &Span| {
+            let severity = match self.level(rule, &span.file) {
+                Level::Allow => return false,
+                Level::Warn => Severity::Warning,
+                Level::Deny => Severity::Error,
+            };
+            lints.push(Lint {
+                rule,
+                severity,
+                message,
+                span: span.clone(),
+            });
+            true
+        };
+
+        let mut undefined = HashSet::new();
+        let mut references =
+            |name: &str, span: &Span, report: &mut dyn FnMut(Rule, String, &Span) -> bool| {
+                if !table.contains(name)
+                    && !undefined.contains(name)
+                    && report(
+                        Rule::UndefinedSymbol,
+                        format!("symbol `{}` is never defined", name),
+                        span,
+                    )
+                {
+                    undefined.insert(name.to_string());
+                }
+            };
+
+        for record in table.symbols() {
+            let Some(definition) = record.definitions.first() else {
+                continue;
+            };
+
+            for d in &record.definitions {
+                for depends_on in &d.depends_on {
+                    for name in depends_on
+                        .expression
+                        .symbols()
+                        .into_iter()
+                        .chain(depends_on.r#if.iter().flat_map(|e| e.symbols()))
+                    {
+                        references(name, &depends_on.span, &mut report);
+                    }
+                }
+                // the dependencies inherited from the enclosing blocks
+                for name in d.dependencies.iter().flat_map(|e| e.symbols()) {
+                    references(name, &d.span, &mut report);
+                }
+            }
+            for select in &record.selects {
+                let select = &select.value;
+                references(&select.symbol, &select.span, &mut report);
+                for name in select.r#if.iter().flat_map(|e| e.symbols()) {
+                    references(name, &select.span, &mut report);
+                }
+                let Some(target) = table.get(&select.symbol) else {
+                    continue;
+                };
+                let reason = match (target.prompts.is_empty(), target.dependencies()) {
+                    (false, _) => "a prompt",
+                    (true, Some(_)) => "dependencies",
+                    (true, None) => continue,
+                };
+                report(
+                    Rule::SelectWithDependencies,
+                    format!(
+                        "`{}` selects `{}` which has {}",
+                        record.name, select.symbol, reason
+                    ),
+                    &select.span,
+                );
+            }
+            for imply in &record.implies {
+                let imply = &imply.value;
+                if let Symbol::NonConstant(name) = &imply.symbol {
+                    references(name, &imply.span, &mut report);
+                }
+                for name in imply.r#if.iter().flat_map(|e| e.symbols()) {
+                    references(name, &imply.span, &mut report);
+                }
+            }
+            for default in &record.defaults {
+                let default = &default.value;
+                for name in default
+                    .expression
+                    .symbols()
+                    .into_iter()
+                    .chain(default.r#if.iter().flat_map(|e| e.symbols()))
+                {
+                    references(name, &default.span, &mut report);
+                }
+            }
+            for range in &record.ranges {
+                for name in range.value.r#if.iter().flat_map(|e| e.symbols()) {
+                    references(name, &range.value.span, &mut report);
+                }
+            }
+
+            let r#type = record.r#type();
+            if let Some(r#type) = r#type {
+                for default in &record.defaults {
+                    if let Some(constant) = type_mismatch(&default.value, r#type) {
+                        report(
+                            Rule::DefaultTypeMismatch,
+                            format!(
+                                "default value `{}` of `{}` is not a valid {}",
+                                constant, record.name, r#type
+                            ),
+                            &default.value.span,
+                        );
+                    }
+                }
+            }
+            if let Some(r#type @ (SymbolType::Bool | SymbolType::Tristate | SymbolType::String)) =
+                r#type
+            {
+                for range in &record.ranges {
+                    report(
+                        Rule::RangeOnBool,
+                        format!(
+                            "`range` has no effect on `{}`, a {} symbol",
+                            record.name, r#type
+                        ),
+                        &range.value.span,
+                    );
+                }
+            }
+
+            for (i, default) in record.defaults.iter().enumerate() {
+                let shadowed = record.defaults[..i]
+                    .iter()
+                    .any(|d| d.definition == default.definition && d.value.r#if.is_none());
+                if shadowed {
+                    report(
+                        Rule::DuplicateDefault,
+                        format!(
+                            "this default of `{}` is never used, a previous default has no `if`",
+                            record.name
+                        ),
+                        &default.value.span,
+                    );
+                }
+            }
+
+            if is_unsettable(record) {
+                report(
+                    Rule::PromptlessWithoutDefault,
+                    format!(
+                        "`{}` has no prompt and no default and is never selected",
+                        record.name
+                    ),
+                    &definition.span,
+                );
+            }
+
+            if let Some(prompt) = record.prompts.first() {
+                if record.help.is_empty() {
+                    let span = &record.definitions[prompt.definition].span;
+                    report(
+                        Rule::MissingHelp,
+                        format!("`{}` has a prompt but no help text", record.name),
+                        span,
+                    );
+                }
+            }
+        }
+
+        for choice in table.choices() {
+            for definition in &choice.definitions {
+                for name in definition.dependencies.iter().flat_map(|e| e.symbols()) {
+                    references(name, &definition.span, &mut report);
+                }
+            }
+        }
+        lints
+    }
+}
+
+/// The constant of a default that cannot be a value of the type.
+fn type_mismatch(default: &DefaultAttribute, r#type: SymbolType) -> Option<String> {
+    let Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(Symbol::Constant(constant))))) =
+        &default.expression
+    else {
+        return None;
+    };
+    let valid = match (r#type, constant) {
+        (SymbolType::Bool, ConstantSymbol::Boolean(_)) => true,
+        (SymbolType::Bool, ConstantSymbol::Tristate(_)) => true,
+        (SymbolType::Tristate, ConstantSymbol::Boolean(_) | ConstantSymbol::Tristate(_)) => true,
+        // "y", "m" and "n" are the same symbols as y, m and n
+        (SymbolType::Bool | SymbolType::Tristate, ConstantSymbol::String(s)) => {
+            matches!(s.as_str(), "y" | "m" | "n")
+        }
+        (SymbolType::Bool | SymbolType::Tristate, _) => false,
+        (SymbolType::Int, ConstantSymbol::Integer(_)) => true,
+        (SymbolType::Int, ConstantSymbol::String(s)) => s.parse::<i64>().is_ok(),
+        (SymbolType::Hex, ConstantSymbol::Hex(_) | ConstantSymbol::Integer(_)) => true,
+        (SymbolType::Hex, ConstantSymbol::String(s)) => {
+            let digits = s
+                .strip_prefix("0x")
+                .or_else(|| s.strip_prefix("0X"))
+                .unwrap_or(s);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        (SymbolType::Int | SymbolType::Hex, _) => false,
+        (SymbolType::String, _) => true,
+    };
+    match valid {
+        true => None,
+        false => Some(default.expression.to_string()),
+    }
+}
+
+/// A symbol without prompt nor default can only get a value from `select` and `imply`.
+fn is_unsettable(record: &SymbolRecord) -> bool {
+    record.prompts.is_empty()
+        && record.defaults.is_empty()
+        && record.selected_by.is_empty()
+        && record.implied_by.is_empty()
+        && record.choice.is_none()
+        && !record.modules
+}
@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+
+use crate::{
+    diagnostic::Severity,
+    kconfig::parse_kconfig,
+    lib_test::parse,
+    lint::{Level, Lint, Linter, Rule},
+    vfs::MemoryFs,
+    KconfigFile, KconfigInput,
+};
+
+fn lints(linter: &Linter, input: &str) -> Vec<(Rule, u32, String)> {
+    linter
+        .lint(&parse(input))
+        .into_iter()
+        .map(|l| (l.rule, l.span.line, l.message))
+        .collect()
+}
+
+fn only(rule: Rule) -> Linter {
+    Rule::ALL
+        .into_iter()
+        .filter(|r| *r != rule)
+        .fold(Linter::default(), |linter, r| {
+            linter.with_level(r, Level::Allow)
+        })
+}
+
+#[test]
+fn test_lint_select_with_dependencies() {
+    let input = r#"config A
+    bool
+    select B
+    select C
+    select D
+
+config B
+    bool "b"
+
+config C
+    bool
+    depends on PCI
+
+config D
+    bool
+
+config PCI
+    def_bool y
+"#;
+    assert_eq!(
+        lints(&only(Rule::SelectWithDependencies), input),
+        vec![
+            (
+                Rule::SelectWithDependencies,
+                3,
+                "`A` selects `B` which has a prompt".to_string()
+            ),
+            (
+                Rule::SelectWithDependencies,
+                4,
+                "`A` selects `C` which has dependencies".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_undefined_symbol() {
+    let input = r#"if NET && !EMBEDDED
+config A
+    bool
+    depends on PCI || USB
+    select MISSING
+    default OTHER if USB
+
+config B
+    bool
+    depends on USB
+endif
+
+config NET
+    def_bool y
+"#;
+    assert_eq!(
+        lints(&only(Rule::UndefinedSymbol), input),
+        vec![
+            (
+                Rule::UndefinedSymbol,
+                4,
+                "symbol `PCI` is never defined".to_string()
+            ),
+            (
+                Rule::UndefinedSymbol,
+                4,
+                "symbol `USB` is never defined".to_string()
+            ),
+            (
+                Rule::UndefinedSymbol,
+                2,
+                "symbol `EMBEDDED` is never defined".to_string()
+            ),
+            (
+                Rule::UndefinedSymbol,
+                5,
+                "symbol `MISSING` is never defined".to_string()
+            ),
+            (
+                Rule::UndefinedSymbol,
+                6,
+                "symbol `OTHER` is never defined".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_types_and_defaults() {
+    let input = r#"config A
+    bool "a"
+    default "foo"
+    range 0 1
+
+config B
+    int "b"
+    default y if A
+    default 3
+    default 4
+
+config C
+    hex "c"
+    default 0x10
+    default "12"
+
+config D
+    tristate "d"
+    default "m" if A
+    default "y"
+"#;
+    let linter = Linter::default()
+        .with_level(Rule::MissingHelp, Level::Allow)
+        .with_level(Rule::DuplicateDefault, Level::Deny);
+    let lints = linter.lint(&parse(input));
+    let summary: Vec<(Rule, Severity, u32)> = lints
+        .iter()
+        .map(|l| (l.rule, l.severity, l.span.line))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Rule::DefaultTypeMismatch, Severity::Warning, 3),
+            (Rule::RangeOnBool, Severity::Warning, 4),
+            (Rule::DefaultTypeMismatch, Severity::Warning, 8),
+            (Rule::DuplicateDefault, Severity::Error, 10),
+            (Rule::DuplicateDefault, Severity::Error, 15),
+        ]
+    );
+    assert_eq!(
+        lints[0].message,
+        "default value `\"foo\"` of `A` is not a valid bool"
+    );
+    assert_eq!(
+        lints[3].to_diagnostic().message,
+        "[duplicate_default] this default of `B` is never used, a previous default has no `if`"
+    );
+}
+
+#[test]
+fn test_lint_promptless_and_help() {
+    let input = r#"config A
+    bool
+
+config B
+    bool "b"
+    help
+      Some help.
+
+config C
+    tristate "c"
+    select D
+
+config D
+    tristate
+"#;
+    let lints: Vec<Lint> = Linter::default().lint(&parse(input));
+    assert_eq!(
+        lints.iter().map(|l| l.to_string()).collect::<Vec<_>>(),
+        vec![
+            "warning[promptless_without_default]: `A` has no prompt and no default and is never selected\n  --> :1:1",
+            "warning[missing_help]: `C` has a prompt but no help text\n  --> :9:1",
+        ]
+    );
+}
+
+#[test]
+fn test_lint_levels_per_file() {
+    let mut fs = MemoryFs::new();
+    fs.insert(
+        "/kernel/Kconfig",
+        "config A\n    bool \"a\"\n\nsource \"drivers/Kconfig\"\n",
+    );
+    fs.insert(
+        "/kernel/drivers/Kconfig",
+        "# kconfig-lint: allow(missing_help), deny(undefined_symbol, unknown)\nconfig B\n    bool \"b\"\n    depends on PCI\n",
+    );
+    let kconfig_file =
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig")).with_vfs(fs);
+    let content = kconfig_file.read_to_string().unwrap();
+    let (_, kconfig) =
+        parse_kconfig(KconfigInput::new_extra(&content, kconfig_file.clone())).unwrap();
+
+    let mut linter = Linter::default();
+    let errors = linter.read_directives_from(&kconfig, &kconfig_file);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unknown lint rule `unknown`");
+    assert_eq!(errors[0].span.file, "drivers/Kconfig");
+    assert_eq!(
+        linter.level(Rule::MissingHelp, "drivers/Kconfig"),
+        Level::Allow
+    );
+    assert_eq!(linter.level(Rule::MissingHelp, "Kconfig"), Level::Warn);
+
+    let lints: Vec<(Rule, Severity, String)> = linter
+        .lint(&kconfig)
+        .into_iter()
+        .map(|l| (l.rule, l.severity, l.span.file))
+        .collect();
+    assert_eq!(
+        lints,
+        vec![
+            (Rule::MissingHelp, Severity::Warning, "Kconfig".to_string()),
+            (
+                Rule::UndefinedSymbol,
+                Severity::Error,
+                "drivers/Kconfig".to_string()
+            ),
+        ]
+    );
+
+    let linter = linter.with_file_level("Kconfig", Rule::MissingHelp, Level::Allow);
+    assert_eq!(linter.lint(&kconfig).len(), 1);
+}
@@ -0,0 +1,350 @@
+//! The menu tree displayed by configuration front-ends like `menuconfig`.
+//!
+//! A [Kconfig] is a list of entries, the menu tree is what the user navigates:
+//! - `menu`, `menuconfig` and `choice` are nodes with children, `config` and `comment` are leaves,
+//! - `if` blocks and sourced files are merged into the enclosing menu, their conditions are added to the dependencies of their entries,
+//! - like `menu_finalize()` of the C implementation, the entries following a `config` or `menuconfig` that depend on its symbol
+//!   become its children: they are displayed in the submenu of a `menuconfig`, and indented below a `config`.
+//!
+//! ```
+//! use nom_kconfig::{menu_tree::{MenuNodeKind, MenuTree}, parse_kconfig, KconfigInput};
+//!
+//! let input = r#"
+//! menuconfig NETDEVICES
+//!     bool "Network device support"
+//! if NETDEVICES
+//! config DUMMY
+//!     tristate "Dummy net driver support"
+//! endif
+//! "#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let tree = MenuTree::new(&kconfig);
+//! let netdevices = &tree.root.children[0];
+//! assert_eq!(netdevices.kind, MenuNodeKind::MenuConfig("NETDEVICES".to_string()));
+//! assert_eq!(netdevices.children[0].prompt.as_deref(), Some("Dummy net driver support"));
+//! ```
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{
+        depends_on::DependsOn,
+        expression::{CompareOperand, CompareOperator},
+        AndExpression, Atom, Expression, Prompt, Term,
+    },
+    entry::{Choice, Config},
+    span::Span,
+    symbol::ConstantSymbol,
+    symbol_table::{and_depends_on, split_type},
+    tristate::Tristate,
+    Attribute, Entry, Kconfig, Symbol,
+};
+
+/// The prompt of the root menu when there is no `mainmenu`.
+pub const DEFAULT_MAIN_MENU: &str = "Main menu";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum MenuNodeKind {
+    /// A `menu` block, or the root of the tree.
+    Menu,
+    Config(String),
+    MenuConfig(String),
+    /// A `choice` block, the name is only known with the `named-choice` feature.
+    Choice(Option<String>),
+    Comment,
+}
+
+/// An entry of the menu tree.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct MenuNode {
+    pub kind: MenuNodeKind,
+    /// The text displayed to the user, a node without prompt is never displayed.
+    pub prompt: Option<String>,
+    /// When the prompt is displayed: the dependencies, the `if` of the prompt and the `visible if` of the enclosing menus.
+    /// `None` when the prompt is always displayed.
+    pub visibility: Option<Expression>,
+    /// `depends on` of the entry and those inherited from the enclosing menus, `if` blocks and choices.
+    pub dependencies: Option<Expression>,
+    pub help: Option<String>,
+    pub span: Span,
+    pub children: Vec<MenuNode>,
+}
+
+impl MenuNode {
+    fn new(kind: MenuNodeKind, span: &Span) -> Self {
+        Self {
+            kind,
+            prompt: None,
+            visibility: None,
+            dependencies: None,
+            help: None,
+            span: span.clone(),
+            children: vec![],
+        }
+    }
+
+    /// The symbol of a `config` or `menuconfig` node.
+    pub fn symbol(&self) -> Option<&str> {
+        match &self.kind {
+            MenuNodeKind::Config(symbol) | MenuNodeKind::MenuConfig(symbol) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    /// Whether the children are displayed in a submenu rather than below the node.
+    pub fn is_menu(&self) -> bool {
+        matches!(
+            self.kind,
+            MenuNodeKind::Menu | MenuNodeKind::MenuConfig(_) | MenuNodeKind::Choice(_)
+        )
+    }
+
+    fn collect<'a>(&'a self, nodes: &mut Vec<&'a MenuNode>) {
+        nodes.push(self);
+        for child in &self.children {
+            child.collect(nodes);
+        }
+    }
+}
+
+/// The menu tree of a [Kconfig].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct MenuTree {
+    /// A [MenuNodeKind::Menu] node prompting the `mainmenu`.
+    pub root: MenuNode,
+}
+
+impl MenuTree {
+    pub fn new(kconfig: &Kconfig) -> Self {
+        let mut root = MenuNode::new(MenuNodeKind::Menu, &Span::default());
+        root.prompt = Some(kconfig.main_menu().unwrap_or(DEFAULT_MAIN_MENU).to_string());
+        visit_entries(&kconfig.entries, &None, &None, &mut root.children);
+        root.children = nest(std::mem::take(&mut root.children));
+        Self { root }
+    }
+
+    /// Every node of the tree, depth first, the root included.
+    pub fn nodes(&self) -> Vec<&MenuNode> {
+        let mut nodes = vec![];
+        self.root.collect(&mut nodes);
+        nodes
+    }
+
+    /// The nodes of a symbol, one per definition.
+    pub fn find(&self, symbol: &str) -> Vec<&MenuNode> {
+        self.nodes()
+            .into_iter()
+            .filter(|node| node.symbol() == Some(symbol))
+            .collect()
+    }
+}
+
+impl From<&Kconfig> for MenuTree {
+    fn from(kconfig: &Kconfig) -> Self {
+        Self::new(kconfig)
+    }
+}
+
+/// `dependencies` are inherited from menus, `if` blocks and choices, `visible` from the `visible if` of menus.
+fn visit_entries(
+    entries: &[Entry],
+    dependencies: &Option<Expression>,
+    visible: &Option<Expression>,
+    nodes: &mut Vec<MenuNode>,
+) {
+    for entry in entries {
+        match entry {
+            Entry::Config(config) => nodes.push(config_node(
+                config,
+                MenuNodeKind::Config(config.symbol.clone()),
+                dependencies,
+                visible,
+            )),
+            Entry::MenuConfig(config) => nodes.push(config_node(
+                config,
+                MenuNodeKind::MenuConfig(config.symbol.clone()),
+                dependencies,
+                visible,
+            )),
+            Entry::Choice(choice) => nodes.push(choice_node(choice, dependencies, visible)),
+            Entry::Menu(menu) => {
+                let mut node = MenuNode::new(MenuNodeKind::Menu, &menu.span);
+                node.prompt = Some(menu.prompt.clone());
+                node.dependencies = and_depends_on(dependencies.clone(), &menu.depends_on);
+                let menu_visible = Expression::and_opt(
+                    visible.clone(),
+                    menu.visible.as_ref().and_then(|v| v.r#if.clone()),
+                );
+                node.visibility =
+                    Expression::and_opt(node.dependencies.clone(), menu_visible.clone());
+                visit_entries(
+                    &menu.entries,
+                    &node.dependencies,
+                    &menu_visible,
+                    &mut node.children,
+                );
+                nodes.push(node);
+            }
+            Entry::Comment(comment) => {
+                let mut node = MenuNode::new(MenuNodeKind::Comment, &comment.span);
+                node.prompt = Some(comment.prompt.clone());
+                node.dependencies = and_depends_on(dependencies.clone(), &comment.dependencies);
+                node.visibility = Expression::and_opt(node.dependencies.clone(), visible.clone());
+                nodes.push(node);
+            }
+            Entry::If(r#if) => {
+                let dependencies =
+                    Expression::and_opt(dependencies.clone(), Some(r#if.condition.clone()));
+                visit_entries(&r#if.entries, &dependencies, visible, nodes)
+            }
+            Entry::Source(source) => source
+                .kconfigs
+                .iter()
+                .for_each(|k| visit_entries(&k.entries, dependencies, visible, nodes)),
+            #[cfg(feature = "kconfiglib")]
+            Entry::OSource(source) | Entry::RSource(source) | Entry::OrSource(source) => source
+                .kconfigs
+                .iter()
+                .for_each(|k| visit_entries(&k.entries, dependencies, visible, nodes)),
+            _ => (),
+        }
+    }
+}
+
+fn config_node(
+    config: &Config,
+    kind: MenuNodeKind,
+    dependencies: &Option<Expression>,
+    visible: &Option<Expression>,
+) -> MenuNode {
+    let mut node = MenuNode::new(kind, &config.span);
+    node.dependencies = and_depends_on(dependencies.clone(), &depends_on(&config.attributes));
+    set_prompt_and_help(&mut node, &config.attributes, visible);
+    node
+}
+
+fn choice_node(
+    choice: &Choice,
+    dependencies: &Option<Expression>,
+    visible: &Option<Expression>,
+) -> MenuNode {
+    #[cfg(feature = "named-choice")]
+    let name = choice.name.clone();
+    #[cfg(not(feature = "named-choice"))]
+    let name: Option<String> = None;
+
+    let mut node = MenuNode::new(MenuNodeKind::Choice(name), &choice.span);
+    node.dependencies = and_depends_on(dependencies.clone(), &depends_on(&choice.options));
+    set_prompt_and_help(&mut node, &choice.options, visible);
+    visit_entries(
+        &choice.entries,
+        &node.dependencies,
+        visible,
+        &mut node.children,
+    );
+    node
+}
+
+fn depends_on(attributes: &[Attribute]) -> Vec<DependsOn> {
+    attributes
+        .iter()
+        .filter_map(|a| match a {
+            Attribute::DependsOn(d) => Some(d.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The first prompt and the first help text of the entry.
+fn set_prompt_and_help(
+    node: &mut MenuNode,
+    attributes: &[Attribute],
+    visible: &Option<Expression>,
+) {
+    let prompt: Option<Prompt> = attributes.iter().find_map(|a| match a {
+        Attribute::Type(config_type) => split_type(config_type).1,
+        Attribute::Prompt(prompt) => Some(prompt.clone()),
+        _ => None,
+    });
+    node.help = attributes.iter().find_map(|a| match a {
+        Attribute::Help(help, _) => Some(help.clone()),
+        _ => None,
+    });
+    if let Some(prompt) = prompt {
+        node.visibility = Expression::and_opt(
+            Expression::and_opt(node.dependencies.clone(), prompt.r#if),
+            visible.clone(),
+        );
+        node.prompt = Some(prompt.prompt);
+    }
+}
+
+/// Moves the nodes following a symbol and depending on it under that symbol, recursively.
+fn nest(nodes: Vec<MenuNode>) -> Vec<MenuNode> {
+    let mut result = vec![];
+    let mut nodes = nodes.into_iter().peekable();
+    while let Some(mut node) = nodes.next() {
+        if let Some(symbol) = node.symbol().map(str::to_string) {
+            while let Some(next) = nodes.peek() {
+                let dependencies = match next.prompt {
+                    Some(_) => &next.visibility,
+                    None => &next.dependencies,
+                };
+                match dependencies {
+                    Some(dependencies) if depends_on_symbol(dependencies, &symbol) => {
+                        node.children.extend(nodes.next())
+                    }
+                    _ => break,
+                }
+            }
+        }
+        node.children = nest(std::mem::take(&mut node.children));
+        result.push(node);
+    }
+    result
+}
+
+/// Whether `expression` is `SYMBOL`, `SYMBOL=y`, `SYMBOL=m` or `SYMBOL!=n`, or a `&&` of terms including one of them.
+fn depends_on_symbol(expression: &Expression, symbol: &str) -> bool {
+    let Expression::Term(and) = expression else {
+        return false;
+    };
+    let terms = match and {
+        AndExpression::Term(term) => std::slice::from_ref(term),
+        AndExpression::Expression(terms) => terms.as_slice(),
+    };
+    terms.iter().any(|term| match term {
+        Term::Atom(Atom::Symbol(Symbol::NonConstant(name))) => name == symbol,
+        Term::Atom(Atom::Compare(compare)) => {
+            let CompareOperand::Symbol(Symbol::NonConstant(name)) = &compare.left else {
+                return false;
+            };
+            let CompareOperand::Symbol(Symbol::Constant(value)) = &compare.right else {
+                return false;
+            };
+            let value = match value {
+                ConstantSymbol::Boolean(true) => Tristate::Yes,
+                ConstantSymbol::Boolean(false) => Tristate::No,
+                ConstantSymbol::Tristate(tristate) => *tristate,
+                _ => return false,
+            };
+            name == symbol
+                && match compare.operator {
+                    CompareOperator::Equal => value != Tristate::No,
+                    CompareOperator::NotEqual => value == Tristate::No,
+                    _ => false,
+                }
+        }
+        Term::Atom(Atom::Parenthesis(expression)) => depends_on_symbol(expression, symbol),
+        _ => false,
+    })
+}
@@ -0,0 +1,159 @@
+use crate::{
+    lib_test::parse,
+    menu_tree::{MenuNode, MenuNodeKind, MenuTree, DEFAULT_MAIN_MENU},
+};
+
+fn tree(input: &str) -> MenuTree {
+    let kconfig = parse(input);
+    MenuTree::new(&kconfig)
+}
+
+fn prompts(nodes: &[MenuNode]) -> Vec<&str> {
+    nodes.iter().filter_map(|n| n.prompt.as_deref()).collect()
+}
+
+#[test]
+fn test_menu_tree_menus_and_comments() {
+    let tree = tree(
+        r#"mainmenu "Linux Kernel Configuration"
+
+menu "General setup"
+    depends on EXPERT
+    visible if BROKEN
+
+config LOCALVERSION
+    string "Local version"
+    help
+      Appended to the kernel release.
+
+comment "Compiler: gcc"
+    depends on CC_IS_GCC
+
+endmenu
+
+config HIDDEN
+    bool
+"#,
+    );
+    assert_eq!(
+        tree.root.prompt.as_deref(),
+        Some("Linux Kernel Configuration")
+    );
+    assert_eq!(prompts(&tree.root.children), vec!["General setup"]);
+    let menu = &tree.root.children[0];
+    assert!(menu.is_menu());
+    assert_eq!(
+        menu.visibility.as_ref().unwrap().symbols(),
+        vec!["EXPERT", "BROKEN"]
+    );
+    assert_eq!(
+        prompts(&menu.children),
+        vec!["Local version", "Compiler: gcc"]
+    );
+
+    let localversion = &tree.find("LOCALVERSION")[0];
+    assert_eq!(
+        localversion.dependencies.as_ref().unwrap().symbols(),
+        vec!["EXPERT"]
+    );
+    assert_eq!(
+        localversion.visibility.as_ref().unwrap().symbols(),
+        vec!["EXPERT", "BROKEN"]
+    );
+    assert_eq!(
+        localversion.help.as_deref(),
+        Some("Appended to the kernel release.")
+    );
+    assert_eq!(menu.children[1].kind, MenuNodeKind::Comment);
+    assert_eq!(
+        menu.children[1].dependencies.as_ref().unwrap().symbols(),
+        vec!["EXPERT", "CC_IS_GCC"]
+    );
+
+    let hidden = &tree.find("HIDDEN")[0];
+    assert_eq!(hidden.prompt, None);
+    assert_eq!(hidden.dependencies, None);
+}
+
+#[test]
+fn test_menu_tree_implicit_submenus() {
+    let tree = tree(
+        r#"
+menuconfig NETDEVICES
+    bool "Network device support"
+
+if NETDEVICES
+
+config DUMMY
+    tristate "Dummy net driver support"
+
+config DUMMY_DEBUG
+    bool "Debug"
+    depends on DUMMY=y && DEBUG_KERNEL
+
+config MII
+    tristate "MII"
+
+endif
+
+config VLAN
+    bool "VLAN" if NETDEVICES != n
+
+config UNRELATED
+    bool "unrelated"
+    depends on !NETDEVICES
+"#,
+    );
+    assert_eq!(tree.root.prompt.as_deref(), Some(DEFAULT_MAIN_MENU));
+    assert_eq!(
+        prompts(&tree.root.children),
+        vec!["Network device support", "unrelated"]
+    );
+    let netdevices = &tree.root.children[0];
+    assert!(netdevices.is_menu());
+    assert_eq!(
+        prompts(&netdevices.children),
+        vec!["Dummy net driver support", "MII", "VLAN"]
+    );
+    let dummy = &netdevices.children[0];
+    assert!(!dummy.is_menu());
+    assert_eq!(prompts(&dummy.children), vec!["Debug"]);
+    assert_eq!(
+        tree.find("VLAN")[0].visibility.as_ref().unwrap().symbols(),
+        vec!["NETDEVICES"]
+    );
+}
+
+#[test]
+fn test_menu_tree_choice() {
+    let tree = tree(
+        r#"
+choice
+    prompt "Preemption Model"
+    depends on SMP
+    help
+      Preemption.
+
+config PREEMPT_NONE
+    bool "No Forced Preemption"
+
+config PREEMPT
+    bool "Preemptible Kernel"
+
+endchoice
+"#,
+    );
+    let choice = &tree.root.children[0];
+    assert!(matches!(choice.kind, MenuNodeKind::Choice(_)));
+    assert_eq!(choice.prompt.as_deref(), Some("Preemption Model"));
+    assert_eq!(choice.help.as_deref(), Some("Preemption."));
+    assert_eq!(
+        prompts(&choice.children),
+        vec!["No Forced Preemption", "Preemptible Kernel"]
+    );
+    assert_eq!(
+        choice.children[1].dependencies.as_ref().unwrap().symbols(),
+        vec!["SMP"]
+    );
+    assert_eq!(tree.nodes().len(), 4);
+}
@@ -0,0 +1,589 @@
+//! The Kconfig [macro language](https://www.kernel.org/doc/html/next/kbuild/kconfig-macro-language.html).
+//!
+//! Files are preprocessed when they are read: variable references are expanded line after line, in the order
+//! the variables are defined. Like in the kernel, help texts and comments are left untouched.
+//!
+//! - `A = value` defines a recursively-expanded variable: `value` is expanded every time `$(A)` is used.
+//! - `A := value` defines a simply-expanded variable: `value` is expanded once, when `A` is defined.
+//! - `A += value` appends ` value` to `A`, `value` is expanded right away if `A` is simply-expanded.
+//!   `A` keeps its [Flavor], it is recursively-expanded if it was not defined.
+//!
+//! A variable can be called as a function, `$(1)`, `$(2)`... are replaced by the arguments:
+//! `$(greeting,world)` with `greeting = hello $(1)` expands to `hello world`.
+//!
+//! The built-in functions are:
+//! - `$(if,condition,then[,else])`: `then` if the condition expands to a non-empty string, `else` otherwise.
+//! - `$(filter-out,words,text)`: the words of `text` not in `words`.
+//! - `$(info,text)`, `$(warning-if,condition,text)` and `$(error-if,condition,text)`: report a [Diagnostic]
+//!   (when the condition is `y`), see [Preprocessor::diagnostics].
+//! - `$(filename)` and `$(lineno)`: the location being preprocessed.
+//! - `$(shell,command)`: the output of the command, depending on the [ShellPolicy].
+//!
+//! The toolchain probes of `scripts/Kconfig.include` can be answered by a [ToolchainProfile].
+//!
+//! References to undefined variables are kept as is, so that the parser can still report them.
+//! With [Preprocessor::with_unexpanded_sources], the paths of `source` statements are kept as written,
+//! as [Source::path](crate::entry::Source::path) records them. [KconfigFile::preprocessor](crate::KconfigFile::preprocessor)
+//! enables it, the paths are expanded when the statements are parsed.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use nom_kconfig::preprocess::Preprocessor;
+//!
+//! let mut preprocessor = Preprocessor::new(HashMap::from([("SRCARCH".to_string(), "x86".to_string())]));
+//! let content = preprocessor.preprocess("\
+//! ARCH_DIR := arch/$(SRCARCH)
+//! source \"$(ARCH_DIR)/Kconfig\"
+//! ");
+//! assert_eq!(content, "ARCH_DIR := arch/$(SRCARCH)\nsource \"arch/x86/Kconfig\"\n");
+//! ```
+
+use std::{collections::HashMap, rc::Rc};
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    shell::ShellPolicy,
+    span::Span,
+    toolchain::ToolchainProfile,
+};
+
+/// Recursive variables referencing each other are expanded up to this depth.
+const MAX_DEPTH: usize = 64;
+
+/// How the value of a variable is expanded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum Flavor {
+    /// Defined with `=`, the value is stored unexpanded and expanded every time the variable is used.
+    #[default]
+    Recursive,
+    /// Defined with `:=`, the value is expanded once, when the variable is defined.
+    Simple,
+}
+
+/// A variable of the macro language.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Variable {
+    pub value: String,
+    pub flavor: Flavor,
+}
+
+impl Variable {
+    pub fn recursive<S: Into<String>>(value: S) -> Self {
+        Self {
+            value: value.into(),
+            flavor: Flavor::Recursive,
+        }
+    }
+
+    pub fn simple<S: Into<String>>(value: S) -> Self {
+        Self {
+            value: value.into(),
+            flavor: Flavor::Simple,
+        }
+    }
+}
+
+/// Variables given without flavor, on the command line for instance, are recursively-expanded.
+impl From<String> for Variable {
+    fn from(value: String) -> Self {
+        Self::recursive(value)
+    }
+}
+
+impl From<&str> for Variable {
+    fn from(value: &str) -> Self {
+        Self::recursive(value)
+    }
+}
+
+/// Expands the macros of Kconfig files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preprocessor {
+    /// The variables defined so far.
+    pub variables: HashMap<String, Variable>,
+    /// The file being preprocessed, returned by `$(filename)`.
+    pub file: String,
+    /// The line being preprocessed, returned by `$(lineno)`.
+    pub line: Span,
+    /// Messages of `$(info)`, `$(warning-if)` and `$(error-if)`, as well as invalid function calls.
+    pub diagnostics: Vec<Diagnostic>,
+    /// How `$(shell,...)` is expanded.
+    pub shell: ShellPolicy,
+    /// Answers the toolchain probes, see [toolchain](crate::toolchain).
+    pub toolchain: Option<Rc<ToolchainProfile>>,
+    /// `source` statements are left unexpanded.
+    pub unexpanded_sources: bool,
+}
+
+/// Where a line is relative to a help text. Help texts end at the first line less indented than their first line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Help {
+    Outside,
+    /// After the `help` keyword, before the first line of text.
+    Start,
+    /// In the help text, indented with at least the given number of columns.
+    Text(usize),
+}
+
+impl Help {
+    /// The state at `line`, the one after the `help` keyword itself is [Help::Start].
+    pub(crate) fn next(self, line: &str) -> Self {
+        let is_blank = line.trim().is_empty();
+        match self {
+            Help::Start if is_blank => Help::Start,
+            Help::Start => match indentation(line) {
+                0 => Help::Outside,
+                indent => Help::Text(indent),
+            },
+            Help::Text(indent) if is_blank || indentation(line) >= indent => Help::Text(indent),
+            _ => Help::Outside,
+        }
+    }
+}
+
+impl Preprocessor {
+    pub fn new<V: Into<Variable>>(variables: HashMap<String, V>) -> Self {
+        Self {
+            variables: variables
+                .into_iter()
+                .map(|(name, variable)| (name, variable.into()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the file returned by `$(filename)`.
+    pub fn with_file<S: Into<String>>(mut self, file: S) -> Self {
+        self.file = file.into();
+        self
+    }
+
+    pub fn with_shell_policy(mut self, shell: ShellPolicy) -> Self {
+        self.shell = shell;
+        self
+    }
+
+    pub fn with_toolchain(mut self, toolchain: Option<Rc<ToolchainProfile>>) -> Self {
+        self.toolchain = toolchain;
+        self
+    }
+
+    /// Leaves `source`, `osource`, `rsource` and `orsource` statements unexpanded.
+    pub fn with_unexpanded_sources(mut self, unexpanded_sources: bool) -> Self {
+        self.unexpanded_sources = unexpanded_sources;
+        self
+    }
+
+    /// Expands the macros of a text.
+    pub fn expand(&mut self, text: &str) -> String {
+        self.expand_text(text, &[], &mut vec![])
+    }
+
+    /// Calls a built-in or user-defined function.
+    pub fn call<S: AsRef<str>>(&mut self, name: &str, arguments: &[S]) -> String {
+        let mut reference = format!("$({name}");
+        for argument in arguments {
+            reference.push(',');
+            reference.push_str(argument.as_ref());
+        }
+        reference.push(')');
+        self.expand(&reference)
+    }
+
+    /// Defines a variable, `operator` is one of `=`, `:=` or `+=`.
+    pub fn assign(&mut self, name: &str, operator: &str, value: &str) {
+        let value = self.assigned_value(name, operator, value);
+        self.variables.insert(name.to_string(), value);
+    }
+
+    /// The variable once assigned. Appending to a simply-expanded variable expands `value` right away.
+    pub fn assigned_value(&mut self, name: &str, operator: &str, value: &str) -> Variable {
+        let value = value.trim();
+        match (operator, self.variables.get(name).cloned()) {
+            (":=", _) => Variable::simple(self.expand(value)),
+            ("+=", Some(previous)) => {
+                let value = match previous.flavor {
+                    Flavor::Simple => self.expand(value),
+                    Flavor::Recursive => value.to_string(),
+                };
+                Variable {
+                    value: match previous.value.is_empty() {
+                        true => value,
+                        false => format!("{} {}", previous.value, value),
+                    },
+                    flavor: previous.flavor,
+                }
+            }
+            _ => Variable::recursive(value),
+        }
+    }
+
+    /// Expands the macros of a Kconfig file and defines the variables assigned in it.
+    /// Assignments themselves are kept unexpanded, except for the variable name.
+    pub fn preprocess(&mut self, content: &str) -> String {
+        let mut output = String::with_capacity(content.len());
+        let mut help = Help::Outside;
+        let mut offset = 0;
+        // the previous line ended with `\`, this one continues its statement
+        let mut continued = false;
+        for (index, line) in content.split_inclusive('\n').enumerate() {
+            self.line = Span {
+                file: self.file.clone(),
+                offset,
+                line: index as u32 + 1,
+                column: 1,
+                length: line.trim_end().len(),
+            };
+            offset += line.len();
+            help = help.next(line);
+            if help != Help::Outside {
+                output.push_str(line);
+                continue;
+            }
+
+            let (code, comment) = line.split_at(find_comment(line).unwrap_or(line.len()));
+            let (code, eol) = match code.strip_suffix('\n') {
+                Some(code) => (code, "\n"),
+                None => (code, ""),
+            };
+            let statement_start = !continued;
+            continued = comment.is_empty() && code.trim_end_matches('\r').ends_with('\\');
+            if statement_start && matches!(code.trim(), "help" | "---help---") {
+                help = Help::Start;
+                output.push_str(line);
+                continue;
+            }
+            // an assignment starts a statement, `\tARCH=x86` after `depends on A && \` is not one
+            match split_assignment(code).filter(|_| statement_start) {
+                Some(assignment) => {
+                    let name = self.expand(assignment.name);
+                    self.assign(&name, assignment.operator, assignment.value);
+                    output.push_str(assignment.indent);
+                    output.push_str(&name);
+                    output.push_str(assignment.rest);
+                }
+                None if statement_start && self.unexpanded_sources && is_source_statement(code) => {
+                    output.push_str(code)
+                }
+                None => output.push_str(&self.expand(code)),
+            }
+            output.push_str(comment);
+            output.push_str(eol);
+        }
+        output
+    }
+
+    /// Expands a text, `arguments` are the arguments of the function being called, its name first.
+    fn expand_text(&mut self, text: &str, arguments: &[String], stack: &mut Vec<String>) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('$') {
+            output.push_str(&rest[..start]);
+            let reference = &rest[start..];
+            match reference_end(reference) {
+                Some(end) => {
+                    let expanded = self.expand_reference(&reference[..end], arguments, stack);
+                    output.push_str(&expanded);
+                    rest = &reference[end..];
+                }
+                None => {
+                    output.push('$');
+                    rest = &reference[1..];
+                }
+            }
+        }
+        output.push_str(rest);
+        output
+    }
+
+    /// Expands `$(...)` or `${...}`.
+    fn expand_reference(
+        &mut self,
+        reference: &str,
+        arguments: &[String],
+        stack: &mut Vec<String>,
+    ) -> String {
+        let inner = &reference[2..reference.len() - 1];
+        if stack.len() >= MAX_DEPTH {
+            return reference.to_string();
+        }
+        let parts = split_arguments(inner);
+        let name = self.expand_text(parts[0], arguments, stack);
+        if parts.len() == 1 && !arguments.is_empty() {
+            if let Ok(index) = name.parse::<usize>() {
+                return arguments.get(index).cloned().unwrap_or_default();
+            }
+        }
+        if let Some(toolchain) = self.toolchain.clone().filter(|t| t.handles(&name)) {
+            let values = self.expand_arguments(&parts[1..], arguments, stack);
+            if let Some(result) = toolchain.call(&name, &values) {
+                return result;
+            }
+            return self
+                .call_variable(name, values, stack)
+                .unwrap_or_else(|| reference.to_string());
+        }
+        if let Some(expanded) = self.call_builtin(&name, &parts[1..], arguments, stack) {
+            return expanded;
+        }
+        if !self.variables.contains_key(&name) || stack.contains(&name) {
+            return reference.to_string();
+        }
+        let values = self.expand_arguments(&parts[1..], arguments, stack);
+        self.call_variable(name, values, stack)
+            .unwrap_or_else(|| reference.to_string())
+    }
+
+    fn expand_arguments(
+        &mut self,
+        parts: &[&str],
+        arguments: &[String],
+        stack: &mut Vec<String>,
+    ) -> Vec<String> {
+        parts
+            .iter()
+            .map(|part| self.expand_text(part, arguments, stack))
+            .collect()
+    }
+
+    /// Expands a variable, `values` are the arguments when it is called as a function.
+    fn call_variable(
+        &mut self,
+        name: String,
+        values: Vec<String>,
+        stack: &mut Vec<String>,
+    ) -> Option<String> {
+        let variable = self.variables.get(&name).cloned()?;
+        if stack.contains(&name) {
+            return None;
+        }
+        if variable.flavor == Flavor::Simple {
+            return Some(variable.value);
+        }
+        let mut call = vec![name.clone()];
+        call.extend(values);
+        stack.push(name);
+        let expanded = self.expand_text(&variable.value, &call, stack);
+        stack.pop();
+        Some(expanded)
+    }
+
+    /// Calls a built-in function, returns `None` if there is no such function.
+    fn call_builtin(
+        &mut self,
+        name: &str,
+        parts: &[&str],
+        arguments: &[String],
+        stack: &mut Vec<String>,
+    ) -> Option<String> {
+        let (min, max) = match name {
+            "filename" | "lineno" => (0, 0),
+            "info" => (1, 1),
+            "shell" if self.shell != ShellPolicy::Keep => (1, 1),
+            "filter-out" | "warning-if" | "error-if" => (2, 2),
+            "if" => (2, 3),
+            _ => return None,
+        };
+        if parts.len() < min || parts.len() > max {
+            let message = match parts.len() < min {
+                true => format!("too few function arguments passed to `{name}`"),
+                false => format!("too many function arguments passed to `{name}`"),
+            };
+            self.report(Severity::Error, message);
+            return Some(String::new());
+        }
+        // `if` only expands the branch it takes
+        if name == "if" {
+            let condition = self.expand_text(parts[0], arguments, stack);
+            let branch = match condition.trim().is_empty() {
+                true => parts.get(2),
+                false => parts.get(1),
+            };
+            return Some(
+                branch
+                    .map(|branch| self.expand_text(branch, arguments, stack))
+                    .unwrap_or_default(),
+            );
+        }
+        let values = self.expand_arguments(parts, arguments, stack);
+        Some(match name {
+            "filename" => self.file.clone(),
+            "lineno" => self.line.line.to_string(),
+            "shell" => match self.shell.shell(&values[0]) {
+                Some(Ok(output)) => output,
+                Some(Err(message)) => {
+                    self.report(Severity::Warning, message);
+                    String::new()
+                }
+                None => unreachable!("$(shell) is kept unexpanded"),
+            },
+            "filter-out" => {
+                let excluded: Vec<&str> = values[0].split_whitespace().collect();
+                values[1]
+                    .split_whitespace()
+                    .filter(|word| !excluded.contains(word))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            _ => {
+                let (severity, message) = match name {
+                    "info" => (Some(Severity::Info), &values[0]),
+                    "warning-if" => (
+                        (values[0].trim() == "y").then_some(Severity::Warning),
+                        &values[1],
+                    ),
+                    _ => (
+                        (values[0].trim() == "y").then_some(Severity::Error),
+                        &values[1],
+                    ),
+                };
+                if let Some(severity) = severity {
+                    self.report(severity, message.trim().to_string());
+                }
+                String::new()
+            }
+        })
+    }
+
+    fn report(&mut self, severity: Severity, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            ..Diagnostic::error(message, self.line.clone())
+        });
+    }
+}
+
+/// Length of the `$(...)` or `${...}` reference at the beginning of the text.
+fn reference_end(text: &str) -> Option<usize> {
+    let (open, close) = match text.as_bytes().get(1) {
+        Some(b'(') => (b'(', b')'),
+        Some(b'{') => (b'{', b'}'),
+        _ => return None,
+    };
+    let mut depth = 0;
+    for (index, byte) in text.bytes().enumerate().skip(1) {
+        if byte == open {
+            depth += 1;
+        } else if byte == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(index + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Splits the content of a reference at the commas that are not nested in another reference.
+fn split_arguments(inner: &str) -> Vec<&str> {
+    let mut arguments = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, byte) in inner.bytes().enumerate() {
+        match byte {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            b',' if depth == 0 => {
+                arguments.push(&inner[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    arguments.push(&inner[start..]);
+    arguments
+}
+
+/// Whether the line is a `source`, `osource`, `rsource` or `orsource` statement.
+fn is_source_statement(code: &str) -> bool {
+    code.split_whitespace()
+        .next()
+        .is_some_and(|keyword| matches!(keyword, "source" | "osource" | "rsource" | "orsource"))
+}
+
+/// Position of the `#` starting a comment, if any.
+fn find_comment(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0;
+    let mut escaped = false;
+    let mut previous = '\0';
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '(') if previous == '$' || depth > 0 => depth += 1,
+            (None, ')') if depth > 0 => depth -= 1,
+            (None, '"' | '\'') if depth == 0 => quote = Some(c),
+            (None, '#') if depth == 0 => return Some(index),
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// A line of the form `NAME op value`.
+struct Assignment<'a> {
+    indent: &'a str,
+    name: &'a str,
+    /// What follows the name, from the operator to the end of the line.
+    rest: &'a str,
+    operator: &'static str,
+    value: &'a str,
+}
+
+fn split_assignment(code: &str) -> Option<Assignment<'_>> {
+    let trimmed = code.trim_start();
+    let indent = &code[..code.len() - trimmed.len()];
+    let mut name_end = 0;
+    while name_end < trimmed.len() {
+        let rest = &trimmed[name_end..];
+        let c = rest.chars().next()?;
+        if rest.starts_with("$(") || rest.starts_with("${") {
+            name_end += reference_end(rest)?;
+        } else if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+            name_end += 1;
+        } else {
+            break;
+        }
+    }
+    if name_end == 0 {
+        return None;
+    }
+    let rest = &trimmed[name_end..];
+    let operation = rest.trim_start();
+    let operator = [":=", "+=", "="]
+        .into_iter()
+        .find(|operator| operation.starts_with(operator))?;
+    Some(Assignment {
+        indent,
+        name: &trimmed[..name_end],
+        rest,
+        operator,
+        value: &operation[operator.len()..],
+    })
+}
+
+/// Width of the leading whitespace, tabs are 8 columns wide.
+fn indentation(line: &str) -> usize {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width = (width / 8 + 1) * 8,
+            _ => break,
+        }
+    }
+    width
+}
@@ -0,0 +1,287 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use nom::Parser;
+
+use crate::{
+    attribute::{
+        parse_function_call,
+        r#type::{ConfigType, Type},
+    },
+    diagnostic::{parse_kconfig_with_diagnostics, Severity},
+    kconfig::parse_kconfig,
+    preprocess::{Preprocessor, Variable},
+    util::ws,
+    vfs::MemoryFs,
+    Attribute, Entry, KconfigFile, KconfigInput,
+};
+
+fn preprocess(content: &str) -> (String, Preprocessor) {
+    let mut preprocessor = Preprocessor::default().with_file("Kconfig");
+    let output = preprocessor.preprocess(content);
+    (output, preprocessor)
+}
+
+#[test]
+fn test_preprocess_recursive_and_simple_variables() {
+    let (output, mut preprocessor) = preprocess(
+        "B := x
+RECURSIVE = $(B)
+SIMPLE := $(B)
+B := y
+config A
+    string \"$(RECURSIVE) $(SIMPLE)\"
+",
+    );
+    assert!(output.ends_with("config A\n    string \"y x\"\n"));
+    assert_eq!(
+        preprocessor.variables["RECURSIVE"],
+        Variable::recursive("$(B)")
+    );
+    assert_eq!(preprocessor.variables["SIMPLE"], Variable::simple("x"));
+    assert_eq!(preprocessor.expand("$(RECURSIVE)"), "y");
+}
+
+#[test]
+fn test_preprocess_unexpanded_sources() {
+    let content =
+        "ARCH := x86\nsource \"arch/$(ARCH)/Kconfig\"\n  osource \"$(ARCH)\"\nconfig $(ARCH)\n";
+    let mut preprocessor = Preprocessor::default().with_unexpanded_sources(true);
+    assert_eq!(
+        preprocessor.preprocess(content),
+        "ARCH := x86\nsource \"arch/$(ARCH)/Kconfig\"\n  osource \"$(ARCH)\"\nconfig x86\n"
+    );
+}
+
+#[test]
+fn test_preprocess_continued_line_is_not_an_assignment() {
+    let (output, preprocessor) =
+        preprocess("config A\n\tdepends on B && \\\n\tARCH=$(X)\nX := x86\n");
+    assert_eq!(
+        output,
+        "config A\n\tdepends on B && \\\n\tARCH=$(X)\nX := x86\n"
+    );
+    assert!(!preprocessor.variables.contains_key("ARCH"));
+    assert_eq!(preprocessor.variables["X"], Variable::simple("x86"));
+}
+
+#[test]
+fn test_preprocess_append() {
+    let (_, preprocessor) = preprocess("FLAGS := -O2\nFLAGS += -g\nEMPTY += -Wall\n");
+    assert_eq!(preprocessor.variables["FLAGS"], Variable::simple("-O2 -g"));
+    assert_eq!(
+        preprocessor.variables["EMPTY"],
+        Variable::recursive("-Wall")
+    );
+}
+
+#[test]
+fn test_preprocess_append_keeps_flavor() {
+    let (output, preprocessor) = preprocess(
+        "X := a
+Y := $(X)
+Y += $(X)
+R = $(X)
+R += $(X)
+X := b
+S := $(LATER)
+LATER = late
+config A
+    string \"$(Y) $(R) $(S)\"
+",
+    );
+    assert!(output.ends_with("config A\n    string \"a a b b $(LATER)\"\n"));
+    assert_eq!(preprocessor.variables["Y"], Variable::simple("a a"));
+    assert_eq!(
+        preprocessor.variables["R"],
+        Variable::recursive("$(X) $(X)")
+    );
+}
+
+#[test]
+fn test_preprocess_only_in_token_positions() {
+    let input = "NAME := kernel
+config A
+    bool \"$(NAME)\" # $(NAME) in a comment
+    default $(NAME)
+    help
+      $(NAME) in a help text
+
+      is not expanded
+config B
+    string '$(NAME)'
+";
+    let (output, _) = preprocess(input);
+    assert_eq!(
+        output,
+        "NAME := kernel
+config A
+    bool \"kernel\" # $(NAME) in a comment
+    default kernel
+    help
+      $(NAME) in a help text
+
+      is not expanded
+config B
+    string 'kernel'
+"
+    );
+}
+
+#[test]
+fn test_preprocess_undefined_and_self_referencing_variables() {
+    let (output, _) = preprocess("LOOP = $(LOOP)\nsource \"$(UNDEFINED)/$(LOOP)/Kconfig\"\n");
+    assert_eq!(
+        output,
+        "LOOP = $(LOOP)\nsource \"$(UNDEFINED)/$(LOOP)/Kconfig\"\n"
+    );
+}
+
+#[test]
+fn test_preprocess_variable_name() {
+    let (output, preprocessor) = preprocess("PREFIX := CC\n$(PREFIX)_FLAGS := -O2\n");
+    assert_eq!(output, "PREFIX := CC\nCC_FLAGS := -O2\n");
+    assert_eq!(preprocessor.variables["CC_FLAGS"], Variable::simple("-O2"));
+}
+
+#[test]
+fn test_preprocess_sourced_files() {
+    let mut fs = MemoryFs::new();
+    fs.insert(
+        "/kernel/Kconfig",
+        "BITS := 64\nARCH = x86_$(BITS)\nsource \"arch/Kconfig\"\n",
+    );
+    fs.insert(
+        "/kernel/arch/Kconfig",
+        "config ARCH\n    string \"$(ARCH) on $(SRCARCH)\"\n",
+    );
+    let kconfig_file = KconfigFile::new_with_vars(
+        PathBuf::from("/kernel"),
+        PathBuf::from("Kconfig"),
+        &HashMap::from([("SRCARCH", "x86")]),
+        &HashMap::default(),
+    )
+    .with_vfs(fs);
+    let content = kconfig_file.read_to_string().unwrap();
+    let (input, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file)).unwrap();
+    assert_eq!(
+        input.extra.local_vars["ARCH"],
+        Variable::recursive("x86_$(BITS)")
+    );
+    assert_eq!(input.extra.local_vars["BITS"], Variable::simple("64"));
+
+    let Entry::Source(source) = &kconfig.entries[2] else {
+        unreachable!()
+    };
+    let Entry::Config(config) = &source.kconfigs[0].entries[0] else {
+        unreachable!()
+    };
+    assert_eq!(
+        config.attributes[0],
+        Attribute::Type(ConfigType {
+            r#type: Type::String(Some("x86_64 on x86".to_string())),
+            r#if: None,
+            span: Default::default(),
+        })
+    );
+}
+
+#[test]
+fn test_preprocess_builtin_functions() {
+    let (output, preprocessor) = preprocess(
+        "ARCHS := x86 arm riscv
+config A
+    string \"$(filter-out,arm,$(ARCHS))\"
+config B
+    string \"$(if,$(ARCHS),some,none) $(if,,some,none) $(if,,some)\"
+    default \"$(filename):$(lineno)\"
+",
+    );
+    assert!(output.contains("string \"x86 riscv\""));
+    assert!(output.contains("string \"some none \""));
+    assert!(output.contains("default \"Kconfig:6\""));
+    assert!(preprocessor.diagnostics.is_empty());
+}
+
+#[test]
+fn test_preprocess_user_defined_functions() {
+    let (output, _) = preprocess(
+        "greeting = $(1), $(2)!
+twice = $(greeting,$(1),$(1))
+config A
+    string \"$(greeting,hello,world) $(twice,hi) $(greeting,alone)\"
+",
+    );
+    assert!(output.ends_with("string \"hello, world! hi, hi! alone, !\"\n"));
+}
+
+#[test]
+fn test_function_call() {
+    let (_, call) = ws(parse_function_call)
+        .parse(KconfigInput::new_extra(
+            "$(greeting,kconfig)",
+            Default::default(),
+        ))
+        .unwrap();
+    let mut preprocessor = Preprocessor::default();
+    preprocessor.assign("greeting", "=", "hello $(1)");
+    assert_eq!(call.call(&mut preprocessor), "hello kconfig");
+    assert_eq!(preprocessor.call("filter-out", &["b", "a b c"]), "a c");
+}
+
+#[test]
+fn test_preprocess_messages() {
+    let (output, preprocessor) = preprocess(
+        "$(info,parsing $(filename))
+$(warning-if,y,deprecated)
+$(warning-if,n,not reported)
+$(error-if,$(if,x,y),unsupported)
+$(if,a)
+",
+    );
+    assert_eq!(output, "\n\n\n\n\n");
+    let messages: Vec<(Severity, u32, &str)> = preprocessor
+        .diagnostics
+        .iter()
+        .map(|d| (d.severity, d.span.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (Severity::Info, 1, "parsing Kconfig"),
+            (Severity::Warning, 2, "deprecated"),
+            (Severity::Error, 4, "unsupported"),
+            (
+                Severity::Error,
+                5,
+                "too few function arguments passed to `if`"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_error_if_fails_the_parse() {
+    let mut fs = MemoryFs::new();
+    fs.insert(
+        "/kernel/Kconfig",
+        "TOOLCHAIN_OK := n\nsource \"arch/Kconfig\"\n",
+    );
+    fs.insert(
+        "/kernel/arch/Kconfig",
+        "$(error-if,$(if,$(filter-out,y,$(TOOLCHAIN_OK)),y),unsupported toolchain)\nconfig A\n    bool\n",
+    );
+    let kconfig_file =
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig")).with_vfs(fs);
+    let content = kconfig_file.read_to_string().unwrap();
+    assert!(parse_kconfig(KconfigInput::new_extra(&content, kconfig_file.clone())).is_err());
+
+    let result = parse_kconfig_with_diagnostics(KconfigInput::new_extra(&content, kconfig_file));
+    assert_eq!(result.diagnostics.len(), 1);
+    let error = &result.diagnostics[0];
+    assert_eq!(error.message, "unsupported toolchain");
+    assert_eq!(
+        (error.span.file.as_str(), error.span.line),
+        ("arch/Kconfig", 1)
+    );
+    assert_eq!(error.include_chain.len(), 1);
+}
@@ -0,0 +1,476 @@
+//! Computes the final value of every symbol, the equivalent of `make olddefconfig`.
+//!
+//! The algorithm follows `sym_calc_value()` of the C implementation:
+//! - the visibility of a symbol is the highest visibility of its prompts (`prompt ... if` and `depends on`),
+//! - a visible symbol takes the value found in the input configuration, limited to its visibility,
+//! - otherwise the first `default` whose condition is met is used, `imply` can raise it up to the dependencies of the symbol,
+//! - `select` forces a minimum value whatever the dependencies are,
+//! - int and hex values are clamped to the first active `range`,
+//! - a choice selects the member set in the configuration, then its first active `default`, then its first visible member.
+//!
+//! `configdefault` entries of the `kconfiglib` feature are merged into the defaults of the symbol, in definition order.
+//!
+//! ```
+//! use nom_kconfig::{
+//!     attribute::SymbolValue, dotconfig::DotConfig, parse_kconfig, resolve::resolve,
+//!     tristate::Tristate, KconfigInput,
+//! };
+//!
+//! let input = r#"
+//! config A
+//!     bool "a"
+//! config B
+//!     bool
+//!     default y if A
+//! "#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let configuration = resolve(&kconfig, Some(&DotConfig::parse("CONFIG_A=y\n")));
+//! assert_eq!(configuration.get("B"), Some(&SymbolValue::Tristate(Tristate::Yes)));
+//! assert_eq!(configuration.to_dotconfig("CONFIG_").to_string(), "CONFIG_A=y\nCONFIG_B=y\n");
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{
+        evaluate, evaluate_value,
+        expression::eval::{parse_number, parse_number_str},
+        range::RangeBound,
+        AndExpression, Atom, Expression, SymbolProvider, SymbolValue, Term,
+    },
+    dotconfig::DotConfig,
+    symbol_table::{ChoiceRecord, SymbolRecord, SymbolType},
+    tristate::Tristate,
+    Kconfig, Symbol, SymbolTable,
+};
+
+/// The resolved value of a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct ResolvedSymbol {
+    pub name: String,
+    pub r#type: SymbolType,
+    pub value: SymbolValue,
+    /// The highest visibility of the prompts of the symbol, `n` when the user cannot change it.
+    pub visibility: Tristate,
+    /// `false` when the symbol would not appear in a `.config` file, for instance when its dependencies are not met.
+    pub written: bool,
+    /// The bounds of the first active `range` of an int or hex symbol.
+    pub range: Option<(i128, i128)>,
+}
+
+/// The result of [resolve].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct Configuration {
+    pub symbols: Vec<ResolvedSymbol>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    index: HashMap<String, usize>,
+}
+
+impl Configuration {
+    pub fn get(&self, name: &str) -> Option<&SymbolValue> {
+        self.symbol(name).map(|s| &s.value)
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<&ResolvedSymbol> {
+        self.index.get(name).map(|i| &self.symbols[*i])
+    }
+
+    /// Builds the `.config` file. Symbols set to `n` are written as `# CONFIG_FOO is not set`.
+    pub fn to_dotconfig(&self, prefix: &str) -> DotConfig {
+        let mut config = DotConfig::parse_with_prefix("", prefix);
+        for symbol in self.symbols.iter().filter(|s| s.written) {
+            config.set(&symbol.name, symbol.value.clone());
+        }
+        config
+    }
+}
+
+impl SymbolProvider for Configuration {
+    fn value(&self, symbol: &str) -> Option<SymbolValue> {
+        self.get(symbol).cloned()
+    }
+}
+
+/// Resolves the value of every symbol of a [Kconfig] tree, starting from an optional configuration file.
+pub fn resolve(kconfig: &Kconfig, config: Option<&DotConfig>) -> Configuration {
+    resolve_table(&SymbolTable::new(kconfig), config)
+}
+
+/// Same as [resolve] when the [SymbolTable] has already been built.
+pub fn resolve_table(table: &SymbolTable, config: Option<&DotConfig>) -> Configuration {
+    let resolver = Resolver::new(table, config);
+    let mut configuration = Configuration::default();
+    for record in table.symbols() {
+        let Some(r#type) = record.r#type() else {
+            continue;
+        };
+        let value = resolver.calc(record);
+        configuration
+            .index
+            .insert(record.name.clone(), configuration.symbols.len());
+        configuration.symbols.push(ResolvedSymbol {
+            name: record.name.clone(),
+            r#type,
+            visibility: resolver.visibility(record),
+            written: !record.transitional && resolver.is_written(record, &value),
+            range: match r#type {
+                SymbolType::Int | SymbolType::Hex => resolver.range(record),
+                _ => None,
+            },
+            value,
+        });
+    }
+    configuration
+}
+
+/// Values are computed lazily and cached, a symbol may be evaluated while computing another one.
+struct Resolver<'a> {
+    table: &'a SymbolTable,
+    config: Option<&'a DotConfig>,
+    /// The symbol enabling modules, `MODULES` when no symbol has the `modules` attribute.
+    modules: &'a str,
+    values: RefCell<HashMap<String, SymbolValue>>,
+    visibilities: RefCell<HashMap<String, Tristate>>,
+    choices: RefCell<HashMap<usize, Option<String>>>,
+    visiting: RefCell<HashSet<String>>,
+}
+
+impl SymbolProvider for Resolver<'_> {
+    fn value(&self, symbol: &str) -> Option<SymbolValue> {
+        let record = self.table.get(symbol)?;
+        record.r#type()?;
+        Some(self.calc(record))
+    }
+}
+
+impl<'a> Resolver<'a> {
+    fn new(table: &'a SymbolTable, config: Option<&'a DotConfig>) -> Self {
+        Self {
+            table,
+            config,
+            modules: table
+                .symbols()
+                .find(|s| s.modules)
+                .map_or("MODULES", |s| s.name.as_str()),
+            values: Default::default(),
+            visibilities: Default::default(),
+            choices: Default::default(),
+            visiting: Default::default(),
+        }
+    }
+
+    fn eval(&self, expression: &Option<Expression>) -> Tristate {
+        expression
+            .as_ref()
+            .map(|e| evaluate(e, self))
+            .unwrap_or(Tristate::Yes)
+    }
+
+    fn eval_condition(
+        &self,
+        r#if: &Option<Expression>,
+        dependencies: &Option<Expression>,
+    ) -> Tristate {
+        self.eval(r#if).and(self.eval(dependencies))
+    }
+
+    fn modules(&self) -> Tristate {
+        self.value(self.modules)
+            .map(|v| v.tristate())
+            .unwrap_or(Tristate::No)
+    }
+
+    /// `m` becomes `y` for bool symbols or when modules are disabled.
+    fn fix_module(&self, r#type: SymbolType, value: Tristate) -> Tristate {
+        match value == Tristate::Module
+            && (r#type == SymbolType::Bool || self.modules() == Tristate::No)
+        {
+            true => Tristate::Yes,
+            false => value,
+        }
+    }
+
+    fn visibility(&self, record: &SymbolRecord) -> Tristate {
+        if let Some(visibility) = self.visibilities.borrow().get(&record.name) {
+            return *visibility;
+        }
+        let visibility = record.prompts.iter().fold(Tristate::No, |acc, p| {
+            acc.or(self.eval_condition(&p.value.r#if, &p.dependencies))
+        });
+        let visibility = match record.choice {
+            Some(choice) => visibility.and(self.choice_visibility(&self.table.choices()[choice])),
+            None => visibility,
+        };
+        let visibility = match record.r#type() {
+            Some(SymbolType::Tristate) => self.fix_module(SymbolType::Tristate, visibility),
+            _ => self.fix_module(SymbolType::Bool, visibility),
+        };
+        self.visibilities
+            .borrow_mut()
+            .insert(record.name.clone(), visibility);
+        visibility
+    }
+
+    fn choice_visibility(&self, choice: &ChoiceRecord) -> Tristate {
+        choice.prompts.iter().fold(Tristate::No, |acc, p| {
+            acc.or(self.eval_condition(&p.value.r#if, &p.dependencies))
+        })
+    }
+
+    /// The first default whose condition is met, with its visibility.
+    fn active_default<'r>(&self, record: &'r SymbolRecord) -> Option<(&'r Expression, Tristate)> {
+        record.defaults.iter().find_map(|d| {
+            let visibility = self.eval_condition(&d.value.r#if, &d.dependencies);
+            (visibility != Tristate::No).then_some((&d.value.expression, visibility))
+        })
+    }
+
+    fn reverse_dependencies(&self, record: &SymbolRecord, implied: bool) -> Tristate {
+        let reverse = match implied {
+            true => &record.implied_by,
+            false => &record.selected_by,
+        };
+        reverse.iter().fold(Tristate::No, |acc, r| {
+            let selector = self
+                .value(&r.symbol)
+                .map(|v| v.tristate())
+                .unwrap_or(Tristate::No);
+            acc.or(selector.and(self.eval(&r.r#if)))
+        })
+    }
+
+    fn user_value(&self, record: &SymbolRecord, r#type: SymbolType) -> Option<SymbolValue> {
+        let value = self.config?.get(&record.name)?;
+        normalize(r#type, value)
+    }
+
+    fn is_written(&self, record: &SymbolRecord, value: &SymbolValue) -> bool {
+        if record.choice.is_some() {
+            return self.eval(&record.dependencies()) != Tristate::No
+                || value.tristate() != Tristate::No;
+        }
+        let default = match value {
+            SymbolValue::Tristate(t) => *t != Tristate::No,
+            // numbers without any value are not written
+            SymbolValue::String(s)
+                if s.is_empty() && record.r#type() != Some(SymbolType::String) =>
+            {
+                return false
+            }
+            _ => self.active_default(record).is_some(),
+        };
+        default
+            || self.visibility(record) != Tristate::No
+            || self.reverse_dependencies(record, false) != Tristate::No
+            || self.reverse_dependencies(record, true) != Tristate::No
+    }
+
+    fn calc(&self, record: &SymbolRecord) -> SymbolValue {
+        if let Some(value) = self.values.borrow().get(&record.name) {
+            return value.clone();
+        }
+        // a symbol depending on itself is computed as `n` while it is being evaluated
+        if !self.visiting.borrow_mut().insert(record.name.clone()) {
+            return SymbolValue::Tristate(Tristate::No);
+        }
+        let value = match record.r#type() {
+            Some(t @ (SymbolType::Bool | SymbolType::Tristate)) => {
+                SymbolValue::Tristate(self.calc_tristate(record, t))
+            }
+            Some(t) => self.calc_value(record, t),
+            None => SymbolValue::Tristate(Tristate::No),
+        };
+        self.visiting.borrow_mut().remove(&record.name);
+        self.values
+            .borrow_mut()
+            .insert(record.name.clone(), value.clone());
+        value
+    }
+
+    fn calc_tristate(&self, record: &SymbolRecord, r#type: SymbolType) -> Tristate {
+        let selected = self.reverse_dependencies(record, false);
+        if let Some(choice) = record.choice {
+            let value = match self.choice_selection(choice) {
+                Some(member) if member == record.name => Tristate::Yes,
+                _ => Tristate::No,
+            };
+            return value.or(selected);
+        }
+
+        let visibility = self.visibility(record);
+        let user = match visibility {
+            Tristate::No => None,
+            _ => self.user_value(record, r#type),
+        };
+        let value = match user {
+            Some(user) => user.tristate().and(visibility),
+            None => {
+                let mut value = self
+                    .active_default(record)
+                    .map(|(e, visibility)| evaluate(e, self).and(visibility))
+                    .unwrap_or(Tristate::No);
+                let implied = self.reverse_dependencies(record, true);
+                if implied != Tristate::No {
+                    value = value.or(implied).and(self.eval(&record.dependencies()));
+                }
+                value
+            }
+        };
+        self.fix_module(r#type, value.or(selected))
+    }
+
+    fn calc_value(&self, record: &SymbolRecord, r#type: SymbolType) -> SymbolValue {
+        let user = match self.visibility(record) {
+            Tristate::No => None,
+            _ => self.user_value(record, r#type),
+        };
+        let value = match user {
+            Some(user) => Some(user),
+            None => self
+                .active_default(record)
+                .and_then(|(e, _)| normalize(r#type, evaluate_value(e, self))),
+        };
+        let value = match (r#type, value) {
+            (SymbolType::String, value) => value.unwrap_or(SymbolValue::String(String::new())),
+            (_, Some(value)) => value,
+            // an empty number is clamped to the lower bound of the range, if any
+            (_, None) => SymbolValue::String(String::new()),
+        };
+        match r#type {
+            SymbolType::Int | SymbolType::Hex => self.clamp(record, r#type, value),
+            _ => value,
+        }
+    }
+
+    fn clamp(&self, record: &SymbolRecord, r#type: SymbolType, value: SymbolValue) -> SymbolValue {
+        let Some((lower, upper)) = self.range(record) else {
+            return value;
+        };
+        let bound = match parse_number(&value) {
+            Some(v) if v < lower => lower,
+            Some(v) if v > upper => upper,
+            Some(_) => return value,
+            None => lower,
+        };
+        match r#type {
+            SymbolType::Hex => SymbolValue::Hex(format!("{:#x}", bound)),
+            _ => SymbolValue::Int(bound as i64),
+        }
+    }
+
+    /// The bounds of the first active range.
+    fn range(&self, record: &SymbolRecord) -> Option<(i128, i128)> {
+        let range = record
+            .ranges
+            .iter()
+            .find(|r| self.eval_condition(&r.value.r#if, &r.dependencies) != Tristate::No)?;
+        Some((
+            self.range_bound(&range.value.lower_bound)?,
+            self.range_bound(&range.value.upper_bound)?,
+        ))
+    }
+
+    fn range_bound(&self, bound: &RangeBound) -> Option<i128> {
+        match bound {
+            RangeBound::Number(n) => Some(*n as i128),
+            RangeBound::Hex(h) => parse_number_str(h),
+            RangeBound::Symbol(s) => match self.value(s) {
+                Some(value) => parse_number(&value),
+                None => parse_number_str(s),
+            },
+            RangeBound::Variable(_) => None,
+        }
+    }
+
+    /// The member of the choice set to `y`, if any.
+    fn choice_selection(&self, index: usize) -> Option<String> {
+        if let Some(selection) = self.choices.borrow().get(&index) {
+            return selection.clone();
+        }
+        // guards against members whose visibility depends on the choice itself
+        self.choices.borrow_mut().insert(index, None);
+        let choice = &self.table.choices()[index];
+        let selection = self.calc_choice(choice);
+        self.choices.borrow_mut().insert(index, selection.clone());
+        selection
+    }
+
+    fn calc_choice(&self, choice: &ChoiceRecord) -> Option<String> {
+        if self.eval(&choice.dependencies()) == Tristate::No {
+            return None;
+        }
+        let visible = choice
+            .members
+            .iter()
+            .filter(|m| {
+                self.table.get(m).is_some_and(|record| {
+                    record.prompts.iter().any(|p| {
+                        self.eval_condition(&p.value.r#if, &p.dependencies) != Tristate::No
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        if self.choice_visibility(choice) != Tristate::No {
+            let user = visible.iter().find(|m| {
+                self.config
+                    .and_then(|c| c.get(m))
+                    .is_some_and(|v| v.tristate() == Tristate::Yes)
+            });
+            if let Some(user) = user {
+                return Some(user.to_string());
+            }
+            if choice.optional {
+                return None;
+            }
+        }
+        let default = choice
+            .defaults
+            .iter()
+            .filter(|d| self.eval_condition(&d.value.r#if, &d.dependencies) != Tristate::No)
+            .find_map(|d| match &d.value.expression {
+                Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
+                    Symbol::NonConstant(name),
+                )))) => visible.iter().find(|m| **m == name),
+                _ => None,
+            });
+        default.or(visible.first()).map(|m| m.to_string())
+    }
+}
+
+/// Converts a value to the representation expected for the type, `None` when the value is not valid.
+fn normalize(r#type: SymbolType, value: SymbolValue) -> Option<SymbolValue> {
+    match r#type {
+        SymbolType::Bool | SymbolType::Tristate => match value {
+            SymbolValue::Tristate(t) => Some(SymbolValue::Tristate(t)),
+            _ => None,
+        },
+        SymbolType::Int => match &value {
+            SymbolValue::Int(_) => Some(value),
+            SymbolValue::String(s) => s.trim().parse::<i64>().ok().map(SymbolValue::Int),
+            _ => None,
+        },
+        SymbolType::Hex => {
+            let raw = value.raw();
+            let digits = raw
+                .strip_prefix("0x")
+                .or_else(|| raw.strip_prefix("0X"))
+                .unwrap_or(&raw);
+            match !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                true => Some(SymbolValue::Hex(match raw.len() == digits.len() {
+                    true => format!("0x{}", digits),
+                    false => raw.clone(),
+                })),
+                false => None,
+            }
+        }
+        SymbolType::String => Some(SymbolValue::String(value.raw())),
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    attribute::SymbolValue, lib_test::resolve_input, resolve::Configuration, tristate::Tristate,
+};
+
+fn tristate(configuration: &Configuration, name: &str) -> Tristate {
+    configuration.get(name).unwrap().tristate()
+}
+
+#[test]
+fn test_resolve_depends_on_and_defaults() {
+    let (_, configuration) = resolve_input(
+        r#"
+config MODULES
+    bool "modules"
+    modules
+
+config A
+    tristate "a"
+    default m
+
+config B
+    bool "b"
+    depends on A
+    default y
+
+config C
+    tristate
+    default n if B
+    default y
+
+config D
+    tristate "d"
+    depends on A
+"#,
+        "CONFIG_MODULES=y\nCONFIG_D=y\n",
+    );
+    assert_eq!(tristate(&configuration, "A"), Tristate::Module);
+    assert_eq!(tristate(&configuration, "B"), Tristate::Yes);
+    assert_eq!(tristate(&configuration, "C"), Tristate::No);
+    // the user value is limited by the visibility
+    assert_eq!(tristate(&configuration, "D"), Tristate::Module);
+}
+
+#[test]
+fn test_resolve_modules_disabled() {
+    let (_, configuration) = resolve_input(
+        r#"
+config MODULES
+    bool "modules"
+    option modules
+
+config A
+    tristate "a"
+"#,
+        "CONFIG_A=m\n",
+    );
+    assert_eq!(tristate(&configuration, "MODULES"), Tristate::No);
+    assert_eq!(tristate(&configuration, "A"), Tristate::Yes);
+}
+
+#[test]
+fn test_resolve_select_and_imply() {
+    let (_, configuration) = resolve_input(
+        r#"
+config A
+    bool "a"
+    select B
+    imply C
+    imply D
+
+config B
+    bool "b"
+    depends on NEVER
+
+config C
+    bool "c"
+
+config D
+    bool "d"
+"#,
+        "CONFIG_A=y\n# CONFIG_D is not set\n",
+    );
+    assert_eq!(tristate(&configuration, "B"), Tristate::Yes);
+    assert_eq!(tristate(&configuration, "C"), Tristate::Yes);
+    // imply is a weak reverse dependency, the user can still disable the symbol
+    assert_eq!(tristate(&configuration, "D"), Tristate::No);
+}
+
+#[test]
+fn test_resolve_ranges() {
+    let (_, configuration) = resolve_input(
+        r#"
+config MIN
+    int
+    default 10
+
+config NUM
+    int "num"
+    range MIN 20
+    default 5
+
+config ADDR
+    hex "addr"
+    range 0x1000 0x2000
+    default 0x1800
+
+config NAME
+    string "name"
+    default "linux"
+"#,
+        "CONFIG_ADDR=0xffff\n",
+    );
+    assert_eq!(configuration.get("NUM"), Some(&SymbolValue::Int(10)));
+    assert_eq!(
+        configuration.get("ADDR"),
+        Some(&SymbolValue::Hex("0x2000".to_string()))
+    );
+    assert_eq!(
+        configuration.get("NAME"),
+        Some(&SymbolValue::String("linux".to_string()))
+    );
+    assert_eq!(configuration.symbol("NUM").unwrap().range, Some((10, 20)));
+    assert_eq!(
+        configuration.symbol("ADDR").unwrap().range,
+        Some((0x1000, 0x2000))
+    );
+    assert_eq!(configuration.symbol("NAME").unwrap().range, None);
+}
+
+#[test]
+fn test_resolve_choice() {
+    let input = r#"
+choice
+    prompt "Timer frequency"
+    default HZ_250
+
+config HZ_100
+    bool "100 HZ"
+
+config HZ_250
+    bool "250 HZ"
+
+endchoice
+
+config HZ
+    int
+    default 100 if HZ_100
+    default 250 if HZ_250
+"#;
+    let (_, configuration) = resolve_input(input, "");
+    assert_eq!(tristate(&configuration, "HZ_100"), Tristate::No);
+    assert_eq!(tristate(&configuration, "HZ_250"), Tristate::Yes);
+    assert_eq!(configuration.get("HZ"), Some(&SymbolValue::Int(250)));
+
+    let (_, configuration) = resolve_input(input, "CONFIG_HZ_100=y\n");
+    assert_eq!(tristate(&configuration, "HZ_100"), Tristate::Yes);
+    assert_eq!(tristate(&configuration, "HZ_250"), Tristate::No);
+    assert_eq!(
+        configuration.to_dotconfig("CONFIG_").to_string(),
+        "CONFIG_HZ_100=y\n# CONFIG_HZ_250 is not set\nCONFIG_HZ=100\n"
+    );
+}
+
+#[test]
+fn test_resolve_written_symbols() {
+    let (_, configuration) = resolve_input(
+        r#"
+config A
+    bool "a"
+
+config B
+    bool "b"
+    depends on A
+
+config C
+    bool
+"#,
+        "",
+    );
+    assert_eq!(
+        configuration.to_dotconfig("CONFIG_").to_string(),
+        "# CONFIG_A is not set\n"
+    );
+}
+
+#[cfg(feature = "kconfiglib")]
+#[test]
+fn test_resolve_configdefault() {
+    let (_, configuration) = resolve_input(
+        r#"
+config A
+    int "a"
+
+configdefault A
+    default 42
+"#,
+        "",
+    );
+    assert_eq!(configuration.get("A"), Some(&SymbolValue::Int(42)));
+}
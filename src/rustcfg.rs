@@ -0,0 +1,297 @@
+//! Kconfig values for Rust code: `--cfg` flags for rustc, `cargo:rustc-cfg` directives for build scripts and a module of typed constants.
+//!
+//! Flags and directives follow `include/generated/rustc_cfg` of the Linux kernel:
+//! a `bool` or `tristate` symbol set to `y` or `m` gives `--cfg=CONFIG_FOO`, the equivalent of `IS_ENABLED()`,
+//! and every symbol that is set gives `--cfg=CONFIG_FOO="value"`.
+//!
+//! The generated module maps the type of a symbol to a Rust type:
+//!
+//! | Kconfig    | Rust        |
+//! |------------|-------------|
+//! | `bool`     | `bool`      |
+//! | `tristate` | `Tristate`, an enum defined by the module |
+//! | `int`      | `i64`       |
+//! | `hex`      | `u64`       |
+//! | `string`   | `&str`      |
+//!
+//! `int` and `hex` symbols can be given another integer type with [RustCfg::with_rust_type],
+//! [RustCfg::rust_module] fails when a value does not fit in it.
+//!
+//! ```
+//! use nom_kconfig::{parse_kconfig, resolve::resolve, rustcfg::RustCfg, KconfigInput};
+//!
+//! let input = "config USB\n    def_tristate y\nconfig HZ\n    int\n    default 250\n";
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let configuration = resolve(&kconfig, None);
+//! let rust = RustCfg::new(&configuration).with_rust_type("HZ", "u32");
+//! assert_eq!(rust.rustc_cfg(), "--cfg=CONFIG_USB\n--cfg=CONFIG_USB=\"y\"\n--cfg=CONFIG_HZ=\"250\"\n");
+//! assert!(rust.rust_module().unwrap().contains("pub const CONFIG_HZ: u32 = 250;\n"));
+//! ```
+//!
+//! A build script can use [build_script] to get the directives and the module in one call:
+//! ```no_run
+//! // build.rs
+//! use std::path::{Path, PathBuf};
+//! use nom_kconfig::{rustcfg::build_script, KconfigFile};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let kconfig_file = KconfigFile::new(PathBuf::from("."), PathBuf::from("Kconfig"));
+//!     let out_dir = std::env::var("OUT_DIR")?;
+//!     build_script(kconfig_file, Path::new(".config"), Path::new(&out_dir))?;
+//!     Ok(())
+//! }
+//! // src/lib.rs: include!(concat!(env!("OUT_DIR"), "/kconfig.rs"));
+//! ```
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use crate::{
+    attribute::SymbolValue,
+    dotconfig::{DotConfig, DEFAULT_PREFIX},
+    kconfig::parse_kconfig,
+    resolve::{resolve, Configuration, ResolvedSymbol},
+    symbol_table::SymbolType,
+    tristate::Tristate,
+    KconfigFile, KconfigInput,
+};
+
+/// Name of the module written by [build_script] in `OUT_DIR`.
+pub const RUST_MODULE: &str = "kconfig.rs";
+
+const TRISTATE_ENUM: &str = r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tristate {
+    No,
+    Module,
+    Yes,
+}
+"#;
+
+/// Generators of Rust configuration for a resolved [Configuration].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RustCfg<'a> {
+    configuration: &'a Configuration,
+    prefix: String,
+    rust_types: HashMap<String, String>,
+}
+
+impl<'a> RustCfg<'a> {
+    pub fn new(configuration: &'a Configuration) -> Self {
+        Self {
+            configuration,
+            prefix: DEFAULT_PREFIX.to_string(),
+            rust_types: HashMap::new(),
+        }
+    }
+
+    /// Changes the prefix of the symbols, `CONFIG_` by default.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Overrides the Rust type of an `int` or `hex` symbol in the generated module, an integer type like `u32`.
+    pub fn with_rust_type<S: Into<String>, T: Into<String>>(
+        mut self,
+        symbol: S,
+        rust_type: T,
+    ) -> Self {
+        self.rust_types.insert(symbol.into(), rust_type.into());
+        self
+    }
+
+    /// Symbols that are set, with their value as written in the flags.
+    fn values(&self) -> impl Iterator<Item = (&ResolvedSymbol, String)> {
+        self.configuration
+            .symbols
+            .iter()
+            .filter(|s| s.written)
+            .filter_map(|symbol| {
+                let value = match (symbol.r#type, &symbol.value) {
+                    (SymbolType::Bool | SymbolType::Tristate, value) => {
+                        if value.tristate() == Tristate::No {
+                            return None;
+                        }
+                        value.raw()
+                    }
+                    (SymbolType::Hex, value) => with_hex_prefix(value.raw()),
+                    (_, value) => value.raw(),
+                };
+                Some((symbol, value))
+            })
+    }
+
+    /// The `--cfg` flags passed to rustc, one per line like `include/generated/rustc_cfg`.
+    pub fn rustc_cfg(&self) -> String {
+        self.cfgs()
+            .into_iter()
+            .map(|cfg| format!("--cfg={}\n", cfg))
+            .collect()
+    }
+
+    /// The directives printed by a build script: `cargo:rustc-check-cfg` for every symbol so that `#[cfg(CONFIG_FOO)]` does not warn when `FOO` is not set,
+    /// then `cargo:rustc-cfg` for the symbols that are set.
+    pub fn cargo_directives(&self) -> String {
+        let mut output = String::new();
+        for symbol in &self.configuration.symbols {
+            let values = match symbol.r#type {
+                SymbolType::Bool => r#"none(), "y""#,
+                SymbolType::Tristate => r#"none(), "y", "m""#,
+                _ => "any()",
+            };
+            output.push_str(&format!(
+                "cargo:rustc-check-cfg=cfg({}{}, values({}))\n",
+                self.prefix, symbol.name, values
+            ));
+        }
+        for cfg in self.cfgs() {
+            output.push_str(&format!("cargo:rustc-cfg={}\n", cfg));
+        }
+        output
+    }
+
+    fn cfgs(&self) -> Vec<String> {
+        let mut cfgs = vec![];
+        for (symbol, value) in self.values() {
+            let name = format!("{}{}", self.prefix, symbol.name);
+            if matches!(symbol.r#type, SymbolType::Bool | SymbolType::Tristate) {
+                cfgs.push(name.clone());
+            }
+            cfgs.push(format!("{}={:?}", name, value));
+        }
+        cfgs
+    }
+
+    /// A Rust module with a constant per symbol.
+    /// `bool` and `tristate` symbols that are not written are `false` and `Tristate::No`, other symbols that are not written are left out.
+    /// Fails when the value of an `int` or `hex` symbol does not fit in its Rust type.
+    pub fn rust_module(&self) -> io::Result<String> {
+        let mut output = "// Automatically generated file; DO NOT EDIT.\n\n".to_string();
+        if self
+            .configuration
+            .symbols
+            .iter()
+            .any(|s| s.r#type == SymbolType::Tristate)
+        {
+            output.push_str(TRISTATE_ENUM);
+            output.push('\n');
+        }
+        for symbol in &self.configuration.symbols {
+            let enabled = symbol.written.then(|| symbol.value.tristate());
+            let (rust_type, value) = match (symbol.r#type, &symbol.value) {
+                (SymbolType::Bool, _) => (
+                    "bool".to_string(),
+                    (enabled.unwrap_or(Tristate::No) != Tristate::No).to_string(),
+                ),
+                (SymbolType::Tristate, _) => (
+                    "Tristate".to_string(),
+                    match enabled.unwrap_or(Tristate::No) {
+                        Tristate::Yes => "Tristate::Yes",
+                        Tristate::Module => "Tristate::Module",
+                        Tristate::No => "Tristate::No",
+                    }
+                    .to_string(),
+                ),
+                _ if !symbol.written => continue,
+                (SymbolType::Int, SymbolValue::Int(i)) => {
+                    let rust_type = self.rust_type(symbol, "i64");
+                    check_range(symbol, &rust_type, Some(*i as i128))?;
+                    (rust_type, i.to_string())
+                }
+                (SymbolType::Hex, value) => {
+                    let rust_type = self.rust_type(symbol, "u64");
+                    let value = with_hex_prefix(value.raw());
+                    let number = u128::from_str_radix(&value[2..], 16)
+                        .ok()
+                        .and_then(|n| i128::try_from(n).ok());
+                    check_range(symbol, &rust_type, number)?;
+                    (rust_type, value)
+                }
+                (SymbolType::String, value) => ("&str".to_string(), format!("{:?}", value.raw())),
+                // an invalid value, it cannot be typed
+                _ => continue,
+            };
+            output.push_str(&format!(
+                "pub const {}{}: {} = {};\n",
+                self.prefix, symbol.name, rust_type, value
+            ));
+        }
+        Ok(output)
+    }
+
+    fn rust_type(&self, symbol: &ResolvedSymbol, default: &str) -> String {
+        self.rust_types
+            .get(&symbol.name)
+            .cloned()
+            .unwrap_or_else(|| default.to_string())
+    }
+}
+
+/// The value with a lowercase `0x` prefix.
+fn with_hex_prefix(value: String) -> String {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(&value);
+    format!("0x{}", digits)
+}
+
+/// The values an integer type can hold, `None` if it is not an integer type.
+fn integer_range(rust_type: &str) -> Option<(i128, i128)> {
+    Some(match rust_type {
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => (i64::MIN.into(), i64::MAX.into()),
+        "i128" => (i128::MIN, i128::MAX),
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        "u128" => (0, i128::MAX),
+        _ => return None,
+    })
+}
+
+/// Fails when `value` is not a number that fits in `rust_type`.
+fn check_range(symbol: &ResolvedSymbol, rust_type: &str, value: Option<i128>) -> io::Result<()> {
+    let error = |message: String| Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    let Some((min, max)) = integer_range(rust_type) else {
+        return error(format!(
+            "{}: {} is not an integer type",
+            symbol.name, rust_type
+        ));
+    };
+    match value {
+        Some(value) if (min..=max).contains(&value) => Ok(()),
+        _ => error(format!(
+            "{}: {} does not fit in {}",
+            symbol.name,
+            symbol.value.raw(),
+            rust_type
+        )),
+    }
+}
+
+/// For build scripts: parses the Kconfig tree, resolves it with the configuration file `dotconfig`,
+/// prints the cargo directives and writes [RUST_MODULE] in `out_dir`.
+/// Cargo is asked to run the build script again when `dotconfig` or one of the Kconfig files changes.
+pub fn build_script(
+    kconfig_file: KconfigFile,
+    dotconfig: &Path,
+    out_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root_dir = kconfig_file.root_dir.clone();
+    let content = kconfig_file.read_to_string()?;
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file))?;
+    let config = DotConfig::from_file(dotconfig, DEFAULT_PREFIX)?;
+    let configuration = resolve(&kconfig, Some(&config));
+    let rust = RustCfg::new(&configuration);
+
+    println!("cargo:rerun-if-changed={}", dotconfig.display());
+    for file in kconfig.files() {
+        println!("cargo:rerun-if-changed={}", root_dir.join(file).display());
+    }
+    print!("{}", rust.cargo_directives());
+    fs::write(out_dir.join(RUST_MODULE), rust.rust_module()?)?;
+    Ok(())
+}
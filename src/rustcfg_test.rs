@@ -0,0 +1,120 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    lib_test::{resolve_input, CONFIGURATION},
+    rustcfg::{build_script, RustCfg, RUST_MODULE},
+    KconfigFile,
+};
+
+#[test]
+fn test_rustc_cfg() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\nCONFIG_USB=m\n");
+    assert_eq!(
+        RustCfg::new(&configuration).rustc_cfg(),
+        r#"--cfg=CONFIG_MODULES
+--cfg=CONFIG_MODULES="y"
+--cfg=CONFIG_USB
+--cfg=CONFIG_USB="m"
+--cfg=CONFIG_HZ="250"
+--cfg=CONFIG_PHYS="0x1000000"
+--cfg=CONFIG_LOCALVERSION="-custom"
+"#
+    );
+}
+
+#[test]
+fn test_cargo_directives() {
+    let (_, configuration) = resolve_input(
+        "config USB\n    tristate \"usb\"\nconfig HZ\n    int \"hz\"\n    default 100\n",
+        "CONFIG_USB=y\n",
+    );
+    assert_eq!(
+        RustCfg::new(&configuration)
+            .with_prefix("CFG_")
+            .cargo_directives(),
+        r#"cargo:rustc-check-cfg=cfg(CFG_USB, values(none(), "y", "m"))
+cargo:rustc-check-cfg=cfg(CFG_HZ, values(any()))
+cargo:rustc-cfg=CFG_USB
+cargo:rustc-cfg=CFG_USB="y"
+cargo:rustc-cfg=CFG_HZ="100"
+"#
+    );
+}
+
+#[test]
+fn test_rust_module() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "CONFIG_MODULES=y\nCONFIG_USB=m\n");
+    assert_eq!(
+        RustCfg::new(&configuration)
+            .with_rust_type("HZ", "u32")
+            .rust_module()
+            .unwrap(),
+        r#"// Automatically generated file; DO NOT EDIT.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tristate {
+    No,
+    Module,
+    Yes,
+}
+
+pub const CONFIG_MODULES: bool = true;
+pub const CONFIG_USB: Tristate = Tristate::Module;
+pub const CONFIG_PCI: bool = false;
+pub const CONFIG_DEBUG: bool = false;
+pub const CONFIG_HZ: u32 = 250;
+pub const CONFIG_PHYS: u64 = 0x1000000;
+pub const CONFIG_LOCALVERSION: &str = "-custom";
+"#
+    );
+}
+
+#[test]
+fn test_rust_module_normalizes_hex_prefix() {
+    let (_, configuration) = resolve_input("config PHYS\n    hex \"phys\"\n", "CONFIG_PHYS=0X1F\n");
+    let rust = RustCfg::new(&configuration);
+    assert!(rust
+        .rust_module()
+        .unwrap()
+        .ends_with("pub const CONFIG_PHYS: u64 = 0x1F;\n"));
+    assert_eq!(rust.rustc_cfg(), "--cfg=CONFIG_PHYS=\"0x1F\"\n");
+}
+
+#[test]
+fn test_rust_module_out_of_range() {
+    let (_, configuration) = resolve_input(CONFIGURATION, "");
+    let error = |symbol, rust_type| {
+        RustCfg::new(&configuration)
+            .with_rust_type(symbol, rust_type)
+            .rust_module()
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(error("HZ", "i8"), "HZ: 250 does not fit in i8");
+    assert_eq!(error("PHYS", "u16"), "PHYS: 0x1000000 does not fit in u16");
+    assert_eq!(error("HZ", "f32"), "HZ: f32 is not an integer type");
+    assert!(RustCfg::new(&configuration)
+        .with_rust_type("PHYS", "u32")
+        .rust_module()
+        .is_ok());
+}
+
+#[test]
+fn test_build_script() {
+    let directory =
+        std::env::temp_dir().join(format!("nom-kconfig-rustcfg-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("Kconfig"), "config FOO\n    bool \"foo\"\n").unwrap();
+    fs::write(directory.join(".config"), "CONFIG_FOO=y\n").unwrap();
+    let result = build_script(
+        KconfigFile::new(directory.clone(), PathBuf::from("Kconfig")),
+        &directory.join(".config"),
+        &directory,
+    )
+    .map_err(|e| e.to_string())
+    .and_then(|_| fs::read_to_string(directory.join(RUST_MODULE)).map_err(|e| e.to_string()));
+    fs::remove_dir_all(&directory).unwrap();
+    assert!(result
+        .unwrap()
+        .ends_with("pub const CONFIG_FOO: bool = true;\n"));
+}
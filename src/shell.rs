@@ -0,0 +1,137 @@
+//! How `$(shell,...)` is expanded by the [preprocessor](crate::preprocess).
+//!
+//! Kconfig files probe the toolchain with `$(shell,...)`, directly or through `$(success)`, `$(cc-option)`...
+//! Commands are not executed unless [ShellPolicy::Run] is set.
+//! Recorded outputs make the result deterministic on machines without the toolchain:
+//!
+//! ```
+//! use std::{collections::HashMap, path::PathBuf};
+//! use nom_kconfig::{shell::ShellPolicy, KconfigFile};
+//!
+//! let policy = ShellPolicy::recorded(HashMap::from([(
+//!     "gcc -dumpversion".to_string(),
+//!     "13\n".to_string(),
+//! )]));
+//! let kconfig_file = KconfigFile::new(PathBuf::from("/linux"), PathBuf::from("Kconfig")).with_shell_policy(policy);
+//! let mut preprocessor = kconfig_file.preprocessor();
+//! assert_eq!(preprocessor.expand("$(shell,gcc -dumpversion)"), "13");
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    rc::Rc,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// What to do with `$(shell,...)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ShellPolicy {
+    /// `$(shell,...)` is left unexpanded.
+    #[default]
+    Keep,
+    /// `$(shell,...)` expands to an empty string and a warning is reported.
+    Deny,
+    /// Commands are run with `/bin/sh -c`, they are killed after `timeout`.
+    Run {
+        working_dir: PathBuf,
+        timeout: Duration,
+    },
+    /// Commands are looked up in a table of outputs, a warning is reported for unknown commands.
+    Recorded(Rc<HashMap<String, String>>),
+}
+
+impl ShellPolicy {
+    pub fn run<P: Into<PathBuf>>(working_dir: P, timeout: Duration) -> Self {
+        Self::Run {
+            working_dir: working_dir.into(),
+            timeout,
+        }
+    }
+
+    pub fn recorded(outputs: HashMap<String, String>) -> Self {
+        Self::Recorded(Rc::new(outputs))
+    }
+
+    /// Expands `$(shell,command)`, `None` if the policy is [ShellPolicy::Keep].
+    /// Like in the kernel, trailing new lines are removed and the other ones are replaced by spaces.
+    pub fn shell(&self, command: &str) -> Option<Result<String, String>> {
+        let output = match self {
+            ShellPolicy::Keep => return None,
+            ShellPolicy::Deny => Err(format!("`$(shell,{command})` is not allowed")),
+            ShellPolicy::Run {
+                working_dir,
+                timeout,
+            } => run(command, working_dir, *timeout)
+                .map_err(|e| format!("failed to run `{command}`: {e}")),
+            ShellPolicy::Recorded(outputs) => outputs
+                .get(command)
+                .cloned()
+                .ok_or_else(|| format!("no recorded output for `{command}`")),
+        };
+        Some(output.map(|output| output.trim_end_matches('\n').replace('\n', " ")))
+    }
+}
+
+fn run(command: &str, working_dir: &Path, timeout: Duration) -> io::Result<String> {
+    let mut command_line = Command::new("/bin/sh");
+    command_line
+        .arg("-c")
+        .arg(command)
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    // the command and the processes it starts are in their own group, killed together on timeout
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command_line, 0);
+    let mut child = command_line.spawn()?;
+    // the output is read in the background so that the command never blocks on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = String::new();
+        let _ = sender.send(stdout.read_to_string(&mut output).map(|_| output));
+    });
+    let deadline = Instant::now() + timeout;
+    let timed_out = || {
+        io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("timed out after {:?}", timeout),
+        )
+    };
+    while child.try_wait()?.is_none() {
+        if Instant::now() > deadline {
+            kill(&mut child)?;
+            return Err(timed_out());
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    // processes started in the background keep stdout open after the shell exits
+    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(output) => output,
+        Err(_) => {
+            kill(&mut child)?;
+            Err(timed_out())
+        }
+    }
+}
+
+/// Kills the shell and the processes it started.
+fn kill(child: &mut Child) -> io::Result<()> {
+    // the id of the group is the one of the shell
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    // the shell may have exited already
+    let _ = child.kill();
+    child.wait()?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attribute::{
+        expression::{AndExpression, Atom, Expression, Term},
+        r#type::Type,
+    },
+    diagnostic::Severity,
+    kconfig::parse_kconfig,
+    preprocess::Preprocessor,
+    shell::ShellPolicy,
+    symbol::{ConstantSymbol, Symbol},
+    vfs::MemoryFs,
+    Attribute, Entry, KconfigFile, KconfigInput,
+};
+
+fn expand(policy: ShellPolicy, text: &str) -> (String, Vec<(Severity, String)>) {
+    let mut preprocessor = Preprocessor::default().with_shell_policy(policy);
+    let output = preprocessor.expand(text);
+    let diagnostics = preprocessor
+        .diagnostics
+        .into_iter()
+        .map(|d| (d.severity, d.message))
+        .collect();
+    (output, diagnostics)
+}
+
+#[test]
+fn test_shell_keep() {
+    assert_eq!(
+        expand(ShellPolicy::Keep, "$(shell,echo hi)"),
+        ("$(shell,echo hi)".to_string(), vec![])
+    );
+}
+
+#[test]
+fn test_shell_deny() {
+    assert_eq!(
+        expand(ShellPolicy::Deny, "[$(shell,echo hi)]"),
+        (
+            "[]".to_string(),
+            vec![(
+                Severity::Warning,
+                "`$(shell,echo hi)` is not allowed".to_string()
+            )]
+        )
+    );
+}
+
+#[test]
+fn test_shell_recorded() {
+    let policy = ShellPolicy::recorded(HashMap::from([(
+        "printf 'a\\nb\\n\\n'".to_string(),
+        "a\nb\n\n".to_string(),
+    )]));
+    assert_eq!(
+        expand(policy.clone(), "$(shell,printf 'a\\nb\\n\\n')"),
+        ("a b".to_string(), vec![])
+    );
+    assert_eq!(
+        expand(policy, "$(shell,uname)"),
+        (
+            String::new(),
+            vec![(
+                Severity::Warning,
+                "no recorded output for `uname`".to_string()
+            )]
+        )
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shell_run() {
+    // commands run in the working directory, not in the directory of the Kconfig files
+    let directory = std::env::temp_dir().canonicalize().unwrap();
+    let policy = ShellPolicy::run(&directory, Duration::from_secs(10));
+    assert_eq!(
+        expand(policy.clone(), "$(shell,echo a; echo b)"),
+        ("a b".to_string(), vec![])
+    );
+    assert_eq!(
+        expand(policy, "$(shell,pwd -P)"),
+        (directory.display().to_string(), vec![])
+    );
+
+    let (output, diagnostics) = expand(
+        ShellPolicy::run(&directory, Duration::from_millis(50)),
+        "$(shell,sleep 5)",
+    );
+    assert_eq!(output, "");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].1.contains("timed out"));
+
+    // a background process keeps stdout open, it is killed along with the shell
+    let marker = directory.join(format!("nom-kconfig-shell-{}", std::process::id()));
+    let start = Instant::now();
+    let (output, diagnostics) = expand(
+        ShellPolicy::run(&directory, Duration::from_millis(100)),
+        &format!("$(shell,(sleep 1; touch {}) &)", marker.display()),
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(output, "");
+    assert!(diagnostics[0].1.contains("timed out"));
+    std::thread::sleep(Duration::from_millis(1500));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_recorded_toolchain_probe() {
+    let mut fs = MemoryFs::new();
+    fs.insert(
+        "/kernel/Kconfig",
+        "success = $(shell,{ $(1); } >/dev/null 2>&1 && echo y || echo n)
+cc-option = $(success,$(CC) -Werror $(1) -c -x c /dev/null -o /dev/null)
+
+config CC_HAS_FOO
+	def_bool $(cc-option,-mfoo)
+",
+    );
+    let policy = ShellPolicy::recorded(HashMap::from([(
+        "{ gcc -Werror -mfoo -c -x c /dev/null -o /dev/null; } >/dev/null 2>&1 && echo y || echo n"
+            .to_string(),
+        "y\n".to_string(),
+    )]));
+    let kconfig_file = KconfigFile::new_with_vars(
+        PathBuf::from("/kernel"),
+        PathBuf::from("Kconfig"),
+        &HashMap::from([("CC", "gcc")]),
+        &HashMap::default(),
+    )
+    .with_vfs(fs)
+    .with_shell_policy(policy);
+    let content = kconfig_file.read_to_string().unwrap();
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file)).unwrap();
+    let Entry::Config(config) = &kconfig.entries[2] else {
+        unreachable!()
+    };
+    let Attribute::Type(r#type) = &config.attributes[0] else {
+        unreachable!()
+    };
+    let Type::DefBool(expression) = &r#type.r#type else {
+        unreachable!()
+    };
+    assert_eq!(
+        expression,
+        &Expression::Term(AndExpression::Term(Term::Atom(Atom::Symbol(
+            Symbol::Constant(ConstantSymbol::Boolean(true))
+        ))))
+    );
+}
@@ -0,0 +1,174 @@
+//! Locations of entries and attributes in the Kconfig files.
+//!
+//! Spans are ignored when comparing or hashing the nodes of the AST: two entries parsed from different files are equal as long as their content is the same.
+//! Spans themselves compare their locations.
+//! They are serialized as `null` unless the `serde-spans` feature is enabled.
+//!
+//! ```
+//! use nom_kconfig::{kconfig::parse_kconfig, Entry, KconfigInput};
+//!
+//! let input = "config FOO\n    bool \"foo\"\n    select BAR\n";
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let Entry::Config(config) = &kconfig.entries[0] else { unreachable!() };
+//! let select = config.attributes[1].span();
+//! assert_eq!((select.line, select.column, select.length), (3, 5, 10));
+//! assert_eq!(&input[select.offset..select.offset + select.length], "select BAR");
+//! ```
+
+use nom::{combinator::consumed, IResult, Parser};
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{util::ws_comment, KconfigInput};
+
+/// A region of a Kconfig file.
+///
+/// Sourced files are parsed after [preprocessing](crate::preprocess): offsets and columns refer to the text where `$(...)` references have been expanded.
+/// Lines match the original file, but columns after a reference on the same line may be shifted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(all(feature = "serialize", feature = "serde-spans"), derive(Serialize))]
+#[cfg_attr(
+    all(feature = "deserialize", feature = "serde-spans"),
+    derive(Deserialize)
+)]
+pub struct Span {
+    /// The file, relative to the root directory defined in [KconfigFile](crate::KconfigFile).
+    pub file: String,
+    /// Offset in bytes from the beginning of the file.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: u32,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+    /// Length in bytes.
+    pub length: usize,
+}
+
+/// Without `serde-spans`, a span is serialized as `null`.
+#[cfg(all(feature = "serialize", not(feature = "serde-spans")))]
+impl Serialize for Span {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_none()
+    }
+}
+
+/// Without `serde-spans`, a span is ignored and deserialized as [Span::default], even if it is missing.
+#[cfg(all(feature = "deserialize", not(feature = "serde-spans")))]
+impl<'de> Deserialize<'de> for Span {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IgnoredSpan;
+
+        impl<'de> serde::de::Visitor<'de> for IgnoredSpan {
+            type Value = Span;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a span")
+            }
+
+            fn visit_none<E: serde::de::Error>(self) -> Result<Span, E> {
+                Ok(Span::default())
+            }
+
+            fn visit_unit<E: serde::de::Error>(self) -> Result<Span, E> {
+                Ok(Span::default())
+            }
+
+            fn visit_some<D: serde::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Span, D::Error> {
+                serde::de::IgnoredAny::deserialize(deserializer)?;
+                Ok(Span::default())
+            }
+        }
+
+        // a missing field is deserialized as `None` by `deserialize_option`
+        deserializer.deserialize_option(IgnoredSpan)
+    }
+}
+
+impl Span {
+    /// Returns `true` if the offset is inside the span.
+    pub fn contains(&self, offset: usize) -> bool {
+        self.offset <= offset && offset < self.offset + self.length
+    }
+
+    /// Builds the span of the fragment, leading comments and whitespaces as well as trailing whitespaces excluded.
+    pub fn from_input(input: &KconfigInput) -> Self {
+        let fragment = *input.fragment();
+        let rest = ws_comment::<&str, ()>(fragment)
+            .map(|(rest, _)| rest)
+            .unwrap_or(fragment);
+        let skipped = &fragment[..fragment.len() - rest.len()];
+        let (line, column) = match skipped.rfind('\n') {
+            Some(index) => (
+                input.location_line() + skipped.matches('\n').count() as u32,
+                skipped[index + 1..].chars().count() + 1,
+            ),
+            None => (
+                input.location_line(),
+                input.get_utf8_column() + skipped.chars().count(),
+            ),
+        };
+        Self {
+            file: input.extra.file.display().to_string(),
+            offset: input.location_offset() + skipped.len(),
+            line,
+            column,
+            length: rest.trim_end().len(),
+        }
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Implements `PartialEq` and, with the `hash` feature, `Hash` for a node of the AST, its `span` field excluded.
+macro_rules! ignore_span {
+    ($type:ident { $($(#[$meta:meta])* $field:ident),* $(,)? }) => {
+        impl PartialEq for $type {
+            fn eq(&self, other: &Self) -> bool {
+                let Self {
+                    span: _,
+                    $($(#[$meta])* $field,)*
+                } = self;
+                $($(#[$meta])* if *$field != other.$field {
+                    return false;
+                })*
+                true
+            }
+        }
+
+        #[cfg(feature = "hash")]
+        impl std::hash::Hash for $type {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                let Self {
+                    span: _,
+                    $($(#[$meta])* $field,)*
+                } = self;
+                $($(#[$meta])* $field.hash(state);)*
+            }
+        }
+    };
+}
+pub(crate) use ignore_span;
+
+/// Runs the parser and returns its output along with the [Span] of the consumed input.
+pub fn spanned<'a, O, F>(
+    parser: F,
+) -> impl Parser<KconfigInput<'a>, Output = (O, Span), Error = nom::error::Error<KconfigInput<'a>>>
+where
+    F: Parser<KconfigInput<'a>, Output = O, Error = nom::error::Error<KconfigInput<'a>>>,
+{
+    let mut parser = consumed(parser);
+    move |input: KconfigInput<'a>| -> IResult<KconfigInput<'a>, (O, Span)> {
+        let (input, (consumed, output)) = parser.parse(input)?;
+        Ok((input, (output, Span::from_input(&consumed))))
+    }
+}
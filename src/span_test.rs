@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use crate::{kconfig::parse_kconfig, span::Span, Entry, KconfigFile, KconfigInput};
+
+fn location(span: &Span) -> (u32, usize, usize) {
+    (span.line, span.column, span.length)
+}
+
+#[test]
+fn test_span_entries_and_attributes() {
+    let input = r#"# comment
+mainmenu "Linux"
+
+menu "Drivers"
+    visible if EXPERT
+
+config FOO
+	bool "foo"   # trailing comment
+	depends on BAR
+	help
+	  Some help.
+
+endmenu
+"#;
+    let file = KconfigFile::new(PathBuf::from("/linux"), PathBuf::from("drivers/Kconfig"));
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, file)).unwrap();
+
+    let Entry::MainMenu(main_menu) = &kconfig.entries[0] else {
+        unreachable!()
+    };
+    assert_eq!(main_menu.span.file, "drivers/Kconfig");
+    assert_eq!(location(&main_menu.span), (2, 1, 16));
+    assert_eq!(main_menu.span.to_string(), "drivers/Kconfig:2:1");
+
+    let Entry::Menu(menu) = &kconfig.entries[1] else {
+        unreachable!()
+    };
+    assert_eq!((menu.span.line, menu.span.column), (4, 1));
+    assert!(input[menu.span.offset..menu.span.offset + menu.span.length].ends_with("endmenu"));
+    assert_eq!(location(&menu.visible.as_ref().unwrap().span), (5, 5, 17));
+
+    let Entry::Config(config) = &menu.entries[0] else {
+        unreachable!()
+    };
+    assert_eq!((config.span.line, config.span.column), (7, 1));
+    let spans = config
+        .attributes
+        .iter()
+        .map(|a| location(a.span()))
+        .collect::<Vec<_>>();
+    assert_eq!(spans[0], (8, 2, 10));
+    assert_eq!(spans[1], (9, 2, 14));
+    assert_eq!((spans[2].0, spans[2].1), (10, 2));
+    assert!(config.span.contains(config.attributes[1].span().offset));
+}
+
+#[test]
+fn test_span_is_ignored_by_equality() {
+    let first = parse_kconfig(KconfigInput::new_extra(
+        "config A\n    bool\n",
+        Default::default(),
+    ))
+    .unwrap()
+    .1;
+    let second = parse_kconfig(KconfigInput::new_extra(
+        "\n\nconfig A\n bool\n",
+        Default::default(),
+    ))
+    .unwrap()
+    .1;
+    assert_eq!(first, second);
+    let (Entry::Config(a), Entry::Config(b)) = (&first.entries[0], &second.entries[0]) else {
+        unreachable!()
+    };
+    assert_ne!(a.span, b.span);
+    let spans: std::collections::HashSet<&Span> = [&a.span, &b.span, &a.span].into();
+    assert_eq!(spans.len(), 2);
+}
@@ -0,0 +1,718 @@
+//! A flattened view of a parsed [Kconfig](crate::Kconfig).
+//!
+//! The parser returns a tree where configs are nested inside menus, if blocks, choices and sourced files.
+//! A [SymbolTable] walks that tree once and merges every definition of a symbol into a single [SymbolRecord].
+//! Each attribute keeps the dependencies inherited from its surroundings (`if` blocks, `menu ... depends on`, choices and the `depends on` of the entry itself),
+//! the same way the C implementation propagates them to the properties of a symbol.
+//!
+//! ```
+//! use nom_kconfig::{parse_kconfig, KconfigInput, SymbolTable};
+//!
+//! let input = r#"
+//! if NET
+//! config FOO
+//!     bool "foo"
+//!     default y
+//! endif"#;
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let table = SymbolTable::new(&kconfig);
+//! let foo = table.get("CONFIG_FOO").unwrap();
+//! assert_eq!(foo.defaults.len(), 1);
+//! assert_eq!(foo.defaults[0].dependencies.as_ref().unwrap().to_string(), "NET");
+//! ```
+
+use std::collections::HashMap;
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{
+    attribute::{
+        depends_on::DependsOn,
+        option::OptionValues,
+        r#type::{ConfigType, Type},
+        DefaultAttribute, Expression, Imply, Prompt, Range, Select,
+    },
+    entry::{Choice, Config},
+    span::Span,
+    Attribute, Entry, Kconfig, Symbol,
+};
+
+/// The basic types a symbol can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "lowercase")
+)]
+pub enum SymbolType {
+    Bool,
+    Tristate,
+    Int,
+    Hex,
+    String,
+}
+
+impl From<&Type> for SymbolType {
+    fn from(value: &Type) -> Self {
+        match value {
+            Type::Bool(_) | Type::DefBool(_) => SymbolType::Bool,
+            Type::Tristate(_) | Type::DefTristate(_) => SymbolType::Tristate,
+            Type::String(_) => SymbolType::String,
+            Type::Hex(_) => SymbolType::Hex,
+            Type::Int(_) => SymbolType::Int,
+            #[cfg(feature = "kconfiglib")]
+            Type::DefInt(_) => SymbolType::Int,
+            #[cfg(feature = "kconfiglib")]
+            Type::DefHex(_) => SymbolType::Hex,
+            #[cfg(feature = "kconfiglib")]
+            Type::DefString(_) => SymbolType::String,
+        }
+    }
+}
+
+#[cfg(feature = "display")]
+impl std::fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymbolType::Bool => write!(f, "bool"),
+            SymbolType::Tristate => write!(f, "tristate"),
+            SymbolType::Int => write!(f, "int"),
+            SymbolType::Hex => write!(f, "hex"),
+            SymbolType::String => write!(f, "string"),
+        }
+    }
+}
+
+/// An attribute along with the dependencies it inherited from the location where it is defined.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Conditional<T> {
+    pub value: T,
+    /// Dependencies of the definition the attribute belongs to. `None` means the attribute is unconditional.
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub dependencies: Option<Expression>,
+    /// Index of the definition in [SymbolRecord::definitions] (or [ChoiceRecord::definitions]).
+    pub definition: usize,
+}
+
+impl<T> Conditional<T> {
+    fn new(dependencies: &Option<Expression>, definition: usize, value: T) -> Self {
+        Self {
+            value,
+            dependencies: dependencies.clone(),
+            definition,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub enum DefinitionKind {
+    Config,
+    MenuConfig,
+    Choice,
+    #[cfg(feature = "kconfiglib")]
+    ConfigDefault,
+}
+
+/// A place where a symbol is defined.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct Definition {
+    pub kind: DefinitionKind,
+    /// The file containing the definition, as stored in [Kconfig::file].
+    pub file: String,
+    /// Dependencies inherited from the enclosing blocks, combined with the `depends on` of the definition.
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub dependencies: Option<Expression>,
+    /// The `depends on` attributes of the definition itself, with their location.
+    pub depends_on: Vec<DependsOn>,
+    /// Location of the `config`, `menuconfig`, `choice` or `configdefault` entry.
+    pub span: Span,
+}
+
+/// A reverse dependency created by a `select` or an `imply` on another symbol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct ReverseDependency {
+    /// The symbol that selects or implies.
+    pub symbol: String,
+    /// The `if` condition of the `select`/`imply`.
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub r#if: Option<Expression>,
+    /// Dependencies of the definition containing the `select`/`imply`.
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub dependencies: Option<Expression>,
+    /// Location of the `select`/`imply` attribute.
+    pub span: Span,
+}
+
+/// Everything known about a symbol, merged from all its definitions.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct SymbolRecord {
+    pub name: String,
+    pub definitions: Vec<Definition>,
+    pub types: Vec<Conditional<SymbolType>>,
+    /// Prompts, including the ones declared with the type (`bool "prompt"`).
+    pub prompts: Vec<Conditional<Prompt>>,
+    /// Defaults, including the ones declared with `def_bool`, `def_tristate`...
+    pub defaults: Vec<Conditional<DefaultAttribute>>,
+    pub selects: Vec<Conditional<Select>>,
+    pub implies: Vec<Conditional<Imply>>,
+    pub ranges: Vec<Conditional<Range>>,
+    pub help: Vec<Conditional<String>>,
+    pub options: Vec<Conditional<OptionValues>>,
+    /// Symbols selecting this symbol.
+    pub selected_by: Vec<ReverseDependency>,
+    /// Symbols implying this symbol.
+    pub implied_by: Vec<ReverseDependency>,
+    /// Index of the choice in [SymbolTable::choices] if the symbol is a choice value.
+    pub choice: Option<usize>,
+    /// `true` when the symbol is the one enabling modules (`modules` attribute or `option modules`).
+    pub modules: bool,
+    pub transitional: bool,
+}
+
+impl SymbolRecord {
+    /// The type of the symbol, the first declared one wins.
+    pub fn r#type(&self) -> Option<SymbolType> {
+        self.types.first().map(|t| t.value)
+    }
+
+    /// The dependencies of the symbol: definitions are joined with `||` like the C implementation does.
+    pub fn dependencies(&self) -> Option<Expression> {
+        or_dependencies(
+            self.definitions
+                .iter()
+                .filter(|d| matches!(d.kind, DefinitionKind::Config | DefinitionKind::MenuConfig))
+                .map(|d| d.dependencies.clone()),
+        )
+    }
+
+    /// Returns true if at least one definition is a `menuconfig`.
+    pub fn is_menuconfig(&self) -> bool {
+        self.definitions
+            .iter()
+            .any(|d| d.kind == DefinitionKind::MenuConfig)
+    }
+}
+
+/// A `choice` block and its attributes.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct ChoiceRecord {
+    #[cfg_attr(
+        any(feature = "serialize", feature = "deserialize"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub name: Option<String>,
+    pub definitions: Vec<Definition>,
+    pub types: Vec<Conditional<SymbolType>>,
+    pub prompts: Vec<Conditional<Prompt>>,
+    pub defaults: Vec<Conditional<DefaultAttribute>>,
+    pub help: Vec<Conditional<String>>,
+    pub optional: bool,
+    /// Symbols of the choice, in definition order.
+    pub members: Vec<String>,
+}
+
+impl ChoiceRecord {
+    /// The type of the choice. If none is declared, the type of the first typed member is used by the caller.
+    pub fn r#type(&self) -> Option<SymbolType> {
+        self.types.first().map(|t| t.value)
+    }
+
+    pub fn dependencies(&self) -> Option<Expression> {
+        or_dependencies(self.definitions.iter().map(|d| d.dependencies.clone()))
+    }
+}
+
+/// Every symbol of a [Kconfig] tree, indexed by name.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct SymbolTable {
+    symbols: Vec<SymbolRecord>,
+    choices: Vec<ChoiceRecord>,
+    /// Symbols that are selected or implied but never defined. They only carry reverse dependencies.
+    undefined: Vec<SymbolRecord>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    index: HashMap<String, usize>,
+}
+
+impl SymbolTable {
+    pub fn new(kconfig: &Kconfig) -> Self {
+        let mut table = Self::default();
+        table.visit_kconfig(kconfig, &None, None);
+        table.link_reverse_dependencies();
+        table
+    }
+
+    /// Looks up a symbol. The `CONFIG_` prefix is optional: `get("CONFIG_FOO")` returns the symbol `FOO` unless a symbol is literally named `CONFIG_FOO`.
+    pub fn get(&self, name: &str) -> Option<&SymbolRecord> {
+        self.position(name).map(|i| &self.symbols[i])
+    }
+
+    /// Returns true if the symbol is defined. Like [SymbolTable::get], the `CONFIG_` prefix is optional.
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// Defined symbols in the order of their first definition.
+    pub fn symbols(&self) -> impl Iterator<Item = &SymbolRecord> {
+        self.symbols.iter()
+    }
+
+    /// Symbols that are selected or implied but never defined, with their reverse dependencies.
+    pub fn undefined(&self) -> impl Iterator<Item = &SymbolRecord> {
+        self.undefined.iter()
+    }
+
+    pub fn choices(&self) -> &[ChoiceRecord] {
+        &self.choices
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.index
+            .get(name)
+            .or_else(|| {
+                name.strip_prefix("CONFIG_")
+                    .and_then(|stripped| self.index.get(stripped))
+            })
+            .copied()
+    }
+
+    fn record_mut(&mut self, name: &str) -> &mut SymbolRecord {
+        let index = match self.index.get(name) {
+            Some(index) => *index,
+            None => {
+                self.symbols.push(SymbolRecord {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.index.insert(name.to_string(), self.symbols.len() - 1);
+                self.symbols.len() - 1
+            }
+        };
+        &mut self.symbols[index]
+    }
+
+    fn visit_kconfig(
+        &mut self,
+        kconfig: &Kconfig,
+        dependencies: &Option<Expression>,
+        choice: Option<usize>,
+    ) {
+        self.visit_entries(&kconfig.entries, &kconfig.file, dependencies, choice)
+    }
+
+    fn visit_entries(
+        &mut self,
+        entries: &[Entry],
+        file: &str,
+        dependencies: &Option<Expression>,
+        choice: Option<usize>,
+    ) {
+        for entry in entries {
+            match entry {
+                Entry::Config(config) => {
+                    self.visit_config(config, DefinitionKind::Config, file, dependencies, choice)
+                }
+                Entry::MenuConfig(config) => self.visit_config(
+                    config,
+                    DefinitionKind::MenuConfig,
+                    file,
+                    dependencies,
+                    choice,
+                ),
+                Entry::Choice(c) => self.visit_choice(c, file, dependencies),
+                Entry::Menu(menu) => {
+                    let dependencies = and_depends_on(dependencies.clone(), &menu.depends_on);
+                    self.visit_entries(&menu.entries, file, &dependencies, None)
+                }
+                Entry::If(r#if) => {
+                    let dependencies =
+                        Expression::and_opt(dependencies.clone(), Some(r#if.condition.clone()));
+                    self.visit_entries(&r#if.entries, file, &dependencies, choice)
+                }
+                Entry::Source(source) => source
+                    .kconfigs
+                    .iter()
+                    .for_each(|k| self.visit_kconfig(k, dependencies, choice)),
+                #[cfg(feature = "kconfiglib")]
+                Entry::OSource(source) | Entry::RSource(source) | Entry::OrSource(source) => source
+                    .kconfigs
+                    .iter()
+                    .for_each(|k| self.visit_kconfig(k, dependencies, choice)),
+                #[cfg(feature = "kconfiglib")]
+                Entry::ConfigDefault(config_default) => {
+                    let record = self.record_mut(&config_default.symbol);
+                    record.definitions.push(Definition {
+                        kind: DefinitionKind::ConfigDefault,
+                        file: file.to_string(),
+                        dependencies: dependencies.clone(),
+                        depends_on: vec![],
+                        span: config_default.span.clone(),
+                    });
+                    let definition = record.definitions.len() - 1;
+                    record.defaults.push(Conditional {
+                        value: config_default.default.clone(),
+                        dependencies: dependencies.clone(),
+                        definition,
+                    });
+                }
+                Entry::Comment(_)
+                | Entry::VariableAssignment(_)
+                | Entry::FunctionCall(_)
+                | Entry::Function(_)
+                | Entry::MainMenu(_) => (),
+            }
+        }
+    }
+
+    fn visit_config(
+        &mut self,
+        config: &Config,
+        kind: DefinitionKind,
+        file: &str,
+        dependencies: &Option<Expression>,
+        choice: Option<usize>,
+    ) {
+        let depends_on = config
+            .attributes
+            .iter()
+            .filter_map(|a| match a {
+                Attribute::DependsOn(d) => Some(d.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let dependencies = and_depends_on(dependencies.clone(), &depends_on);
+
+        if let Some(choice) = choice {
+            let members = &mut self.choices[choice].members;
+            if !members.contains(&config.symbol) {
+                members.push(config.symbol.clone());
+            }
+        }
+
+        let record = self.record_mut(&config.symbol);
+        if choice.is_some() {
+            record.choice = choice;
+        }
+        record.definitions.push(Definition {
+            kind,
+            file: file.to_string(),
+            dependencies: dependencies.clone(),
+            depends_on,
+            span: config.span.clone(),
+        });
+        let definition = record.definitions.len() - 1;
+
+        for attribute in &config.attributes {
+            match attribute {
+                Attribute::Type(config_type) => {
+                    let (r#type, prompt, default) = split_type(config_type);
+                    record
+                        .types
+                        .push(Conditional::new(&dependencies, definition, r#type));
+                    if let Some(prompt) = prompt {
+                        record
+                            .prompts
+                            .push(Conditional::new(&dependencies, definition, prompt));
+                    }
+                    if let Some(default) = default {
+                        record
+                            .defaults
+                            .push(Conditional::new(&dependencies, definition, default));
+                    }
+                }
+                Attribute::Prompt(prompt) => {
+                    record
+                        .prompts
+                        .push(Conditional::new(&dependencies, definition, prompt.clone()))
+                }
+                Attribute::Default(default) => record.defaults.push(Conditional::new(
+                    &dependencies,
+                    definition,
+                    default.clone(),
+                )),
+                Attribute::Select(select) => {
+                    record
+                        .selects
+                        .push(Conditional::new(&dependencies, definition, select.clone()))
+                }
+                Attribute::Imply(imply) => {
+                    record
+                        .implies
+                        .push(Conditional::new(&dependencies, definition, imply.clone()))
+                }
+                Attribute::Range(range) => {
+                    record
+                        .ranges
+                        .push(Conditional::new(&dependencies, definition, range.clone()))
+                }
+                Attribute::Help(help, _) => {
+                    record
+                        .help
+                        .push(Conditional::new(&dependencies, definition, help.clone()))
+                }
+                Attribute::Modules(_) => record.modules = true,
+                Attribute::Option(OptionValues::Modules, _) => record.modules = true,
+                Attribute::Option(option, _) => {
+                    record
+                        .options
+                        .push(Conditional::new(&dependencies, definition, option.clone()))
+                }
+                Attribute::Transitional(_) => record.transitional = true,
+                Attribute::DependsOn(_)
+                | Attribute::Optional(_)
+                | Attribute::Visible(_)
+                | Attribute::Requires(..) => (),
+            }
+        }
+    }
+
+    fn visit_choice(&mut self, choice: &Choice, file: &str, dependencies: &Option<Expression>) {
+        let depends_on = choice
+            .options
+            .iter()
+            .filter_map(|a| match a {
+                Attribute::DependsOn(d) => Some(d.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let dependencies = and_depends_on(dependencies.clone(), &depends_on);
+
+        #[cfg(feature = "named-choice")]
+        let name = choice.name.clone();
+        #[cfg(not(feature = "named-choice"))]
+        let name: Option<String> = None;
+
+        // Named choices can be defined several times, they are merged like symbols.
+        let index = match name
+            .as_ref()
+            .and_then(|n| self.choices.iter().position(|c| c.name.as_ref() == Some(n)))
+        {
+            Some(index) => index,
+            None => {
+                self.choices.push(ChoiceRecord {
+                    name,
+                    ..Default::default()
+                });
+                self.choices.len() - 1
+            }
+        };
+
+        let record = &mut self.choices[index];
+        record.definitions.push(Definition {
+            kind: DefinitionKind::Choice,
+            file: file.to_string(),
+            dependencies: dependencies.clone(),
+            depends_on,
+            span: choice.span.clone(),
+        });
+        let definition = record.definitions.len() - 1;
+        for attribute in &choice.options {
+            match attribute {
+                Attribute::Type(config_type) => {
+                    let (r#type, prompt, default) = split_type(config_type);
+                    record
+                        .types
+                        .push(Conditional::new(&dependencies, definition, r#type));
+                    if let Some(prompt) = prompt {
+                        record
+                            .prompts
+                            .push(Conditional::new(&dependencies, definition, prompt));
+                    }
+                    if let Some(default) = default {
+                        record
+                            .defaults
+                            .push(Conditional::new(&dependencies, definition, default));
+                    }
+                }
+                Attribute::Prompt(prompt) => {
+                    record
+                        .prompts
+                        .push(Conditional::new(&dependencies, definition, prompt.clone()))
+                }
+                Attribute::Default(default) => record.defaults.push(Conditional::new(
+                    &dependencies,
+                    definition,
+                    default.clone(),
+                )),
+                Attribute::Help(help, _) => {
+                    record
+                        .help
+                        .push(Conditional::new(&dependencies, definition, help.clone()))
+                }
+                Attribute::Optional(_) => record.optional = true,
+                _ => (),
+            }
+        }
+
+        self.visit_entries(&choice.entries, file, &dependencies, Some(index));
+    }
+
+    fn link_reverse_dependencies(&mut self) {
+        let mut selected_by = vec![];
+        let mut implied_by = vec![];
+        for record in &self.symbols {
+            for select in &record.selects {
+                selected_by.push((
+                    select.value.symbol.clone(),
+                    ReverseDependency {
+                        symbol: record.name.clone(),
+                        r#if: select.value.r#if.clone(),
+                        dependencies: select.dependencies.clone(),
+                        span: select.value.span.clone(),
+                    },
+                ));
+            }
+            for imply in &record.implies {
+                if let Symbol::NonConstant(name) = &imply.value.symbol {
+                    implied_by.push((
+                        name.clone(),
+                        ReverseDependency {
+                            symbol: record.name.clone(),
+                            r#if: imply.value.r#if.clone(),
+                            dependencies: imply.dependencies.clone(),
+                            span: imply.value.span.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+        let mut undefined = HashMap::new();
+        for (name, reverse) in selected_by {
+            self.target_mut(&mut undefined, &name)
+                .selected_by
+                .push(reverse);
+        }
+        for (name, reverse) in implied_by {
+            self.target_mut(&mut undefined, &name)
+                .implied_by
+                .push(reverse);
+        }
+    }
+
+    /// The record of a selected or implied symbol. Symbols that are not defined go to [SymbolTable::undefined].
+    fn target_mut(
+        &mut self,
+        undefined: &mut HashMap<String, usize>,
+        name: &str,
+    ) -> &mut SymbolRecord {
+        if let Some(index) = self.index.get(name) {
+            return &mut self.symbols[*index];
+        }
+        let index = *undefined.entry(name.to_string()).or_insert_with(|| {
+            self.undefined.push(SymbolRecord {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            self.undefined.len() - 1
+        });
+        &mut self.undefined[index]
+    }
+}
+
+impl From<&Kconfig> for SymbolTable {
+    fn from(kconfig: &Kconfig) -> Self {
+        Self::new(kconfig)
+    }
+}
+
+/// `def_bool y if X` is a shortcut for `bool` + `default y if X` and `bool "prompt" if X` for `bool` + `prompt "prompt" if X`.
+pub(crate) fn split_type(
+    config_type: &ConfigType,
+) -> (SymbolType, Option<Prompt>, Option<DefaultAttribute>) {
+    let r#if = config_type.r#if.clone();
+    let (prompt, default) = match &config_type.r#type {
+        Type::Bool(p) | Type::Tristate(p) | Type::String(p) | Type::Hex(p) | Type::Int(p) => (
+            p.as_ref().map(|prompt| Prompt {
+                prompt: prompt.clone(),
+                r#if,
+                span: config_type.span.clone(),
+            }),
+            None,
+        ),
+        Type::DefBool(e) | Type::DefTristate(e) => (
+            None,
+            Some(DefaultAttribute {
+                expression: e.clone(),
+                r#if,
+                span: config_type.span.clone(),
+            }),
+        ),
+        #[cfg(feature = "kconfiglib")]
+        Type::DefInt(e) | Type::DefHex(e) | Type::DefString(e) => (
+            None,
+            Some(DefaultAttribute {
+                expression: e.clone(),
+                r#if,
+                span: config_type.span.clone(),
+            }),
+        ),
+    };
+    ((&config_type.r#type).into(), prompt, default)
+}
+
+/// `depends on A if B` means `A` is only required when `B` is set, in other words `!B || A`.
+pub(crate) fn and_depends_on(
+    dependencies: Option<Expression>,
+    depends_on: &[DependsOn],
+) -> Option<Expression> {
+    depends_on.iter().fold(dependencies, |acc, d| {
+        let expression = match &d.r#if {
+            Some(condition) => condition.clone().negate().or(d.expression.clone()),
+            None => d.expression.clone(),
+        };
+        Expression::and_opt(acc, Some(expression))
+    })
+}
+
+fn or_dependencies(dependencies: impl Iterator<Item = Option<Expression>>) -> Option<Expression> {
+    let mut result: Option<Expression> = None;
+    for dependency in dependencies {
+        match dependency {
+            // an unconditional definition makes the whole symbol unconditional
+            None => return None,
+            Some(d) => {
+                result = Some(match result {
+                    Some(r) => r.or(d),
+                    None => d,
+                })
+            }
+        }
+    }
+    result
+}
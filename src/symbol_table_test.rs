@@ -0,0 +1,137 @@
+use crate::{
+    attribute::Expression,
+    lib_test::parse,
+    symbol_table::{DefinitionKind, SymbolTable, SymbolType},
+};
+
+fn to_string(expression: &Option<Expression>) -> Option<String> {
+    expression.as_ref().map(|e| e.to_string())
+}
+
+#[test]
+fn test_symbol_table_merges_definitions() {
+    let kconfig = parse(
+        r#"
+config FOO
+    bool "foo"
+    select BAR
+
+menuconfig FOO
+    default y
+    help
+      Some help
+"#,
+    );
+    let table = SymbolTable::new(&kconfig);
+    let foo = table.get("FOO").unwrap();
+    assert_eq!(foo.definitions.len(), 2);
+    assert_eq!(foo.definitions[1].kind, DefinitionKind::MenuConfig);
+    assert_eq!(foo.r#type(), Some(SymbolType::Bool));
+    assert_eq!(foo.prompts[0].value.prompt, "foo");
+    assert_eq!(foo.defaults[0].definition, 1);
+    assert_eq!(foo.help[0].value, "Some help");
+    assert!(foo.is_menuconfig());
+
+    assert!(table.contains("CONFIG_FOO"));
+    assert_eq!(table.get("BAR"), None);
+    assert!(!table.contains("BAR"));
+    assert_eq!(table.len(), 1);
+    let bar = table.undefined().next().unwrap();
+    assert_eq!(bar.name, "BAR");
+    assert_eq!(bar.selected_by[0].symbol, "FOO");
+    assert!(bar.definitions.is_empty());
+}
+
+#[test]
+fn test_symbol_table_inherited_dependencies() {
+    let kconfig = parse(
+        r#"
+menu "Network"
+    depends on NET
+
+if PCI
+config FOO
+    def_bool y if ACPI
+    depends on X86 || ARM
+    imply BAR if BAZ
+endif
+endmenu
+"#,
+    );
+    let table = SymbolTable::new(&kconfig);
+    let foo = table.get("FOO").unwrap();
+    assert_eq!(
+        to_string(&foo.definitions[0].dependencies),
+        Some("NET && PCI && (X86 || ARM)".to_string())
+    );
+    assert_eq!(foo.defaults[0].value.expression.to_string(), "y");
+    assert_eq!(
+        to_string(&foo.defaults[0].value.r#if),
+        Some("ACPI".to_string())
+    );
+    assert_eq!(
+        to_string(&foo.defaults[0].dependencies),
+        Some("NET && PCI && (X86 || ARM)".to_string())
+    );
+
+    let bar = table.undefined().next().unwrap();
+    assert_eq!(bar.implied_by[0].symbol, "FOO");
+    assert_eq!(to_string(&bar.implied_by[0].r#if), Some("BAZ".to_string()));
+}
+
+#[test]
+fn test_symbol_table_choice() {
+    let kconfig = parse(
+        r#"
+choice
+    prompt "Timer frequency"
+    default HZ_250
+    depends on TIMERS
+
+config HZ_100
+    bool "100 HZ"
+
+config HZ_250
+    bool "250 HZ"
+
+endchoice
+"#,
+    );
+    let table = SymbolTable::new(&kconfig);
+    assert_eq!(table.choices().len(), 1);
+    let choice = &table.choices()[0];
+    assert_eq!(choice.members, vec!["HZ_100", "HZ_250"]);
+    assert_eq!(choice.defaults[0].value.expression.to_string(), "HZ_250");
+    assert_eq!(
+        to_string(&choice.dependencies()),
+        Some("TIMERS".to_string())
+    );
+
+    let hz_100 = table.get("HZ_100").unwrap();
+    assert_eq!(hz_100.choice, Some(0));
+    assert_eq!(
+        to_string(&hz_100.dependencies()),
+        Some("TIMERS".to_string())
+    );
+}
+
+#[test]
+fn test_symbol_table_dependencies_are_or_ed() {
+    let kconfig = parse(
+        r#"
+if A
+config FOO
+    bool
+endif
+if B
+config FOO
+    bool
+endif
+"#,
+    );
+    let table = SymbolTable::new(&kconfig);
+    assert_eq!(
+        to_string(&table.get("FOO").unwrap().dependencies()),
+        Some("A || B".to_string())
+    );
+}
@@ -0,0 +1,245 @@
+//! Undefined and unused symbols of a source tree, like `scripts/checkkconfigsymbols.py`.
+//!
+//! Symbols are defined by the parsed [Kconfig]. They are referenced by other Kconfig symbols (`depends on`, `select`, `default`...)
+//! and by the sources: `CONFIG_FOO` in C files, Makefiles and device trees, `IS_ENABLED(CONFIG_FOO)` included.
+//! `CONFIG_FOO_MODULE` is a reference to `FOO` when `FOO_MODULE` does not exist.
+//!
+//! ```
+//! use std::path::Path;
+//! use nom_kconfig::{parse_kconfig, symbol_usage::SymbolUsage, KconfigInput};
+//!
+//! let input = "config USB\n    bool \"usb\"\n\nconfig PCI\n    bool \"pci\"\n";
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(input, Default::default())).unwrap();
+//! let mut usage = SymbolUsage::new(&kconfig);
+//! usage.scan_file(Path::new("drivers/usb.c"), "#if IS_ENABLED(CONFIG_USB) && defined(CONFIG_USB_LEGACY)\n");
+//! assert_eq!(usage.undefined()[0].0, "USB_LEGACY");
+//! assert_eq!(usage.undefined()[0].1[0].to_string(), "drivers/usb.c:1:39");
+//! assert_eq!(usage.unused(), vec!["PCI"]);
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::Path,
+};
+
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+use crate::{span::Span, symbol_table::SymbolTable, Kconfig, Symbol};
+
+/// Extensions of the files scanned by [SymbolUsage::scan_tree].
+pub const SOURCE_EXTENSIONS: [&str; 9] = ["c", "h", "S", "s", "lds", "dts", "dtsi", "dtso", "mk"];
+
+/// A place where a symbol is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+pub struct SymbolReference {
+    pub file: String,
+    /// Line number, starting at 1.
+    pub line: u32,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+}
+
+impl From<&Span> for SymbolReference {
+    fn from(span: &Span) -> Self {
+        Self {
+            file: span.file.clone(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+}
+
+impl std::fmt::Display for SymbolReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Defined symbols and their references.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SymbolUsage {
+    prefix: String,
+    defined: HashSet<String>,
+    references: BTreeMap<String, Vec<SymbolReference>>,
+}
+
+impl SymbolUsage {
+    /// Collects the symbols defined by the tree and the references between them.
+    pub fn new(kconfig: &Kconfig) -> Self {
+        Self::from_table(&SymbolTable::new(kconfig))
+    }
+
+    pub fn from_table(table: &SymbolTable) -> Self {
+        let mut usage = Self {
+            prefix: "CONFIG_".to_string(),
+            ..Default::default()
+        };
+        usage.defined = table.symbols().map(|record| record.name.clone()).collect();
+
+        let mut add = |name: &str, span: &Span| {
+            let reference = SymbolReference::from(span);
+            let references = usage.references.entry(name.to_string()).or_default();
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        };
+        for record in table.symbols() {
+            for definition in &record.definitions {
+                for name in definition.dependencies.iter().flat_map(|e| e.symbols()) {
+                    add(name, &definition.span);
+                }
+            }
+            for select in &record.selects {
+                add(&select.value.symbol, &select.value.span);
+                for name in select.value.r#if.iter().flat_map(|e| e.symbols()) {
+                    add(name, &select.value.span);
+                }
+            }
+            for imply in &record.implies {
+                if let Symbol::NonConstant(name) = &imply.value.symbol {
+                    add(name, &imply.value.span);
+                }
+                for name in imply.value.r#if.iter().flat_map(|e| e.symbols()) {
+                    add(name, &imply.value.span);
+                }
+            }
+            for default in &record.defaults {
+                let default = &default.value;
+                for name in default.expression.symbols() {
+                    add(name, &default.span);
+                }
+                for name in default.r#if.iter().flat_map(|e| e.symbols()) {
+                    add(name, &default.span);
+                }
+            }
+            for range in &record.ranges {
+                for name in range.value.r#if.iter().flat_map(|e| e.symbols()) {
+                    add(name, &range.value.span);
+                }
+            }
+        }
+        for choice in table.choices() {
+            for definition in &choice.definitions {
+                for name in definition.dependencies.iter().flat_map(|e| e.symbols()) {
+                    add(name, &definition.span);
+                }
+            }
+        }
+        usage
+    }
+
+    /// Changes the prefix of the symbols in the sources, `CONFIG_` by default.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Records the references of a source file.
+    pub fn scan_file(&mut self, path: &Path, content: &str) {
+        let file = path.display().to_string();
+        for (line_index, line) in content.lines().enumerate() {
+            let mut start = 0;
+            while let Some(found) = line[start..].find(&self.prefix) {
+                let position = start + found;
+                let name_start = position + self.prefix.len();
+                let name_end = line[name_start..]
+                    .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+                    .map_or(line.len(), |end| name_start + end);
+                start = name_end.max(position + 1);
+                // CONFIG_ must not be the end of another identifier, like MY_CONFIG_FOO
+                let preceded = line[..position]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+                let name = &line[name_start..name_end];
+                if preceded || name.is_empty() {
+                    continue;
+                }
+                let name = match name.strip_suffix("_MODULE") {
+                    Some(base) if !self.defined.contains(name) => base,
+                    _ => name,
+                };
+                self.references
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(SymbolReference {
+                        file: file.clone(),
+                        line: line_index as u32 + 1,
+                        column: line[..position].chars().count() + 1,
+                    });
+            }
+        }
+    }
+
+    /// Scans every source file under `directory`: files with one of the [SOURCE_EXTENSIONS], `Makefile*` and `Kbuild*`.
+    /// Hidden files and directories are skipped. Paths of the references are relative to `directory`.
+    pub fn scan_tree(&mut self, directory: &Path) -> io::Result<()> {
+        let mut directories = vec![directory.to_path_buf()];
+        while let Some(current) = directories.pop() {
+            let mut entries = fs::read_dir(&current)?.collect::<Result<Vec<_>, _>>()?;
+            entries.sort_by_key(|entry| entry.file_name());
+            for entry in entries {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') {
+                    continue;
+                }
+                if entry.file_type()?.is_dir() {
+                    directories.push(path);
+                } else if is_source_file(&name) {
+                    // binary files and other encodings are not sources
+                    if let Ok(content) = fs::read_to_string(&path) {
+                        let relative = path.strip_prefix(directory).unwrap_or(&path);
+                        self.scan_file(relative, &content);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.defined.contains(name)
+    }
+
+    /// The references of a symbol, the prefix being optional.
+    pub fn references(&self, name: &str) -> &[SymbolReference] {
+        let name = name.strip_prefix(self.prefix.as_str()).unwrap_or(name);
+        self.references.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Symbols referenced but never defined, sorted by name.
+    pub fn undefined(&self) -> Vec<(&str, &[SymbolReference])> {
+        self.references
+            .iter()
+            .filter(|(name, _)| !self.defined.contains(*name))
+            .map(|(name, references)| (name.as_str(), references.as_slice()))
+            .collect()
+    }
+
+    /// Symbols defined but never referenced, sorted by name.
+    pub fn unused(&self) -> Vec<&str> {
+        let mut unused: Vec<&str> = self
+            .defined
+            .iter()
+            .filter(|name| !self.references.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        unused.sort_unstable();
+        unused
+    }
+}
+
+fn is_source_file(name: &str) -> bool {
+    name.starts_with("Makefile")
+        || name.starts_with("Kbuild")
+        || name
+            .rsplit_once('.')
+            .is_some_and(|(_, extension)| SOURCE_EXTENSIONS.contains(&extension))
+}
@@ -0,0 +1,94 @@
+use std::{fs, path::Path};
+
+use crate::{lib_test::parse, symbol_usage::SymbolUsage};
+
+fn usage(input: &str) -> SymbolUsage {
+    let kconfig = parse(input);
+    SymbolUsage::new(&kconfig)
+}
+
+#[test]
+fn test_symbol_usage_kconfig_references() {
+    let usage = usage(
+        r#"config A
+    bool "a"
+    depends on B
+    select C
+    imply D if E
+    default F
+
+config B
+    bool
+
+config C
+    bool
+
+config UNUSED
+    int
+    range 0 10 if MISSING
+"#,
+    );
+    assert!(usage.is_defined("A"));
+    assert!(!usage.is_defined("MISSING"));
+    let undefined: Vec<&str> = usage.undefined().iter().map(|(name, _)| *name).collect();
+    assert_eq!(undefined, vec!["D", "E", "F", "MISSING"]);
+    assert_eq!(usage.unused(), vec!["A", "UNUSED"]);
+    assert_eq!(usage.references("CONFIG_C")[0].to_string(), ":4:5");
+}
+
+#[test]
+fn test_symbol_usage_scan_file() {
+    let mut usage = usage("config USB\n    bool \"usb\"\n");
+    usage.scan_file(
+        Path::new("Makefile"),
+        "obj-$(CONFIG_USB) += usb.o\nobj-$(CONFIG_USB_MODULE) += usb.o\nMY_CONFIG_NOT_A_SYMBOL := y\nCONFIG_ := n\n",
+    );
+    assert_eq!(usage.references("USB").len(), 2);
+    assert_eq!(usage.references("USB")[1].to_string(), "Makefile:2:7");
+    assert!(usage.undefined().is_empty());
+    assert!(usage.unused().is_empty());
+}
+
+#[test]
+fn test_symbol_usage_with_prefix() {
+    let mut usage = usage("config FOO\n    bool\n").with_prefix("CFG_");
+    usage.scan_file(Path::new("main.c"), "#ifdef CFG_FOO\n#ifdef CONFIG_BAR\n");
+    assert_eq!(usage.references("CFG_FOO").len(), 1);
+    assert!(usage.undefined().is_empty());
+}
+
+#[test]
+fn test_symbol_usage_scan_tree() {
+    let directory = std::env::temp_dir().join(format!("nom-kconfig-usage-{}", std::process::id()));
+    fs::create_dir_all(directory.join("drivers")).unwrap();
+    fs::create_dir_all(directory.join(".git")).unwrap();
+    fs::write(
+        directory.join("drivers/net.c"),
+        "#if IS_ENABLED(CONFIG_NET)\n",
+    )
+    .unwrap();
+    fs::write(
+        directory.join("drivers/Kbuild"),
+        "obj-$(CONFIG_GONE) += gone.o\n",
+    )
+    .unwrap();
+    fs::write(directory.join("drivers/README"), "CONFIG_IGNORED\n").unwrap();
+    fs::write(directory.join(".git/config.h"), "CONFIG_HIDDEN\n").unwrap();
+
+    let mut usage = usage("config NET\n    bool \"net\"\n");
+    let result = usage.scan_tree(&directory);
+    fs::remove_dir_all(&directory).unwrap();
+    result.unwrap();
+
+    let undefined = usage.undefined();
+    assert_eq!(undefined.len(), 1);
+    assert_eq!(undefined[0].0, "GONE");
+    assert_eq!(
+        undefined[0].1[0].to_string(),
+        format!("{}:1:7", Path::new("drivers").join("Kbuild").display())
+    );
+    assert_eq!(
+        usage.references("NET")[0].to_string(),
+        "drivers/net.c:1:16".replace('/', std::path::MAIN_SEPARATOR_STR)
+    );
+}
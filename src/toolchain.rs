@@ -0,0 +1,236 @@
+//! Answers the toolchain probes of `scripts/Kconfig.include` without running a compiler.
+//!
+//! The kernel defines `cc-option`, `ld-option`, `as-instr`, `cc-name`, `cc-version`... as macros calling `$(shell,...)`.
+//! When a [ToolchainProfile] is set, the [preprocessor](crate::preprocess) answers them from the profile instead,
+//! whatever their definition is. `$(success,...)`, `$(failure,...)` and `$(if-success,...)` are answered as well
+//! for the commands the profile can evaluate: `test` comparisons, the `asm goto` probes and `command -v`,
+//! which only finds the [tools](ToolchainProfile::tools) of the profile.
+//! Other commands are expanded normally, see [ShellPolicy](crate::shell::ShellPolicy).
+//!
+//! ```
+//! use std::path::PathBuf;
+//! use nom_kconfig::{toolchain::ToolchainProfile, KconfigFile};
+//!
+//! let profile = ToolchainProfile::gcc(130200).with_cc_flags(["-mno-red-zone"]);
+//! let kconfig_file = KconfigFile::new(PathBuf::from("/linux"), PathBuf::from("Kconfig")).with_toolchain(profile);
+//! let mut preprocessor = kconfig_file.preprocessor();
+//! assert_eq!(preprocessor.expand("$(success,test \"$(cc-name)\" = GCC)"), "y");
+//! assert_eq!(preprocessor.expand("$(cc-version) $(cc-option,-mno-red-zone) $(cc-option,-mfoo)"), "130200 y n");
+//! ```
+
+use std::collections::HashSet;
+
+/// Names of the functions answered by a [ToolchainProfile].
+const FUNCTIONS: [&str; 13] = [
+    "cc-name",
+    "cc-version",
+    "cc-info",
+    "ld-name",
+    "ld-version",
+    "ld-info",
+    "cc-option",
+    "cc-option-bit",
+    "ld-option",
+    "as-instr",
+    "success",
+    "failure",
+    "if-success",
+];
+
+/// Description of a compiler, a linker and an assembler.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolchainProfile {
+    /// The compiler name as printed by `scripts/cc-version.sh`: `GCC` or `Clang`.
+    pub cc_name: String,
+    /// The compiler version as printed by `scripts/cc-version.sh`, `130200` for 13.2.0.
+    pub cc_version: u32,
+    /// The linker name as printed by `scripts/ld-version.sh`: `BFD` or `LLD`.
+    pub ld_name: String,
+    /// The linker version as printed by `scripts/ld-version.sh`, `24000` for 2.40.
+    pub ld_version: u32,
+    /// Flags accepted by the compiler, checked by `$(cc-option)` and `$(cc-option-bit)`.
+    pub cc_flags: HashSet<String>,
+    /// Flags accepted by the linker, checked by `$(ld-option)`.
+    pub ld_flags: HashSet<String>,
+    /// Instructions accepted by the assembler, checked by `$(as-instr)`.
+    pub as_instructions: HashSet<String>,
+    /// Whether the compiler supports `asm goto`.
+    pub asm_goto: bool,
+    /// Programs found by `command -v`, `pahole` for instance.
+    pub tools: HashSet<String>,
+}
+
+impl ToolchainProfile {
+    /// GCC with GNU ld 2.40.
+    pub fn gcc(version: u32) -> Self {
+        Self {
+            cc_name: "GCC".to_string(),
+            cc_version: version,
+            ld_name: "BFD".to_string(),
+            ld_version: 24000,
+            asm_goto: true,
+            ..Default::default()
+        }
+    }
+
+    /// Clang with LLD of the same version.
+    pub fn clang(version: u32) -> Self {
+        Self {
+            cc_name: "Clang".to_string(),
+            cc_version: version,
+            ld_name: "LLD".to_string(),
+            ld_version: version,
+            asm_goto: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_linker<S: Into<String>>(mut self, name: S, version: u32) -> Self {
+        self.ld_name = name.into();
+        self.ld_version = version;
+        self
+    }
+
+    pub fn with_cc_flags<I: IntoIterator<Item = S>, S: Into<String>>(mut self, flags: I) -> Self {
+        self.cc_flags.extend(flags.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_ld_flags<I: IntoIterator<Item = S>, S: Into<String>>(mut self, flags: I) -> Self {
+        self.ld_flags.extend(flags.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_as_instructions<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        instructions: I,
+    ) -> Self {
+        self.as_instructions
+            .extend(instructions.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_asm_goto(mut self, asm_goto: bool) -> Self {
+        self.asm_goto = asm_goto;
+        self
+    }
+
+    pub fn with_tools<I: IntoIterator<Item = S>, S: Into<String>>(mut self, tools: I) -> Self {
+        self.tools.extend(tools.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns `true` if the profile may answer the function.
+    pub fn handles(&self, name: &str) -> bool {
+        FUNCTIONS.contains(&name)
+    }
+
+    /// Evaluates a function, `arguments` are already expanded.
+    /// Returns `None` if the profile cannot answer, the function is then expanded normally.
+    pub fn call<S: AsRef<str>>(&self, name: &str, arguments: &[S]) -> Option<String> {
+        let arguments: Vec<&str> = arguments.iter().map(|a| a.as_ref()).collect();
+        let supported = |flags: &HashSet<String>, argument: &str| {
+            argument.split_whitespace().all(|flag| flags.contains(flag))
+        };
+        let result = match (name, arguments.as_slice()) {
+            ("cc-name", []) => self.cc_name.clone(),
+            ("cc-version", []) => self.cc_version.to_string(),
+            ("cc-info", []) => format!("{} {}", self.cc_name, self.cc_version),
+            ("ld-name", []) => self.ld_name.clone(),
+            ("ld-version", []) => self.ld_version.to_string(),
+            ("ld-info", []) => format!("{} {}", self.ld_name, self.ld_version),
+            ("cc-option", [flags]) => yes_no(supported(&self.cc_flags, flags)),
+            ("cc-option-bit", [flags]) => match supported(&self.cc_flags, flags) {
+                true => flags.to_string(),
+                false => String::new(),
+            },
+            ("ld-option", [flags]) => yes_no(supported(&self.ld_flags, flags)),
+            ("as-instr", [instruction] | [instruction, _]) => {
+                yes_no(self.as_instructions.contains(*instruction))
+            }
+            ("success", [command]) => yes_no(self.probe(command)?),
+            ("failure", [command]) => yes_no(!self.probe(command)?),
+            ("if-success", [command, then, otherwise]) => match self.probe(command)? {
+                true => then.to_string(),
+                false => otherwise.to_string(),
+            },
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Tells whether a command would succeed, `None` if it cannot be evaluated.
+    fn probe(&self, command: &str) -> Option<bool> {
+        if command.contains("asm goto") || command.contains("gcc-goto.sh") {
+            return Some(self.asm_goto);
+        }
+        let words = split_words(command)?;
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match words.as_slice() {
+            ["command", "-v", tool] => Some(self.tools.contains(*tool)),
+            ["test", condition @ ..] => test(condition),
+            ["[", condition @ .., "]"] => test(condition),
+            _ => None,
+        }
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    match value {
+        true => "y".to_string(),
+        false => "n".to_string(),
+    }
+}
+
+/// Evaluates the arguments of `test`.
+fn test(condition: &[&str]) -> Option<bool> {
+    let number = |s: &str| s.parse::<i64>().ok();
+    Some(match condition {
+        ["!", condition @ ..] => !test(condition)?,
+        [] => false,
+        [value] => !value.is_empty(),
+        ["-z", value] => value.is_empty(),
+        ["-n", value] => !value.is_empty(),
+        [left, "=" | "==", right] => left == right,
+        [left, "!=", right] => left != right,
+        [left, operator, right] => {
+            let (left, right) = (number(left)?, number(right)?);
+            match *operator {
+                "-eq" => left == right,
+                "-ne" => left != right,
+                "-lt" => left < right,
+                "-le" => left <= right,
+                "-gt" => left > right,
+                "-ge" => left >= right,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Splits a command into words, `None` if it is more than a simple command.
+fn split_words(command: &str) -> Option<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        q if q == c => break,
+                        '$' | '`' if c == '"' => return None,
+                        other => word.push(other),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            '|' | '&' | ';' | '<' | '>' | '(' | ')' | '$' | '`' | '\\' | '*' | '?' => return None,
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Some(words)
+}
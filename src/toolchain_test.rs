@@ -0,0 +1,146 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{
+    attribute::SymbolValue, kconfig::parse_kconfig, resolve::resolve, toolchain::ToolchainProfile,
+    tristate::Tristate, vfs::MemoryFs, KconfigFile, KconfigInput,
+};
+
+const KCONFIG_INCLUDE: &str = r#"comma := ,
+if-success = $(shell,{ $(1); } >/dev/null 2>&1 && echo "$(2)" || echo "$(3)")
+success = $(if-success,$(1),y,n)
+failure = $(if-success,$(1),n,y)
+cc-option = $(success,trap "rm -rf .tmp_$$" EXIT; mkdir .tmp_$$; $(CC) -Werror $(CLANG_FLAGS) $(1) -c -x c /dev/null -o .tmp_$$/tmp.o)
+ld-option = $(success,$(LD) -v $(1))
+as-instr = $(success,printf "%b\n" "$(1)" | $(CC) $(CLANG_FLAGS) $(2) -Wa$(comma)--fatal-warnings -c -x assembler-with-cpp -o /dev/null -)
+$(error-if,$(failure,command -v $(CC)),C compiler '$(CC)' not found)
+cc-info := $(shell,$(srctree)/scripts/cc-version.sh $(CC))
+$(error-if,$(success,test -z "$(cc-info)"),Sorry$(comma) this C compiler is not supported.)
+cc-name := $(shell,set -- $(cc-info) && echo $1)
+cc-version := $(shell,set -- $(cc-info) && echo $2)
+"#;
+
+const INIT_KCONFIG: &str = r#"source "scripts/Kconfig.include"
+
+config CC_IS_GCC
+	def_bool $(success,test "$(cc-name)" = GCC)
+
+config GCC_VERSION
+	int
+	default $(cc-version) if CC_IS_GCC
+	default 0
+
+config CC_IS_CLANG
+	def_bool $(success,test "$(cc-name)" = Clang)
+
+config CLANG_VERSION
+	int
+	default $(cc-version) if CC_IS_CLANG
+	default 0
+
+config CC_HAS_ASM_GOTO
+	def_bool $(success,$(srctree)/scripts/gcc-goto.sh $(CC))
+
+config CC_HAS_NO_PROFILE_FN_ATTR
+	def_bool $(cc-option,-fno-profile-instr-generate -Werror)
+
+config LD_HAS_BUILD_ID
+	def_bool $(ld-option,--build-id=sha1)
+
+config AS_HAS_TPAUSE
+	def_bool $(as-instr,tpause %ecx)
+"#;
+
+fn resolve_with(profile: ToolchainProfile) -> crate::resolve::Configuration {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/scripts/Kconfig.include", KCONFIG_INCLUDE);
+    fs.insert("/kernel/Kconfig", INIT_KCONFIG);
+    let kconfig_file = KconfigFile::new_with_vars(
+        PathBuf::from("/kernel"),
+        PathBuf::from("Kconfig"),
+        &HashMap::from([("CC", "gcc"), ("LD", "ld"), ("srctree", ".")]),
+        &HashMap::default(),
+    )
+    .with_vfs(fs)
+    .with_toolchain(profile.with_tools(["gcc"]));
+    let content = kconfig_file.read_to_string().unwrap();
+    let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file)).unwrap();
+    resolve(&kconfig, None)
+}
+
+fn tristate(value: Tristate) -> Option<SymbolValue> {
+    Some(SymbolValue::Tristate(value))
+}
+
+#[test]
+fn test_toolchain_profile_gcc() {
+    let configuration = resolve_with(
+        ToolchainProfile::gcc(130200)
+            .with_cc_flags(["-fno-profile-instr-generate", "-Werror"])
+            .with_as_instructions(["tpause %ecx"]),
+    );
+    let get = |name| configuration.get(name).cloned();
+    assert_eq!(get("CC_IS_GCC"), tristate(Tristate::Yes));
+    assert_eq!(get("GCC_VERSION"), Some(SymbolValue::Int(130200)));
+    assert_eq!(get("CC_IS_CLANG"), tristate(Tristate::No));
+    assert_eq!(get("CLANG_VERSION"), Some(SymbolValue::Int(0)));
+    assert_eq!(get("CC_HAS_ASM_GOTO"), tristate(Tristate::Yes));
+    assert_eq!(get("CC_HAS_NO_PROFILE_FN_ATTR"), tristate(Tristate::Yes));
+    assert_eq!(get("LD_HAS_BUILD_ID"), tristate(Tristate::No));
+    assert_eq!(get("AS_HAS_TPAUSE"), tristate(Tristate::Yes));
+}
+
+#[test]
+fn test_toolchain_profile_clang() {
+    let configuration = resolve_with(
+        ToolchainProfile::clang(170000)
+            .with_asm_goto(false)
+            .with_ld_flags(["--build-id=sha1"]),
+    );
+    let get = |name| configuration.get(name).cloned();
+    assert_eq!(get("CC_IS_GCC"), tristate(Tristate::No));
+    assert_eq!(get("GCC_VERSION"), Some(SymbolValue::Int(0)));
+    assert_eq!(get("CLANG_VERSION"), Some(SymbolValue::Int(170000)));
+    assert_eq!(get("CC_HAS_ASM_GOTO"), tristate(Tristate::No));
+    assert_eq!(get("CC_HAS_NO_PROFILE_FN_ATTR"), tristate(Tristate::No));
+    assert_eq!(get("LD_HAS_BUILD_ID"), tristate(Tristate::Yes));
+}
+
+#[test]
+fn test_toolchain_profile_call() {
+    let profile = ToolchainProfile::gcc(110400)
+        .with_linker("BFD", 23800)
+        .with_cc_flags(["-m64"]);
+    assert_eq!(profile.call::<&str>("cc-info", &[]).unwrap(), "GCC 110400");
+    assert_eq!(profile.call::<&str>("ld-info", &[]).unwrap(), "BFD 23800");
+    assert_eq!(profile.call("cc-option-bit", &["-m64"]).unwrap(), "-m64");
+    assert_eq!(profile.call("cc-option-bit", &["-m32"]).unwrap(), "");
+    assert_eq!(
+        profile.call("success", &["test 110400 -ge 50100"]).unwrap(),
+        "y"
+    );
+    assert_eq!(profile.call("failure", &["[ -n abc ]"]).unwrap(), "n");
+    assert_eq!(
+        profile
+            .call("if-success", &["test ! a = b", "yes", "no"])
+            .unwrap(),
+        "yes"
+    );
+    assert_eq!(profile.call("success", &["uname -m | grep x86"]), None);
+    assert_eq!(
+        profile.call("success", &["command -v pahole"]).unwrap(),
+        "n"
+    );
+    assert_eq!(
+        profile
+            .clone()
+            .with_tools(["pahole"])
+            .call("success", &["command -v pahole"])
+            .unwrap(),
+        "y"
+    );
+    assert_eq!(
+        profile.call("success", &["test \"$(cc-name)\" = GCC"]),
+        None
+    );
+    assert_eq!(profile.call("lineno", &[] as &[&str]), None);
+}
@@ -4,8 +4,10 @@ use serde::Deserialize;
 use serde::Serialize;
 #[cfg(feature = "display")]
 use std::fmt::Display;
+use std::{cmp::Ordering, ops::Not};
 
-#[derive(Debug, PartialEq, Clone)]
+/// A tristate value. Values are ordered `n < m < y`, `&&` is the minimum, `||` is the maximum and `!` is `2 - x`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "hash", derive(Hash))]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
@@ -15,6 +17,60 @@ pub enum Tristate {
     No,
 }
 
+impl Tristate {
+    fn rank(self) -> u8 {
+        match self {
+            Tristate::No => 0,
+            Tristate::Module => 1,
+            Tristate::Yes => 2,
+        }
+    }
+
+    /// Kconfig `&&`.
+    pub fn and(self, other: Tristate) -> Tristate {
+        self.min(other)
+    }
+
+    /// Kconfig `||`.
+    pub fn or(self, other: Tristate) -> Tristate {
+        self.max(other)
+    }
+}
+
+impl Not for Tristate {
+    type Output = Tristate;
+
+    /// Kconfig `!`.
+    fn not(self) -> Self::Output {
+        match self {
+            Tristate::Yes => Tristate::No,
+            Tristate::Module => Tristate::Module,
+            Tristate::No => Tristate::Yes,
+        }
+    }
+}
+
+impl PartialOrd for Tristate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tristate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl From<bool> for Tristate {
+    fn from(value: bool) -> Self {
+        match value {
+            true => Tristate::Yes,
+            false => Tristate::No,
+        }
+    }
+}
+
 #[cfg(feature = "display")]
 impl Display for Tristate {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -0,0 +1,203 @@
+//! Access to the files included by `source`, `osource`, `rsource` and `orsource`.
+//!
+//! By default, files are read from the filesystem. A [MemoryFs] parses a tree held in memory,
+//! any other storage (tarball, git objects...) can implement the [Vfs] trait.
+//!
+//! ```
+//! use std::path::PathBuf;
+//! use nom_kconfig::{kconfig::parse_kconfig, vfs::MemoryFs, Entry, KconfigFile, KconfigInput};
+//!
+//! let mut fs = MemoryFs::new();
+//! fs.insert("/linux/Kconfig", "source \"arch/Kconfig\"\n");
+//! fs.insert("/linux/arch/Kconfig", "config ARCH\n    bool\n");
+//!
+//! let kconfig_file = KconfigFile::new(PathBuf::from("/linux"), PathBuf::from("Kconfig")).with_vfs(fs);
+//! let content = kconfig_file.read_to_string().unwrap();
+//! let (_, kconfig) = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file)).unwrap();
+//! let Entry::Source(source) = &kconfig.entries[0] else { unreachable!() };
+//! assert_eq!(source.kconfigs[0].file, "arch/Kconfig");
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io,
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+};
+
+/// A source of Kconfig files.
+pub trait Vfs: Debug {
+    /// Reads the whole content of a file.
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Returns `true` if the file exists.
+    fn exists(&self, path: &Path) -> bool {
+        self.read_to_string(path).is_ok()
+    }
+
+    /// Lists the files matching a glob pattern, in alphabetical order.
+    fn glob(&self, pattern: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Returns the canonical form of a path, used to compare paths returned by [Vfs::glob] with the root directory.
+    fn canonicalize(&self, path: &Path) -> PathBuf {
+        normalize(path)
+    }
+}
+
+/// The filesystem of the operating system.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RealFs;
+
+impl Vfs for RealFs {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    #[cfg(feature = "glob-wildcard")]
+    fn glob(&self, pattern: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut paths = glob::glob(&pattern.display().to_string())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    #[cfg(not(feature = "glob-wildcard"))]
+    fn glob(&self, pattern: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(match pattern.is_file() {
+            true => vec![pattern.to_path_buf()],
+            false => vec![],
+        })
+    }
+
+    fn canonicalize(&self, path: &Path) -> PathBuf {
+        // macOS temporary directories are symbolic links (/var/folders -> /private/var/folders)
+        path.canonicalize().unwrap_or_else(|_| normalize(path))
+    }
+}
+
+/// Files held in memory, indexed by their path.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MemoryFs {
+    files: HashMap<PathBuf, String>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert<P: AsRef<Path>, S: Into<String>>(&mut self, path: P, content: S) {
+        self.files.insert(normalize(path.as_ref()), content.into());
+    }
+
+    pub fn files(&self) -> &HashMap<PathBuf, String> {
+        &self.files
+    }
+}
+
+impl From<HashMap<PathBuf, String>> for MemoryFs {
+    fn from(files: HashMap<PathBuf, String>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|(path, content)| (normalize(&path), content))
+                .collect(),
+        }
+    }
+}
+
+impl Vfs for MemoryFs {
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files.get(&normalize(path)).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} does not exist", path.display()),
+            )
+        })
+    }
+
+    #[cfg(feature = "glob-wildcard")]
+    fn glob(&self, pattern: &Path) -> io::Result<Vec<PathBuf>> {
+        let pattern = glob::Pattern::new(&normalize(pattern).display().to_string())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // like `glob::glob`, `*` does not match `/`
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        let mut paths = self
+            .files
+            .keys()
+            .filter(|path| pattern.matches_path_with(path, options))
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    #[cfg(not(feature = "glob-wildcard"))]
+    fn glob(&self, pattern: &Path) -> io::Result<Vec<PathBuf>> {
+        let pattern = normalize(pattern);
+        Ok(match self.files.contains_key(&pattern) {
+            true => vec![pattern],
+            false => vec![],
+        })
+    }
+}
+
+/// The [Vfs] used to read the files of a [KconfigFile](crate::KconfigFile), [RealFs] by default.
+/// It is not part of the identity of a [KconfigFile](crate::KconfigFile): two handles are always equal.
+#[derive(Debug, Clone)]
+pub struct VfsHandle(pub Rc<dyn Vfs>);
+
+impl Default for VfsHandle {
+    fn default() -> Self {
+        Self(Rc::new(RealFs))
+    }
+}
+
+impl PartialEq for VfsHandle {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for VfsHandle {}
+
+impl std::ops::Deref for VfsHandle {
+    type Target = dyn Vfs;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+/// Removes `.` components and resolves `..` without accessing the filesystem.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
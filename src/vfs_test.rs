@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    kconfig::parse_kconfig,
+    vfs::{normalize, MemoryFs, RealFs, Vfs},
+    Entry, Kconfig, KconfigFile, KconfigInput,
+};
+
+fn parse(fs: MemoryFs, file: &str) -> Kconfig {
+    let kconfig_file = KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from(file)).with_vfs(fs);
+    let content = kconfig_file.read_to_string().unwrap();
+    parse_kconfig(KconfigInput::new_extra(&content, kconfig_file))
+        .unwrap()
+        .1
+}
+
+fn sourced_files(entry: &Entry) -> Vec<&str> {
+    match entry {
+        Entry::Source(source) => source.kconfigs.iter().map(|k| k.file.as_str()).collect(),
+        #[cfg(feature = "kconfiglib")]
+        Entry::OSource(source) | Entry::RSource(source) | Entry::OrSource(source) => {
+            source.kconfigs.iter().map(|k| k.file.as_str()).collect()
+        }
+        _ => vec![],
+    }
+}
+
+#[test]
+fn test_memory_fs_source() {
+    let fs = MemoryFs::from(HashMap::from([
+        (
+            PathBuf::from("/kernel/Kconfig"),
+            "source \"drivers/Kconfig\"\n".to_string(),
+        ),
+        (
+            PathBuf::from("/kernel/drivers/Kconfig"),
+            "config DRIVERS\n    bool\nsource \"drivers/net/Kconfig\"\n".to_string(),
+        ),
+        (
+            PathBuf::from("/kernel/drivers/net/Kconfig"),
+            "config NET\n    bool\n".to_string(),
+        ),
+    ]));
+    let kconfig = parse(fs, "Kconfig");
+    assert_eq!(sourced_files(&kconfig.entries[0]), vec!["drivers/Kconfig"]);
+    let Entry::Source(source) = &kconfig.entries[0] else {
+        unreachable!()
+    };
+    assert_eq!(
+        sourced_files(&source.kconfigs[0].entries[1]),
+        vec!["drivers/net/Kconfig"]
+    );
+}
+
+#[test]
+fn test_memory_fs_missing_file() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Kconfig", "source \"missing/Kconfig\"\n");
+    let kconfig_file =
+        KconfigFile::new(PathBuf::from("/kernel"), PathBuf::from("Kconfig")).with_vfs(fs);
+    let content = kconfig_file.read_to_string().unwrap();
+    let result = parse_kconfig(KconfigInput::new_extra(&content, kconfig_file));
+    #[cfg(feature = "kconfiglib")]
+    assert!(result.unwrap().1.entries.len() == 1);
+    #[cfg(not(feature = "kconfiglib"))]
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "kconfiglib")]
+#[test]
+fn test_memory_fs_relative_and_glob_sources() {
+    let mut fs = MemoryFs::new();
+    fs.insert(
+        "/kernel/arch/Kconfig",
+        "rsource \"boards/*.Kconfig\"\norsource \"missing.Kconfig\"\nsource \"arch/../lib/Kconfig\"\n",
+    );
+    fs.insert("/kernel/arch/boards/b.Kconfig", "config B\n    bool\n");
+    fs.insert("/kernel/arch/boards/a.Kconfig", "config A\n    bool\n");
+    fs.insert(
+        "/kernel/arch/boards/nested/c.Kconfig",
+        "config C\n    bool\n",
+    );
+    fs.insert("/kernel/lib/Kconfig", "config LIB\n    bool\n");
+    let kconfig = parse(fs, "arch/Kconfig");
+    assert_eq!(
+        sourced_files(&kconfig.entries[0]),
+        vec!["arch/boards/a.Kconfig", "arch/boards/b.Kconfig"]
+    );
+    assert!(matches!(&kconfig.entries[1], Entry::OrSource(s) if s.kconfigs[0].entries.is_empty()));
+    let Entry::Source(lib) = &kconfig.entries[2] else {
+        unreachable!()
+    };
+    assert_eq!(lib.kconfigs[0].entries.len(), 1);
+}
+
+#[cfg(feature = "glob-wildcard")]
+#[test]
+fn test_memory_fs_glob_does_not_cross_directories() {
+    let mut fs = MemoryFs::new();
+    fs.insert("/kernel/Kconfig", "source \"arch/*/Kconfig\"\n");
+    fs.insert("/kernel/arch/x86/Kconfig", "config X86\n    bool\n");
+    fs.insert("/kernel/arch/x86/boot/Kconfig", "config BOOT\n    bool\n");
+    assert_eq!(
+        fs.glob(Path::new("/kernel/arch/*/Kconfig")).unwrap(),
+        vec![PathBuf::from("/kernel/arch/x86/Kconfig")]
+    );
+    let kconfig = parse(fs, "Kconfig");
+    assert_eq!(sourced_files(&kconfig.entries[0]), vec!["arch/x86/Kconfig"]);
+}
+
+#[test]
+fn test_real_fs() {
+    let root = std::env::temp_dir().join(format!("nom-kconfig-vfs-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("Kconfig"), "config A\n    bool\n").unwrap();
+    let content = RealFs.read_to_string(&root.join("Kconfig"));
+    let missing = RealFs.read_to_string(&root.join("missing"));
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(content.unwrap(), "config A\n    bool\n");
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(
+        normalize(Path::new("/kernel/./arch/../lib/Kconfig")),
+        PathBuf::from("/kernel/lib/Kconfig")
+    );
+    assert_eq!(normalize(Path::new("../a/./b")), PathBuf::from("../a/b"));
+}
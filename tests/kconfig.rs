@@ -33,7 +33,8 @@ fn test_parse() {
             attributes: vec!(
                 Attribute::Type( ConfigType {
                     r#type: Type::Tristate(Some("Kernel-based Virtual Machine (KVM) support".to_string())),
-                    r#if: None
+                    r#if: None,
+                    span: Default::default(),
                 }),
                 Attribute::DependsOn(DependsOn {
                     expression: Expression::Term(AndExpression::Expression(vec!(
@@ -42,13 +43,15 @@ fn test_parse() {
                         Term::Atom(Atom::Symbol(Symbol::NonConstant("X86_LOCAL_APIC".to_string())))
                     ))),
                     r#if: None,
+                    span: Default::default(),
                 }),
-                Attribute::Select(Select { symbol: "PREEMPT_NOTIFIERS".to_string(), r#if: None }),
-                Attribute::Select(Select { symbol: "MMU_NOTIFIER".to_string(), r#if: None }),
-                Attribute::Select(Select { symbol: "HAVE_KVM_IRQCHIP".to_string(), r#if: None }),
-                Attribute::Select(Select { symbol: "HAVE_KVM_PFNCACHE".to_string(), r#if: None }),
-                Attribute::Help("Support hosting fully virtualized guest machines using hardware\nvirtualization extensions.  You will need a fairly recent\nprocessor equipped with virtualization extensions. You will also\nneed to select one or more of the processor modules below.".to_string()),
-            )
+                Attribute::Select(Select { symbol: "PREEMPT_NOTIFIERS".to_string(), r#if: None, span: Default::default() }),
+                Attribute::Select(Select { symbol: "MMU_NOTIFIER".to_string(), r#if: None, span: Default::default() }),
+                Attribute::Select(Select { symbol: "HAVE_KVM_IRQCHIP".to_string(), r#if: None, span: Default::default() }),
+                Attribute::Select(Select { symbol: "HAVE_KVM_PFNCACHE".to_string(), r#if: None, span: Default::default() }),
+                Attribute::Help("Support hosting fully virtualized guest machines using hardware\nvirtualization extensions.  You will need a fairly recent\nprocessor equipped with virtualization extensions. You will also\nneed to select one or more of the processor modules below.".to_string(), Default::default()),
+            ),
+            span: Default::default(),
         }))})))
 }
 
@@ -0,0 +1,163 @@
+//! Talks to `nom-kconfig-lsp` over stdio, like an editor does.
+#![cfg(feature = "lsp")]
+
+use std::{
+    io::BufReader,
+    process::{ChildStdout, Command, Stdio},
+};
+
+use lsp_server::{Message, Notification, Request, RequestId};
+use lsp_types::Url;
+use serde_json::{json, Value};
+
+fn receive(stdout: &mut BufReader<ChildStdout>, method: Option<&str>) -> Message {
+    loop {
+        let message = Message::read(stdout).unwrap().unwrap();
+        match (&message, method) {
+            (Message::Notification(n), Some(method)) if n.method == method => return message,
+            (Message::Response(_), None) => return message,
+            _ => (),
+        }
+    }
+}
+
+fn result(message: Message) -> Value {
+    let Message::Response(response) = message else {
+        unreachable!()
+    };
+    assert!(response.error.is_none(), "{:?}", response.error);
+    response.result.unwrap_or_default()
+}
+
+#[test]
+fn test_lsp_stdio() {
+    let root = std::env::temp_dir().join(format!("nom-kconfig-lsp-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("arch/x86")).unwrap();
+    std::fs::write(
+        root.join("Kconfig"),
+        "config NET\n\tbool \"Networking\"\n\tselect X86_CRC\n\nsource \"arch/$(SRCARCH)/Kconfig\"\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("arch/x86/Kconfig"), "config X86_CRC\n\tbool\n").unwrap();
+    let uri = Url::from_file_path(root.join("Kconfig")).unwrap();
+
+    let mut server = Command::new(env!("CARGO_BIN_EXE_nom-kconfig-lsp"))
+        .args(["--srcarch", "x86"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = server.stdin.take().unwrap();
+    let mut stdout = BufReader::new(server.stdout.take().unwrap());
+    let mut send = |message: Message| message.write(&mut stdin).unwrap();
+
+    send(
+        Request::new(
+            RequestId::from(1),
+            "initialize".to_string(),
+            json!({"capabilities": {}, "rootUri": Url::from_file_path(&root).unwrap()}),
+        )
+        .into(),
+    );
+    let capabilities = result(receive(&mut stdout, None));
+    assert_eq!(capabilities["capabilities"]["definitionProvider"], true);
+    send(Notification::new("initialized".to_string(), json!({})).into());
+
+    // the root file is analyzed right away
+    let Message::Notification(diagnostics) =
+        receive(&mut stdout, Some("textDocument/publishDiagnostics"))
+    else {
+        unreachable!()
+    };
+    assert_eq!(diagnostics.params["uri"], uri.as_str());
+    assert_eq!(
+        diagnostics.params["diagnostics"][0]["message"],
+        "`NET` has a prompt but no help text"
+    );
+
+    // the opened document replaces the saved file
+    let text = "config NET\n\tbool \"Networking\"\n\tselect X86_CRC\n\thelp\n\t  Say Y.\n\nsource \"arch/$(SRCARCH)/Kconfig\"\n";
+    let document = json!({"uri": uri, "languageId": "kconfig", "version": 1, "text": text});
+    send(
+        Notification::new(
+            "textDocument/didOpen".to_string(),
+            json!({ "textDocument": document }),
+        )
+        .into(),
+    );
+    let Message::Notification(diagnostics) =
+        receive(&mut stdout, Some("textDocument/publishDiagnostics"))
+    else {
+        unreachable!()
+    };
+    assert_eq!(diagnostics.params["diagnostics"], json!([]));
+
+    send(
+        Request::new(
+            RequestId::from(2),
+            "textDocument/definition".to_string(),
+            json!({"textDocument": {"uri": uri}, "position": {"line": 2, "character": 10}}),
+        )
+        .into(),
+    );
+    let definition = result(receive(&mut stdout, None));
+    assert_eq!(
+        definition,
+        json!([{
+            "uri": Url::from_file_path(root.join("arch/x86/Kconfig")).unwrap(),
+            "range": {"start": {"line": 0, "character": 7}, "end": {"line": 0, "character": 14}}
+        }])
+    );
+
+    send(
+        Request::new(
+            RequestId::from(3),
+            "textDocument/hover".to_string(),
+            json!({"textDocument": {"uri": uri}, "position": {"line": 0, "character": 8}}),
+        )
+        .into(),
+    );
+    let hover = result(receive(&mut stdout, None));
+    assert!(hover["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .contains("Say Y."));
+
+    // a request right after a change is answered with the changed document
+    let text = "config NET\n\tbool \"Networking\"\n\tselect X86_CRC\n\nsource \"arch/$(SRCARCH)/Kconfig\"\n";
+    send(
+        Notification::new(
+            "textDocument/didChange".to_string(),
+            json!({"textDocument": {"uri": uri, "version": 2}, "contentChanges": [{"text": text}]}),
+        )
+        .into(),
+    );
+    send(
+        Request::new(
+            RequestId::from(4),
+            "textDocument/hover".to_string(),
+            json!({"textDocument": {"uri": uri}, "position": {"line": 0, "character": 8}}),
+        )
+        .into(),
+    );
+    let Message::Notification(diagnostics) =
+        receive(&mut stdout, Some("textDocument/publishDiagnostics"))
+    else {
+        unreachable!()
+    };
+    assert_eq!(
+        diagnostics.params["diagnostics"][0]["message"],
+        "`NET` has a prompt but no help text"
+    );
+    let hover = result(receive(&mut stdout, None));
+    assert!(!hover["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .contains("Say Y."));
+
+    send(Request::new(RequestId::from(5), "shutdown".to_string(), Value::Null).into());
+    result(receive(&mut stdout, None));
+    send(Notification::new("exit".to_string(), Value::Null).into());
+    assert!(server.wait().unwrap().success());
+    std::fs::remove_dir_all(root).unwrap();
+}